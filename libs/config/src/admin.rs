@@ -0,0 +1,14 @@
+use std::env;
+
+use super::Config;
+
+impl Config {
+    /// Token required in the `Authorization: Bearer` header of admin endpoints.
+    ///
+    /// Admin endpoints are disabled when unset.
+    pub fn admin_token() -> Option<String> {
+        env::var("ADMIN_TOKEN")
+            .ok()
+            .filter(|token| !token.is_empty())
+    }
+}
@@ -0,0 +1,14 @@
+use std::{env, time::Duration};
+
+use super::Config;
+
+impl Config {
+    /// How long the total counts of paginated lists are reused, disabled when unset or `0`.
+    pub fn count_cache_ttl() -> Option<Duration> {
+        env::var("COUNT_CACHE_TTL_MS")
+            .ok()
+            .and_then(|ttl| ttl.parse::<u64>().ok())
+            .filter(|ttl| *ttl > 0)
+            .map(Duration::from_millis)
+    }
+}
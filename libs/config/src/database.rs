@@ -0,0 +1,74 @@
+use std::env;
+
+use super::{Config, ConfigError};
+
+const DEFAULT_MIN_CONNECTIONS: u32 = 0;
+const DEFAULT_MAX_CONNECTIONS: u32 = 10;
+const DEFAULT_SLOW_QUERY_MS: u64 = 500;
+
+impl Config {
+    /// Minimum and maximum number of connections kept by the database pool.
+    ///
+    /// Fails if `DATABASE_MIN_CONNECTIONS` is greater than `DATABASE_MAX_CONNECTIONS`.
+    pub fn database_connections() -> Result<(u32, u32), ConfigError> {
+        let min_connections = env::var("DATABASE_MIN_CONNECTIONS")
+            .ok()
+            .and_then(|min| min.parse().ok())
+            .unwrap_or(DEFAULT_MIN_CONNECTIONS);
+
+        let max_connections = env::var("DATABASE_MAX_CONNECTIONS")
+            .ok()
+            .and_then(|max| max.parse().ok())
+            .unwrap_or(DEFAULT_MAX_CONNECTIONS);
+
+        if min_connections > max_connections {
+            return Err(ConfigError::DatabaseConnections {
+                min: min_connections,
+                max: max_connections,
+            });
+        }
+
+        Ok((min_connections, max_connections))
+    }
+
+    /// Maximum time, in milliseconds, a database statement may run before being aborted.
+    ///
+    /// Statements are not limited when unset.
+    pub fn database_statement_timeout_ms() -> Option<u64> {
+        env::var("DATABASE_STATEMENT_TIMEOUT_MS")
+            .ok()
+            .and_then(|ms| ms.parse().ok())
+    }
+
+    /// Time, in milliseconds, after which a database statement is logged as slow.
+    pub fn slow_query_ms() -> u64 {
+        env::var("SLOW_QUERY_MS")
+            .ok()
+            .and_then(|ms| ms.parse().ok())
+            .unwrap_or(DEFAULT_SLOW_QUERY_MS)
+    }
+
+    /// Whether the indexes the queries rely on are checked at startup, warning about missing ones.
+    pub fn check_indexes() -> bool {
+        env::var("CHECK_INDEXES").is_ok_and(|value| value == "true")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Config, ConfigError};
+    use std::env;
+
+    #[test]
+    fn more_minimum_than_maximum_connections_is_an_error() {
+        env::set_var("DATABASE_MIN_CONNECTIONS", "5");
+        env::set_var("DATABASE_MAX_CONNECTIONS", "2");
+        assert!(matches!(
+            Config::database_connections(),
+            Err(ConfigError::DatabaseConnections { min: 5, max: 2 })
+        ));
+
+        env::set_var("DATABASE_MAX_CONNECTIONS", "5");
+        assert_eq!(Config::database_connections().unwrap(), (5, 5));
+    }
+}
@@ -0,0 +1,13 @@
+use std::env;
+
+use super::Config;
+
+impl Config {
+    /// Whether internal errors include their cause in the response body.
+    ///
+    /// Ignored in production, where `500` responses always stay opaque.
+    pub fn expose_errors() -> bool {
+        let production = env::var("ENVIRONMENT").is_ok_and(|value| value == "production");
+        !production && env::var("EXPOSE_ERRORS").is_ok_and(|value| value == "true")
+    }
+}
@@ -0,0 +1,14 @@
+use std::{env, time::Duration};
+
+use super::Config;
+
+impl Config {
+    /// How long identical `POST` requests are collapsed into the first one, disabled when unset or `0`.
+    pub fn dedup_window() -> Option<Duration> {
+        env::var("DEDUP_WINDOW_MS")
+            .ok()
+            .and_then(|window| window.parse::<u64>().ok())
+            .filter(|window| *window > 0)
+            .map(Duration::from_millis)
+    }
+}
@@ -0,0 +1,21 @@
+use std::env;
+
+use super::Config;
+
+impl Config {
+    /// Minimum free space, in megabytes, required in the log directory.
+    ///
+    /// The disk check is skipped when unset.
+    pub fn min_free_disk_mb() -> Option<u64> {
+        env::var("MIN_FREE_DISK_MB")
+            .ok()
+            .and_then(|mb| mb.parse().ok())
+    }
+
+    /// Directory purged movements are archived to once their deletion is committed.
+    ///
+    /// Purged movements are not archived when unset.
+    pub fn archive_dir() -> Option<String> {
+        env::var("ARCHIVE_DIR").ok().filter(|dir| !dir.is_empty())
+    }
+}
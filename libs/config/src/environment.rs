@@ -1,29 +1,33 @@
 use std::{env, path::Path};
 use tracing::{error, info};
 
-pub fn load_environment() {
+/// Loads the `.env` file and the file of the selected environment.
+///
+/// Returns the name of the selected environment.
+pub fn load_environment() -> String {
     dotenvy::dotenv().expect("Error loading .env");
 
     let environment = env::var("ENVIRONMENT").unwrap_or_else(|_| String::from("development"));
 
     match environment.as_str() {
-        "development" => {
-            info!("Running in Development mode");
-            dotenvy::from_path(Path::new("environments/.env.development"))
-                .expect("Error loading .env.development")
-        }
-        "production" => {
-            info!("Running in Production mode");
-            dotenvy::from_path(Path::new("environments/.env.production"))
-                .expect("Error loading .env.production")
-        }
-        "test" => {
-            info!("Running in Test mode");
-            dotenvy::from_path(Path::new("environments/.env.test"))
-                .expect("Error loading .env.tests")
-        }
-        _ => {
-            error!("Unknown environment: {}", environment);
-        }
+        "development" => dotenvy::from_path(Path::new("environments/.env.development"))
+            .expect("Error loading .env.development"),
+        "production" => dotenvy::from_path(Path::new("environments/.env.production"))
+            .expect("Error loading .env.production"),
+        "test" => dotenvy::from_path(Path::new("environments/.env.test"))
+            .expect("Error loading .env.tests"),
+        _ => {}
+    }
+
+    environment
+}
+
+/// Logs the environment selected by `load_environment`.
+pub fn log_environment(environment: &str) {
+    match environment {
+        "development" => info!("Running in Development mode"),
+        "production" => info!("Running in Production mode"),
+        "test" => info!("Running in Test mode"),
+        _ => error!("Unknown environment: {}", environment),
     }
 }
@@ -0,0 +1,11 @@
+use thiserror::Error;
+
+/// Reasons the configuration can't be loaded.
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("The environment could not be loaded: {0}")]
+    Environment(#[from] dotenvy::Error),
+
+    #[error("DATABASE_MIN_CONNECTIONS ({min}) must not be greater than DATABASE_MAX_CONNECTIONS ({max})")]
+    DatabaseConnections { min: u32, max: u32 },
+}
@@ -0,0 +1,20 @@
+use std::env;
+
+use super::Config;
+
+impl Config {
+    /// Whether request bodies with unknown fields are rejected instead of ignored.
+    pub fn strict_json() -> bool {
+        env::var("STRICT_JSON").is_ok_and(|value| value == "true")
+    }
+
+    /// Whether JSON responses are always indented, as with `?pretty=true`.
+    pub fn pretty_json() -> bool {
+        env::var("PRETTY_JSON").is_ok_and(|value| value == "true")
+    }
+
+    /// Whether JSON response fields are renamed to camelCase, selected with `JSON_CASE=camel`.
+    pub fn camel_case_json() -> bool {
+        env::var("JSON_CASE").is_ok_and(|value| value.trim().eq_ignore_ascii_case("camel"))
+    }
+}
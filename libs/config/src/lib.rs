@@ -1,13 +1,37 @@
+mod admin;
+mod cache;
 mod cors;
+mod database;
+mod debug;
+mod dedup;
+mod disk;
 mod environment;
+mod error;
+mod json;
 mod logger;
+mod model;
+mod movement;
+mod naming;
+mod price;
+mod rate_limit;
+mod routing;
+mod uniqueness;
+mod webhook;
+
+pub use error::ConfigError;
 
 pub struct Config {}
 
 impl Config {
-    pub fn init() -> Result<(), dotenvy::Error> {
-        Self::logger_init();
-        environment::load_environment();
+    pub fn init() -> Result<(), ConfigError> {
+        // The environment is loaded first so the log levels it sets are honored
+        let environment = environment::load_environment();
+        // A subscriber set earlier, as in tests, keeps receiving the logs
+        if let Err(e) = Self::logger_init() {
+            tracing::warn!("Logger not initialized: {e}");
+        }
+        environment::log_environment(&environment);
+        Self::database_connections()?;
         Ok(())
     }
 }
@@ -1,4 +1,6 @@
 use chrono::{DateTime, FixedOffset, Utc};
+use std::env;
+use tracing::{subscriber::SetGlobalDefaultError, Subscriber};
 use tracing_appender::rolling;
 use tracing_subscriber::{
     fmt::{self, format::Writer, time::FormatTime},
@@ -8,44 +10,141 @@ use tracing_subscriber::{
 
 use super::Config;
 
+const LOG_DIRECTORY: &str = "logs";
+const DEFAULT_LOG_FILE_LEVEL: &str = "trace";
+const DEFAULT_LOG_CONSOLE_LEVEL: &str = "info";
+
+struct UtcFormattedTime;
+
+impl FormatTime for UtcFormattedTime {
+    fn format_time(&self, writer: &mut Writer<'_>) -> std::fmt::Result {
+        let brasilia_offset = FixedOffset::west_opt(3 * 3600).unwrap();
+        let now: DateTime<FixedOffset> = Utc::now().with_timezone(&brasilia_offset);
+        let formatted_time = now.format("%d/%m/%Y %H:%M:%S").to_string();
+        write!(writer, "{}", formatted_time)
+    }
+}
+
 impl Config {
-    pub fn logger_init() {
-        struct UtcFormattedTime;
-
-        impl FormatTime for UtcFormattedTime {
-            fn format_time(&self, writer: &mut Writer<'_>) -> std::fmt::Result {
-                let brasilia_offset = FixedOffset::west_opt(3 * 3600).unwrap();
-                let now: DateTime<FixedOffset> = Utc::now().with_timezone(&brasilia_offset);
-                let formatted_time = now.format("%d/%m/%Y %H:%M:%S").to_string();
-                write!(writer, "{}", formatted_time)
-            }
-        }
-
-        let rust_log_file = EnvFilter::from_env("RUST_LOG_FILE");
-        let rust_log_console = EnvFilter::from_env("RUST_LOG_CONSOLE");
-
-        let file_appender = rolling::daily("logs", "api.log");
-
-        let file_layer = fmt::Layer::new()
-            .with_timer(UtcFormattedTime)
-            .with_writer(file_appender)
-            .with_file(true)
-            .with_ansi(false)
-            .with_line_number(true)
-            .with_target(false)
-            .with_filter(rust_log_file);
-
-        let console_layer = fmt::Layer::new()
-            .pretty()
-            .with_timer(UtcFormattedTime)
-            .with_file(false)
-            .with_ansi(true)
-            .with_line_number(false)
-            .with_target(false)
-            .with_filter(rust_log_console);
-
-        let subscriber = Registry::default().with(console_layer).with(file_layer);
-
-        tracing::subscriber::set_global_default(subscriber).expect("Failed to set subscriber");
+    /// Directory the rolling log files are written to.
+    pub fn log_directory() -> &'static str {
+        LOG_DIRECTORY
+    }
+
+    /// Builds a log filter from the directives in `var`, such as `warn` or
+    /// `printer_supplies_api=debug`, falling back to `default` when unset or invalid.
+    pub fn log_filter(var: &str, default: &str) -> EnvFilter {
+        EnvFilter::try_from_env(var).unwrap_or_else(|_| EnvFilter::new(default))
+    }
+
+    /// Whether the log output configured by `var` is enabled.
+    ///
+    /// An output set to `off` or left empty is not registered at all; an unset one uses its default level.
+    pub fn log_enabled(var: &str) -> bool {
+        env::var(var).map_or(true, |value| {
+            let value = value.trim();
+            !value.is_empty() && !value.eq_ignore_ascii_case("off")
+        })
+    }
+
+    /// Builds the subscriber holding the console and file loggers that are enabled.
+    pub fn log_subscriber() -> impl Subscriber + Send + Sync {
+        // The log directory is only created when the file output is enabled
+        let file_layer = Self::log_enabled("RUST_LOG_FILE").then(|| {
+            let rust_log_file = Self::log_filter("RUST_LOG_FILE", DEFAULT_LOG_FILE_LEVEL);
+            let file_appender = rolling::daily(LOG_DIRECTORY, "api.log");
+
+            fmt::Layer::new()
+                .with_timer(UtcFormattedTime)
+                .with_writer(file_appender)
+                .with_file(true)
+                .with_ansi(false)
+                .with_line_number(true)
+                .with_target(false)
+                .with_filter(rust_log_file)
+        });
+
+        let console_layer = Self::log_enabled("RUST_LOG_CONSOLE").then(|| {
+            let rust_log_console = Self::log_filter("RUST_LOG_CONSOLE", DEFAULT_LOG_CONSOLE_LEVEL);
+
+            fmt::Layer::new()
+                .pretty()
+                .with_timer(UtcFormattedTime)
+                .with_file(false)
+                .with_ansi(true)
+                .with_line_number(false)
+                .with_target(false)
+                .with_filter(rust_log_console)
+        });
+
+        Registry::default().with(console_layer).with(file_layer)
+    }
+
+    /// Installs the console and file loggers as the global subscriber.
+    ///
+    /// Fails without side effects when a global subscriber is already set, such as on a second call.
+    pub fn logger_init() -> Result<(), SetGlobalDefaultError> {
+        tracing::subscriber::set_global_default(Self::log_subscriber())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::UtcFormattedTime;
+    use crate::Config;
+    use std::env;
+    use tracing::Subscriber;
+    use tracing_subscriber::fmt::format::{Format, Full, Pretty};
+
+    #[test]
+    fn filters_are_read_from_the_variable_or_fall_back_to_the_default() {
+        env::set_var("TEST_LOG_FILTER", "warn,printer_supplies_api=debug");
+        assert_eq!(
+            Config::log_filter("TEST_LOG_FILTER", "info").to_string(),
+            "printer_supplies_api=debug,warn"
+        );
+
+        env::set_var("TEST_LOG_FILTER", "[not a directive");
+        assert_eq!(
+            Config::log_filter("TEST_LOG_FILTER", "info").to_string(),
+            "info"
+        );
+
+        assert_eq!(
+            Config::log_filter("TEST_LOG_FILTER_UNSET", "trace").to_string(),
+            "trace"
+        );
+    }
+
+    #[test]
+    fn outputs_set_to_off_or_empty_are_disabled() {
+        env::set_var("TEST_LOG_OFF", "OFF");
+        env::set_var("TEST_LOG_EMPTY", " ");
+        env::set_var("TEST_LOG_WARN", "warn");
+
+        assert!(!Config::log_enabled("TEST_LOG_OFF"));
+        assert!(!Config::log_enabled("TEST_LOG_EMPTY"));
+        assert!(Config::log_enabled("TEST_LOG_WARN"));
+        assert!(Config::log_enabled("TEST_LOG_UNSET"));
+    }
+
+    #[test]
+    fn initializing_the_logger_again_fails_without_panicking() {
+        assert!(Config::logger_init().is_ok());
+        assert!(Config::logger_init().is_err());
+    }
+
+    #[test]
+    fn disabled_outputs_are_left_out_of_the_subscriber() {
+        env::set_var("RUST_LOG_CONSOLE", "off");
+        env::set_var("RUST_LOG_FILE", "warn");
+        let subscriber: &dyn Subscriber = &Config::log_subscriber();
+
+        assert!(subscriber
+            .downcast_ref::<Format<Pretty, UtcFormattedTime>>()
+            .is_none());
+        assert!(subscriber
+            .downcast_ref::<Format<Full, UtcFormattedTime>>()
+            .is_some());
     }
 }
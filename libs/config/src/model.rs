@@ -0,0 +1,10 @@
+use std::env;
+
+use super::Config;
+
+impl Config {
+    /// Whether printer models are stored in uppercase, so "LaserJet p1102" and "LASERJET P1102" match.
+    pub fn uppercase_printer_models() -> bool {
+        env::var("UPPERCASE_PRINTER_MODELS").is_ok_and(|value| value == "true")
+    }
+}
@@ -0,0 +1,24 @@
+use std::env;
+
+use super::Config;
+
+const DEFAULT_MIN_MOVEMENT_QUANTITY: i64 = -1_000_000;
+const DEFAULT_MAX_MOVEMENT_QUANTITY: i64 = 1_000_000;
+
+impl Config {
+    /// Smallest quantity accepted for a single movement.
+    pub fn min_movement_quantity() -> i64 {
+        env::var("MIN_MOVEMENT_QUANTITY")
+            .ok()
+            .and_then(|quantity| quantity.parse().ok())
+            .unwrap_or(DEFAULT_MIN_MOVEMENT_QUANTITY)
+    }
+
+    /// Largest quantity accepted for a single movement.
+    pub fn max_movement_quantity() -> i64 {
+        env::var("MAX_MOVEMENT_QUANTITY")
+            .ok()
+            .and_then(|quantity| quantity.parse().ok())
+            .unwrap_or(DEFAULT_MAX_MOVEMENT_QUANTITY)
+    }
+}
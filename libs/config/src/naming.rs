@@ -0,0 +1,12 @@
+use std::env;
+
+use super::Config;
+
+impl Config {
+    /// Regular expression brand names must fully match, or `None` to use the built-in character set.
+    pub fn brand_name_pattern() -> Option<String> {
+        env::var("BRAND_NAME_PATTERN")
+            .ok()
+            .filter(|pattern| !pattern.trim().is_empty())
+    }
+}
@@ -0,0 +1,29 @@
+use std::env;
+
+use super::Config;
+
+const DEFAULT_PRICE_SCALE: u32 = 2;
+/// Scale of the `DECIMAL(6, 2)` price columns of toners and drums.
+const MAX_PRICE_SCALE: u32 = 2;
+const FALLBACK_CURRENCY: &str = "USD";
+
+impl Config {
+    /// Number of decimal places prices are rounded to before being stored,
+    /// at most the two places the price columns hold.
+    pub fn price_scale() -> u32 {
+        env::var("PRICE_SCALE")
+            .ok()
+            .and_then(|scale| scale.parse::<u32>().ok())
+            .unwrap_or(DEFAULT_PRICE_SCALE)
+            .min(MAX_PRICE_SCALE)
+    }
+
+    /// ISO 4217 code of the prices of toners and drums stored without a currency.
+    pub fn default_currency() -> String {
+        env::var("DEFAULT_CURRENCY")
+            .ok()
+            .map(|currency| currency.trim().to_uppercase())
+            .filter(|currency| !currency.is_empty())
+            .unwrap_or_else(|| String::from(FALLBACK_CURRENCY))
+    }
+}
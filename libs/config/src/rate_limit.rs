@@ -0,0 +1,13 @@
+use std::env;
+
+use super::Config;
+
+impl Config {
+    /// Most consumption movements accepted per item and minute, unlimited when unset or `0`.
+    pub fn max_consumption_per_minute() -> Option<usize> {
+        env::var("MAX_CONSUMPTION_PER_MINUTE")
+            .ok()
+            .and_then(|limit| limit.parse().ok())
+            .filter(|limit| *limit > 0)
+    }
+}
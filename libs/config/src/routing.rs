@@ -0,0 +1,43 @@
+use std::env;
+
+use super::Config;
+
+/// Prefix the API is served under when `API_PREFIX` is not set.
+const DEFAULT_API_PREFIX: &str = "/api/v1";
+
+impl Config {
+    /// Whether trailing slashes are trimmed from request paths before routing.
+    pub fn normalize_trailing_slash() -> bool {
+        env::var("NORMALIZE_TRAILING_SLASH").map_or(true, |value| value != "false")
+    }
+
+    /// Whether write requests are rejected, such as during maintenance.
+    pub fn read_only() -> bool {
+        env::var("READ_ONLY").is_ok_and(|value| value == "true")
+    }
+
+    /// Public URL the API is reached at, such as `https://example.com`, without the API prefix.
+    ///
+    /// Only needed when the API runs behind a proxy that serves it under a different origin.
+    pub fn public_base_url() -> Option<String> {
+        env::var("PUBLIC_BASE_URL")
+            .ok()
+            .map(|url| String::from(url.trim().trim_end_matches('/')))
+            .filter(|url| !url.is_empty())
+    }
+
+    /// Base path all API routes are nested under, such as `/api/v1`.
+    ///
+    /// A leading slash is added and trailing slashes are removed, so `api/v2/` becomes `/api/v2`.
+    /// An empty value serves the API at the root.
+    pub fn api_prefix() -> String {
+        let prefix = env::var("API_PREFIX").unwrap_or_else(|_| String::from(DEFAULT_API_PREFIX));
+        let prefix = prefix.trim().trim_matches('/');
+
+        if prefix.is_empty() {
+            String::new()
+        } else {
+            format!("/{prefix}")
+        }
+    }
+}
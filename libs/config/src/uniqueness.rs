@@ -0,0 +1,13 @@
+use std::env;
+
+use super::Config;
+
+impl Config {
+    /// Whether toner and drum names only need to be unique within their brand, instead of globally.
+    ///
+    /// The database only enforces uniqueness within a brand. Global uniqueness is checked before
+    /// each write, so two concurrent writes of one name under different brands can both succeed.
+    pub fn unique_names_per_brand() -> bool {
+        env::var("UNIQUE_NAMES_PER_BRAND").is_ok_and(|value| value == "true")
+    }
+}
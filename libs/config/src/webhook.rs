@@ -0,0 +1,10 @@
+use std::env;
+
+use super::Config;
+
+impl Config {
+    /// URL notified whenever a movement changes an item's stock, if configured.
+    pub fn webhook_url() -> Option<String> {
+        env::var("WEBHOOK_URL").ok().filter(|url| !url.is_empty())
+    }
+}
@@ -1,9 +1,147 @@
-use sqlx::PgPool;
-use std::env;
+use log::LevelFilter;
+use sqlx::{
+    postgres::{PgConnectOptions, PgPoolOptions},
+    ConnectOptions, Executor, PgPool,
+};
+use std::{env, str::FromStr, time::Duration};
 
-pub async fn create_pool() -> Result<PgPool, sqlx::Error> {
+/// Settings used to build the database pool.
+pub struct PoolSettings {
+    pub min_connections: u32,
+    pub max_connections: u32,
+    /// Maximum time a statement may run before being aborted, if limited.
+    pub statement_timeout_ms: Option<u64>,
+    /// Statements running longer than this are logged as warnings.
+    pub slow_query_ms: u64,
+}
+
+pub async fn create_pool(settings: PoolSettings) -> Result<PgPool, sqlx::Error> {
     let database_url = env::var("DATABASE_URL").expect("Failed to load DATABASE_URL");
 
-    let pool = PgPool::connect(&database_url).await?;
+    let connect_options = PgConnectOptions::from_str(&database_url)?.log_slow_statements(
+        LevelFilter::Warn,
+        Duration::from_millis(settings.slow_query_ms),
+    );
+
+    let statement_timeout_ms = settings.statement_timeout_ms;
+
+    let pool = PgPoolOptions::new()
+        .min_connections(settings.min_connections)
+        .max_connections(settings.max_connections)
+        .after_connect(move |conn, _meta| {
+            Box::pin(async move {
+                if let Some(timeout) = statement_timeout_ms {
+                    conn.execute(format!("SET statement_timeout = {timeout};").as_str())
+                        .await?;
+                }
+                Ok(())
+            })
+        })
+        .connect_with(connect_options)
+        .await?;
     Ok(pool)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{create_pool, PoolSettings};
+    use log::{LevelFilter, Log, Metadata, Record};
+    use sqlx::Executor;
+    use std::{sync::Mutex, time::Duration};
+
+    /// Keeps the warnings sqlx logs about queries, as no tracing subscriber is installed.
+    struct QueryWarnings(Mutex<Vec<String>>);
+
+    impl Log for QueryWarnings {
+        fn enabled(&self, metadata: &Metadata) -> bool {
+            metadata.target() == "sqlx::query"
+        }
+
+        fn log(&self, record: &Record) {
+            if self.enabled(record.metadata()) && record.level() == log::Level::Warn {
+                self.0.lock().unwrap().push(record.args().to_string());
+            }
+        }
+
+        fn flush(&self) {}
+    }
+
+    static QUERY_WARNINGS: QueryWarnings = QueryWarnings(Mutex::new(Vec::new()));
+
+    fn settings() -> PoolSettings {
+        PoolSettings {
+            min_connections: 0,
+            max_connections: 2,
+            statement_timeout_ms: None,
+            slow_query_ms: 500,
+        }
+    }
+
+    #[tokio::test]
+    async fn the_pool_opens_the_minimum_connections() {
+        let pool = create_pool(PoolSettings {
+            min_connections: 3,
+            max_connections: 5,
+            ..settings()
+        })
+        .await
+        .unwrap();
+
+        // The minimum connections are opened in the background after startup
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while pool.size() < 3 {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+            }
+        })
+        .await
+        .expect("the pool did not open its minimum connections");
+    }
+
+    #[tokio::test]
+    async fn statements_past_the_timeout_are_aborted() {
+        let pool = create_pool(PoolSettings {
+            statement_timeout_ms: Some(100),
+            ..settings()
+        })
+        .await
+        .unwrap();
+
+        let error =
+            tokio::time::timeout(Duration::from_secs(5), pool.execute("SELECT pg_sleep(2);"))
+                .await
+                .expect("the statement was not aborted")
+                .unwrap_err();
+
+        // 57014 is Postgres' `query_canceled`, raised by `statement_timeout`
+        let code = error.as_database_error().and_then(|e| e.code());
+        assert_eq!(code.as_deref(), Some("57014"));
+    }
+
+    #[tokio::test]
+    async fn slow_statements_are_logged_as_warnings() {
+        log::set_logger(&QUERY_WARNINGS).unwrap();
+        log::set_max_level(LevelFilter::Warn);
+        let pool = create_pool(PoolSettings {
+            slow_query_ms: 50,
+            ..settings()
+        })
+        .await
+        .unwrap();
+
+        pool.execute("SELECT 1;").await.unwrap();
+        pool.execute("SELECT pg_sleep(0.2);").await.unwrap();
+
+        // Other tests' pools may log too, so only the warnings about these statements count
+        let warnings = QUERY_WARNINGS.0.lock().unwrap();
+        let about = |sql: &str| {
+            warnings
+                .iter()
+                .filter(|warning| warning.contains(sql))
+                .collect::<Vec<_>>()
+        };
+        assert!(about("SELECT 1;").is_empty());
+        let slow = about("pg_sleep(0.2)");
+        assert_eq!(slow.len(), 1);
+        assert!(slow[0].contains("slow statement"));
+    }
+}
@@ -0,0 +1,42 @@
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// A table's total row count, reused until it is invalidated or older than the TTL.
+///
+/// A count computed while a write commits may be stored after the invalidation,
+/// so the TTL bounds how long a stale total can be served.
+#[derive(Default)]
+pub struct CountCache {
+    count: AtomicU64,
+    /// Milliseconds since the Unix epoch when `count` was stored, or `0` when there is none.
+    stored_at: AtomicU64,
+}
+
+impl CountCache {
+    /// Returns the stored count if it is younger than `ttl`.
+    pub fn get(&self, ttl: Duration) -> Option<i64> {
+        let stored_at = self.stored_at.load(Ordering::Acquire);
+        if stored_at == 0 || now_millis().saturating_sub(stored_at) >= ttl.as_millis() as u64 {
+            return None;
+        }
+        Some(self.count.load(Ordering::Relaxed) as i64)
+    }
+
+    pub fn set(&self, count: i64) {
+        self.count.store(count.max(0) as u64, Ordering::Relaxed);
+        self.stored_at.store(now_millis().max(1), Ordering::Release);
+    }
+
+    /// Discards the stored count, so the next read recomputes it.
+    pub fn invalidate(&self) {
+        self.stored_at.store(0, Ordering::Release);
+    }
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_millis() as u64)
+}
@@ -0,0 +1,77 @@
+use sqlx::PgPool;
+
+/// Columns the listing and filtering queries look rows up by, as `(table, column)`.
+pub const EXPECTED_INDEXES: &[(&str, &str)] = &[
+    ("toners", "name"),
+    ("drums", "name"),
+    ("brands", "name"),
+    ("printers", "name"),
+    ("movements", "printer_id"),
+    ("movements", "item_id"),
+    ("movements", "created_at"),
+];
+
+/// Lists the expected columns that are not the leading column of any index,
+/// so queries filtering by them scan the whole table.
+pub async fn missing_indexes(
+    pool: &PgPool,
+) -> Result<Vec<(&'static str, &'static str)>, sqlx::Error> {
+    let mut missing = Vec::new();
+
+    for &(table, column) in EXPECTED_INDEXES {
+        let indexed = sqlx::query_scalar::<_, bool>(
+            r#"
+            SELECT EXISTS (
+                SELECT 1
+                FROM pg_index i
+                JOIN pg_class t ON t.oid = i.indrelid
+                JOIN pg_namespace n ON n.oid = t.relnamespace
+                JOIN pg_attribute a ON a.attrelid = t.oid AND a.attnum = i.indkey[0]
+                WHERE n.nspname = current_schema()
+                    AND t.relname = $1
+                    AND a.attname = $2
+            );
+            "#,
+        )
+        .bind(table)
+        .bind(column)
+        .fetch_one(pool)
+        .await?;
+
+        if !indexed {
+            missing.push((table, column));
+        }
+    }
+
+    Ok(missing)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::missing_indexes;
+    use sqlx::PgPool;
+
+    #[sqlx::test(migrations = "src/database/migrations")]
+    async fn the_migrations_create_every_expected_index(pool: PgPool) {
+        assert!(missing_indexes(&pool).await.unwrap().is_empty());
+    }
+
+    #[sqlx::test(migrations = "src/database/migrations")]
+    async fn only_the_leading_column_of_an_index_counts(pool: PgPool) {
+        sqlx::query("DROP INDEX movements_created_at_idx;")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            "CREATE INDEX movements_item_id_created_at_idx ON movements (item_id, created_at);",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        assert_eq!(
+            missing_indexes(&pool).await.unwrap(),
+            [("movements", "created_at")]
+        );
+    }
+}
@@ -0,0 +1,5 @@
+ALTER TABLE movements ADD COLUMN updated_at TIMESTAMPTZ DEFAULT NOW();
+
+UPDATE movements SET updated_at = COALESCE(created_at, NOW());
+
+ALTER TABLE movements ALTER COLUMN updated_at SET NOT NULL;
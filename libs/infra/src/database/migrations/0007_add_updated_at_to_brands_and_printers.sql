@@ -0,0 +1,3 @@
+ALTER TABLE brands ADD COLUMN updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW();
+
+ALTER TABLE printers ADD COLUMN updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW();
@@ -0,0 +1,3 @@
+CREATE TYPE movement_kind AS ENUM ('regular', 'adjustment');
+
+ALTER TABLE movements ADD COLUMN kind movement_kind NOT NULL DEFAULT 'regular';
@@ -0,0 +1,5 @@
+CREATE TABLE printer_compatible_supplies (
+    printer_id UUID NOT NULL REFERENCES printers(id) ON DELETE CASCADE,
+    supply_id UUID NOT NULL,
+    PRIMARY KEY (printer_id, supply_id)
+);
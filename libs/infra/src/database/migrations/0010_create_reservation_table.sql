@@ -0,0 +1,9 @@
+CREATE TABLE reservations (
+    id UUID PRIMARY KEY,
+    item_id UUID NOT NULL,
+    quantity INTEGER NOT NULL CHECK (quantity > 0),
+    expires_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+CREATE INDEX reservations_item_id_expires_at_idx ON reservations (item_id, expires_at);
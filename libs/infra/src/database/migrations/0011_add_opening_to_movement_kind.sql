@@ -0,0 +1 @@
+ALTER TYPE movement_kind ADD VALUE 'opening';
@@ -0,0 +1,3 @@
+ALTER TABLE toners ADD COLUMN brand_id UUID REFERENCES brands(id) ON DELETE SET NULL;
+
+ALTER TABLE drums ADD COLUMN brand_id UUID REFERENCES brands(id) ON DELETE SET NULL;
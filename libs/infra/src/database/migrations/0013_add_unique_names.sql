@@ -0,0 +1,32 @@
+-- Names repeated before the constraints existed are kept on one row and suffixed
+-- with the start of the ID on the others, so the constraints can be added
+UPDATE brands b
+SET name = LEFT(r.name, 39) || ' (' || LEFT(r.id::text, 8) || ')'
+FROM (SELECT id, name, ROW_NUMBER() OVER (PARTITION BY name ORDER BY id) AS position FROM brands) r
+WHERE b.id = r.id AND r.position > 1;
+
+UPDATE printers p
+SET name = LEFT(r.name, 89) || ' (' || LEFT(r.id::text, 8) || ')'
+FROM (SELECT id, name, ROW_NUMBER() OVER (PARTITION BY name ORDER BY id) AS position FROM printers) r
+WHERE p.id = r.id AND r.position > 1;
+
+UPDATE toners t
+SET name = LEFT(r.name, 39) || ' (' || LEFT(r.id::text, 8) || ')'
+FROM (SELECT id, name, ROW_NUMBER() OVER (PARTITION BY name, brand_id ORDER BY id) AS position FROM toners) r
+WHERE t.id = r.id AND r.position > 1;
+
+UPDATE drums d
+SET name = LEFT(r.name, 39) || ' (' || LEFT(r.id::text, 8) || ')'
+FROM (SELECT id, name, ROW_NUMBER() OVER (PARTITION BY name, brand_id ORDER BY id) AS position FROM drums) r
+WHERE d.id = r.id AND r.position > 1;
+
+ALTER TABLE brands ADD CONSTRAINT brands_name_key UNIQUE (name);
+
+ALTER TABLE printers ADD CONSTRAINT printers_name_key UNIQUE (name);
+
+-- Supplies without a brand share the same scope, so NULL brands are compared as equal.
+-- Globally unique supply names (UNIQUE_NAMES_PER_BRAND=false) are only enforced by the
+-- handlers, so two concurrent writes of the same name under different brands can both pass
+ALTER TABLE toners ADD CONSTRAINT toners_name_brand_id_key UNIQUE NULLS NOT DISTINCT (name, brand_id);
+
+ALTER TABLE drums ADD CONSTRAINT drums_name_brand_id_key UNIQUE NULLS NOT DISTINCT (name, brand_id);
@@ -0,0 +1,7 @@
+ALTER TABLE toners ALTER COLUMN stock TYPE BIGINT;
+
+ALTER TABLE drums ALTER COLUMN stock TYPE BIGINT;
+
+ALTER TABLE movements ALTER COLUMN quantity TYPE BIGINT;
+
+ALTER TABLE reservations ALTER COLUMN quantity TYPE BIGINT;
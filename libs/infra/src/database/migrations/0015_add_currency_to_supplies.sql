@@ -0,0 +1,3 @@
+ALTER TABLE toners ADD COLUMN currency CHAR(3);
+
+ALTER TABLE drums ADD COLUMN currency CHAR(3);
@@ -0,0 +1,6 @@
+-- The name columns are already indexed by the unique constraints added in 0013
+CREATE INDEX IF NOT EXISTS movements_printer_id_idx ON movements (printer_id);
+
+CREATE INDEX IF NOT EXISTS movements_item_id_idx ON movements (item_id);
+
+CREATE INDEX IF NOT EXISTS movements_created_at_idx ON movements (created_at);
@@ -0,0 +1,8 @@
+CREATE TYPE item_type AS ENUM ('toner', 'drum');
+
+-- Movements of items deleted before this migration keep an unknown type
+ALTER TABLE movements ADD COLUMN item_type item_type;
+
+UPDATE movements m SET item_type = 'toner' WHERE EXISTS (SELECT 1 FROM toners t WHERE t.id = m.item_id);
+
+UPDATE movements m SET item_type = 'drum' WHERE EXISTS (SELECT 1 FROM drums d WHERE d.id = m.item_id);
@@ -1,7 +1,12 @@
 pub mod connection;
+pub mod count_cache;
+pub mod indexes;
 
+use count_cache::CountCache;
 use sqlx::PgPool;
 
 pub struct AppState {
     pub db: PgPool,
+    /// Total of toners reported in paginated lists.
+    pub toner_count: CountCache,
 }
@@ -1,7 +1,16 @@
-use axum::http::StatusCode;
+use axum::extract::rejection::{JsonRejection, PathRejection};
+use axum::http::{header, StatusCode};
 use axum::response::{IntoResponse, Response};
 use axum::Json;
+use config::Config;
+use serde::Serialize;
+use std::fmt;
 use thiserror::Error;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Seconds clients are asked to wait before retrying after a transient database error.
+const RETRY_AFTER_SECONDS: u64 = 5;
 
 #[derive(Error, Debug)]
 pub enum ApiError {
@@ -11,72 +20,574 @@ pub enum ApiError {
     #[error("One or more validation errors occurred: {0}")]
     ValidationError(#[from] validator::ValidationErrors),
 
-    #[error("The provided ID does not correspond to any existing resource.")]
-    IdNotFound,
+    #[error("The request body is not valid JSON: {0}")]
+    JsonRejection(#[from] JsonRejection),
+
+    #[error("The request body must be sent as `application/json`.")]
+    UnsupportedMediaType,
+
+    #[error("The request body is empty.")]
+    EmptyBody,
+
+    #[error("The request body does not match the expected format: {0}")]
+    InvalidJson(#[from] serde_json::Error),
+
+    #[error("The path parameters are not valid: {0}")]
+    PathRejection(#[from] PathRejection),
+
+    #[error("The request body contains unknown fields: {}", .0.join(", "))]
+    UnknownFields(Vec<String>),
+
+    #[error("{resource} with the provided ID does not exist.")]
+    IdNotFound { resource: &'static str },
+
+    #[error("Some of the provided IDs do not correspond to any existing resource: {0:?}")]
+    IdsNotFound(Vec<Uuid>),
+
+    #[error("A resource named '{name}' already exists with ID {id}.")]
+    AlreadyExists { name: String, id: Uuid },
+
+    #[error("There is not enough stock for the requested quantity.")]
+    InsufficientStock,
+
+    #[error("The resource was modified since it was last read.")]
+    Outdated,
+
+    #[error("The resource no longer matches the `If-Match` ETag.")]
+    PreconditionFailed,
+
+    #[error("Too many consumption movements for this item, retry in {retry_after} seconds.")]
+    ConsumptionRateLimited { retry_after: u64 },
+
+    #[error("The purged movements could not be archived: {0}")]
+    ArchiveError(std::io::Error),
+
+    #[error("The request is not authorized to use this endpoint.")]
+    Forbidden,
+
+    #[error("The API is in read-only mode.")]
+    ReadOnly,
+
+    #[error("Migration {0} was modified after being applied.")]
+    MigrationChanged(i64),
 
-    #[error("A resource with the provided name already exists.")]
+    #[error("The batch item at index {index} failed: {source}")]
+    BatchItemFailed { index: usize, source: Box<ApiError> },
+}
+
+/// Machine-readable code identifying the kind of an error response.
+#[derive(Serialize, ToSchema, Debug, Clone, Copy)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    DatabaseUnavailable,
+    DatabaseError,
+    ValidationError,
+    InvalidJson,
+    UnsupportedMediaType,
+    EmptyBody,
+    InvalidUuid,
+    UnknownFields,
+    IdNotFound,
     AlreadyExists,
+    InsufficientStock,
+    Outdated,
+    PreconditionFailed,
+    RateLimited,
+    ArchiveError,
+    Forbidden,
+    ReadOnly,
+    MigrationChanged,
+    BatchItemFailed,
+}
 
-    #[error("No updates were made for the provided ID.")]
-    NotModified,
+impl fmt::Display for ErrorCode {
+    /// Writes the code as it appears in error responses, such as `ID_NOT_FOUND`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match serde_json::to_value(self) {
+            Ok(serde_json::Value::String(code)) => f.write_str(&code),
+            _ => Err(fmt::Error),
+        }
+    }
 }
 
-#[derive(serde::Serialize)]
+#[derive(Serialize)]
 struct ErrorResponse {
-    code: String,
+    code: ErrorCode,
     message: String,
     details: Option<String>,
+    /// The resource a conflicting request collided with, as `id` and `name` fields.
+    #[serde(flatten)]
+    existing: Option<ExistingResource>,
 }
 
-impl IntoResponse for ApiError {
-    fn into_response(self) -> Response {
-        let (status_code, error_response) = match &self {
-            ApiError::DatabaseError(_) => (
+#[derive(Serialize)]
+struct ExistingResource {
+    id: Uuid,
+    name: String,
+}
+
+/// Whether the database error is likely to go away on its own, such as an exhausted pool or a dropped connection.
+fn is_transient(e: &sqlx::Error) -> bool {
+    matches!(
+        e,
+        sqlx::Error::PoolTimedOut
+            | sqlx::Error::PoolClosed
+            | sqlx::Error::Io(_)
+            | sqlx::Error::WorkerCrashed
+    )
+}
+
+/// Joins the error and each of its sources, outermost first.
+///
+/// Sources already quoted by the message that wraps them are not repeated.
+fn error_chain(e: &dyn std::error::Error) -> String {
+    let mut chain = e.to_string();
+    let mut source = e.source();
+    while let Some(cause) = source {
+        let cause_message = cause.to_string();
+        if !chain.ends_with(&cause_message) {
+            chain.push_str(": ");
+            chain.push_str(&cause_message);
+        }
+        source = cause.source();
+    }
+    chain
+}
+
+/// Lists each failed field with the code and message of its errors,
+/// such as `name: [NAME_TOO_SHORT] Name must have at least 3 characters`.
+fn validation_details(errors: &validator::ValidationErrors) -> String {
+    let mut fields: Vec<_> = errors.field_errors().into_iter().collect();
+
+    // Nested errors are not listed per field, so they keep the default format
+    if fields.is_empty() {
+        return errors.to_string();
+    }
+
+    fields.sort_by_key(|(field, _)| *field);
+    fields
+        .into_iter()
+        .flat_map(|(field, field_errors)| {
+            field_errors.iter().map(move |error| match &error.message {
+                Some(message) => format!("{field}: [{}] {message}", error.code),
+                None => format!("{field}: [{}]", error.code),
+            })
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+impl ApiError {
+    fn to_error_response(&self) -> (StatusCode, ErrorResponse) {
+        match self {
+            ApiError::DatabaseError(e) if is_transient(e) => (
+                StatusCode::SERVICE_UNAVAILABLE,
+                ErrorResponse {
+                    code: ErrorCode::DatabaseUnavailable,
+                    message: String::from("The database is temporarily unavailable."),
+                    details: Some(format!(
+                        "Please try again in {RETRY_AFTER_SECONDS} seconds."
+                    )),
+                    existing: None,
+                },
+            ),
+            ApiError::DatabaseError(e) => (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 ErrorResponse {
-                    code: String::from("DATABASE_ERROR"),
+                    code: ErrorCode::DatabaseError,
                     message: String::from("An unexpected database error occurred."),
-                    details: Some(String::from("Please try again later or contact support.")),
+                    details: Some(if Config::expose_errors() {
+                        error_chain(e)
+                    } else {
+                        String::from("Please try again later or contact support.")
+                    }),
+                    existing: None,
                 },
             ),
             ApiError::ValidationError(e) => (
                 StatusCode::BAD_REQUEST,
                 ErrorResponse {
-                    code: String::from("VALIDATION_ERROR"),
+                    code: ErrorCode::ValidationError,
                     message: String::from("One or more validation errors occurred."),
+                    details: Some(validation_details(e)),
+                    existing: None,
+                },
+            ),
+            ApiError::JsonRejection(e) => (
+                e.status(),
+                ErrorResponse {
+                    code: ErrorCode::InvalidJson,
+                    message: String::from("The request body could not be read as JSON."),
+                    details: Some(e.body_text()),
+                    existing: None,
+                },
+            ),
+            ApiError::UnsupportedMediaType => (
+                StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                ErrorResponse {
+                    code: ErrorCode::UnsupportedMediaType,
+                    message: String::from("The request body must be sent as JSON."),
+                    details: Some(String::from(
+                        "Please set the `Content-Type: application/json` header.",
+                    )),
+                    existing: None,
+                },
+            ),
+            ApiError::EmptyBody => (
+                StatusCode::BAD_REQUEST,
+                ErrorResponse {
+                    code: ErrorCode::EmptyBody,
+                    message: String::from("The request body is empty."),
+                    details: Some(String::from("Please send a JSON body with the request.")),
+                    existing: None,
+                },
+            ),
+            ApiError::InvalidJson(e) => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                ErrorResponse {
+                    code: ErrorCode::InvalidJson,
+                    message: String::from("The request body does not match the expected format."),
                     details: Some(e.to_string()),
+                    existing: None,
                 },
             ),
-            ApiError::IdNotFound => (
+            ApiError::PathRejection(e) => (
+                StatusCode::BAD_REQUEST,
+                ErrorResponse {
+                    code: ErrorCode::InvalidUuid,
+                    message: String::from("The provided ID is not a valid UUID."),
+                    details: Some(e.body_text()),
+                    existing: None,
+                },
+            ),
+            ApiError::UnknownFields(fields) => (
+                StatusCode::BAD_REQUEST,
+                ErrorResponse {
+                    code: ErrorCode::UnknownFields,
+                    message: String::from("The request body contains unknown fields."),
+                    details: Some(format!("Unknown fields: {}", fields.join(", "))),
+                    existing: None,
+                },
+            ),
+            ApiError::IdNotFound { resource } => (
                 StatusCode::NOT_FOUND,
                 ErrorResponse {
-                    code: String::from("ID_NOT_FOUND"),
-                    message: String::from("The provided ID does not exist."),
+                    code: ErrorCode::IdNotFound,
+                    message: format!("{resource} with the provided ID does not exist."),
                     details: Some(String::from(
                         "Please verify that the ID is correct and try again.",
                     )),
+                    existing: None,
                 },
             ),
-            ApiError::NotModified => (
-                StatusCode::NOT_MODIFIED,
+            ApiError::IdsNotFound(ids) => (
+                StatusCode::NOT_FOUND,
                 ErrorResponse {
-                    code: String::from("NOT_MODIFIED"),
-                    message: String::from("No updates were made for the provided ID."),
-                    details: Some(String::from(
-                        "The provided ID may not exist, or no fields were changed. Please verify the ID and the update values.",
+                    code: ErrorCode::IdNotFound,
+                    message: String::from("Some of the provided IDs do not exist."),
+                    details: Some(format!(
+                        "Unknown IDs: {}",
+                        ids.iter()
+                            .map(Uuid::to_string)
+                            .collect::<Vec<_>>()
+                            .join(", ")
                     )),
+                    existing: None,
                 },
             ),
-            ApiError::AlreadyExists => (
+            ApiError::AlreadyExists { name, id } => (
                 StatusCode::CONFLICT,
                 ErrorResponse {
-                    code: String::from("ALREADY_EXISTS"),
+                    code: ErrorCode::AlreadyExists,
                     message: String::from("A resource with the provided details already exists."),
-                    details: Some(String::from("Please choose a different name.")),
+                    details: Some(format!(
+                        "A resource named '{name}' already exists with ID {id}. Please choose a different name or update the existing resource."
+                    )),
+                    existing: Some(ExistingResource {
+                        id: *id,
+                        name: name.clone(),
+                    }),
+                },
+            ),
+            ApiError::InsufficientStock => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                ErrorResponse {
+                    code: ErrorCode::InsufficientStock,
+                    message: String::from("There is not enough stock for the requested quantity."),
+                    details: Some(String::from(
+                        "Please check the item's current stock and try a smaller quantity.",
+                    )),
+                    existing: None,
+                },
+            ),
+            ApiError::Outdated => (
+                StatusCode::CONFLICT,
+                ErrorResponse {
+                    code: ErrorCode::Outdated,
+                    message: String::from("The resource was modified since it was last read."),
+                    details: Some(String::from(
+                        "Please fetch the latest version of the resource and try again.",
+                    )),
+                    existing: None,
+                },
+            ),
+            ApiError::PreconditionFailed => (
+                StatusCode::PRECONDITION_FAILED,
+                ErrorResponse {
+                    code: ErrorCode::PreconditionFailed,
+                    message: String::from("The resource no longer matches the `If-Match` ETag."),
+                    details: Some(String::from(
+                        "The resource was modified since it was last read. Please fetch it again for its current ETag and retry.",
+                    )),
+                    existing: None,
+                },
+            ),
+            ApiError::ConsumptionRateLimited { retry_after } => (
+                StatusCode::TOO_MANY_REQUESTS,
+                ErrorResponse {
+                    code: ErrorCode::RateLimited,
+                    message: String::from("Too many consumption movements for this item."),
+                    details: Some(format!(
+                        "At most {} consumption movements per item are accepted per minute. Please try again in {retry_after} seconds.",
+                        Config::max_consumption_per_minute().unwrap_or_default()
+                    )),
+                    existing: None,
+                },
+            ),
+            ApiError::ArchiveError(e) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                ErrorResponse {
+                    code: ErrorCode::ArchiveError,
+                    message: String::from("The purged movements could not be archived."),
+                    details: Some(format!(
+                        "{e}. The movements of the failed batch were not deleted."
+                    )),
+                    existing: None,
+                },
+            ),
+            ApiError::Forbidden => (
+                StatusCode::FORBIDDEN,
+                ErrorResponse {
+                    code: ErrorCode::Forbidden,
+                    message: String::from("The request is not authorized to use this endpoint."),
+                    details: Some(String::from(
+                        "Please send the admin token in the `Authorization: Bearer` header.",
+                    )),
+                    existing: None,
+                },
+            ),
+            ApiError::ReadOnly => (
+                StatusCode::SERVICE_UNAVAILABLE,
+                ErrorResponse {
+                    code: ErrorCode::ReadOnly,
+                    message: String::from("The API is in read-only mode."),
+                    details: Some(String::from(
+                        "Changes are disabled during maintenance. Please try again later.",
+                    )),
+                    existing: None,
                 },
             ),
+            ApiError::MigrationChanged(version) => (
+                StatusCode::CONFLICT,
+                ErrorResponse {
+                    code: ErrorCode::MigrationChanged,
+                    message: format!("Migration {version} was modified after being applied."),
+                    details: Some(String::from(
+                        "The migration file no longer matches the checksum stored in the database. Please restore the original file.",
+                    )),
+                    existing: None,
+                },
+            ),
+            ApiError::BatchItemFailed { index, source } => {
+                let (status_code, source_response) = source.to_error_response();
+                (
+                    status_code,
+                    ErrorResponse {
+                        code: ErrorCode::BatchItemFailed,
+                        message: format!(
+                            "The batch was rolled back because the item at index {index} failed."
+                        ),
+                        details: Some(format!(
+                            "{}: {}",
+                            source_response.code, source_response.message
+                        )),
+                        existing: None,
+                    },
+                )
+            }
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (status_code, error_response) = self.to_error_response();
+
+        let rate_limited = match &self {
+            ApiError::BatchItemFailed { source, .. } => source.as_ref(),
+            error => error,
         };
+        if let ApiError::ConsumptionRateLimited { retry_after } = rate_limited {
+            return (
+                status_code,
+                [(header::RETRY_AFTER, retry_after.to_string())],
+                Json(error_response),
+            )
+                .into_response();
+        }
+
+        // Read-only mode lasts for an unknown time, so no retry delay is suggested
+        if status_code == StatusCode::SERVICE_UNAVAILABLE && !matches!(self, ApiError::ReadOnly) {
+            return (
+                status_code,
+                [(header::RETRY_AFTER, RETRY_AFTER_SECONDS.to_string())],
+                Json(error_response),
+            )
+                .into_response();
+        }
 
         (status_code, Json(error_response)).into_response()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{ApiError, ErrorCode, RETRY_AFTER_SECONDS};
+    use crate::openapi::api_doc::ApiDoc;
+    use crate::test_utils::{app, empty_request, send};
+    use axum::http::header;
+    use axum::http::{Method, StatusCode};
+    use axum::response::IntoResponse;
+    use serde_json::json;
+    use sqlx::PgPool;
+    use utoipa::OpenApi;
+
+    const CODES: [ErrorCode; 19] = [
+        ErrorCode::DatabaseUnavailable,
+        ErrorCode::DatabaseError,
+        ErrorCode::ValidationError,
+        ErrorCode::InvalidJson,
+        ErrorCode::UnsupportedMediaType,
+        ErrorCode::EmptyBody,
+        ErrorCode::InvalidUuid,
+        ErrorCode::UnknownFields,
+        ErrorCode::IdNotFound,
+        ErrorCode::AlreadyExists,
+        ErrorCode::InsufficientStock,
+        ErrorCode::Outdated,
+        ErrorCode::PreconditionFailed,
+        ErrorCode::RateLimited,
+        ErrorCode::ArchiveError,
+        ErrorCode::Forbidden,
+        ErrorCode::ReadOnly,
+        ErrorCode::MigrationChanged,
+        ErrorCode::BatchItemFailed,
+    ];
+
+    #[test]
+    fn codes_serialize_as_documented_and_are_all_in_the_schema() {
+        let doc = serde_json::to_value(ApiDoc::openapi()).unwrap();
+        let schema_codes = &doc["components"]["schemas"]["ErrorCode"]["enum"];
+
+        assert_eq!(schema_codes.as_array().unwrap().len(), CODES.len());
+        for code in CODES {
+            assert_eq!(serde_json::to_value(code).unwrap(), json!(code.to_string()));
+            assert!(schema_codes
+                .as_array()
+                .unwrap()
+                .contains(&json!(code.to_string())));
+        }
+    }
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn unknown_ids_are_reported_with_their_code(pool: PgPool) {
+        let app = app(pool);
+
+        let (status, body) = send(
+            &app,
+            empty_request(
+                Method::GET,
+                "/api/v1/brands/550e8400-e29b-41d4-a716-446655440000",
+            ),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["code"], "ID_NOT_FOUND");
+        assert_eq!(
+            body["message"],
+            "Brand with the provided ID does not exist."
+        );
+    }
+
+    #[test]
+    fn pool_timeouts_ask_the_client_to_retry() {
+        let response = ApiError::DatabaseError(sqlx::Error::PoolTimedOut).into_response();
+
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(
+            response.headers()[header::RETRY_AFTER],
+            RETRY_AFTER_SECONDS.to_string()
+        );
+    }
+
+    #[test]
+    fn permanent_database_errors_are_not_retried() {
+        let response = ApiError::DatabaseError(sqlx::Error::RowNotFound).into_response();
+
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(!response.headers().contains_key(header::RETRY_AFTER));
+    }
+
+    #[tokio::test]
+    async fn database_errors_are_exposed_only_when_enabled() {
+        let details = |response: axum::response::Response| async move {
+            let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+            String::from(body["details"].as_str().unwrap())
+        };
+        let error = || {
+            ApiError::DatabaseError(sqlx::Error::Protocol(String::from(
+                "unexpected message 'Z'",
+            )))
+        };
+
+        std::env::set_var("EXPOSE_ERRORS", "true");
+        let exposed = error().into_response();
+        std::env::set_var("EXPOSE_ERRORS", "false");
+        let redacted = error().into_response();
+
+        assert!(details(exposed).await.contains("unexpected message 'Z'"));
+        assert_eq!(
+            details(redacted).await,
+            "Please try again later or contact support."
+        );
+    }
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn missing_ids_are_reported_with_their_resource(pool: PgPool) {
+        let app = app(pool);
+        let missing = "550e8400-e29b-41d4-a716-446655440000";
+
+        for (collection, resource) in [
+            ("supplies/toners", "Toner"),
+            ("brands", "Brand"),
+            ("printers", "Printer"),
+            ("movements", "Movement"),
+        ] {
+            let (status, body) = send(
+                &app,
+                empty_request(Method::GET, &format!("/api/v1/{collection}/{missing}")),
+            )
+            .await;
+
+            assert_eq!(status, StatusCode::NOT_FOUND, "{collection}");
+            assert_eq!(
+                body["message"],
+                format!("{resource} with the provided ID does not exist.")
+            );
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use crate::errors::api_error::ApiError;
+use axum::{
+    async_trait,
+    extract::FromRequestParts,
+    http::{header, request::Parts},
+};
+use config::Config;
+use tracing::warn;
+
+/// Guard for admin endpoints, accepting only requests that carry `Authorization: Bearer <ADMIN_TOKEN>`.
+///
+/// Every request is rejected with a `403` while `ADMIN_TOKEN` is unset.
+pub struct Admin;
+
+#[async_trait]
+impl<S> FromRequestParts<S> for Admin
+where
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let Some(token) = Config::admin_token() else {
+            warn!(
+                "Rejected admin request to {}: ADMIN_TOKEN is not set",
+                parts.uri
+            );
+            return Err(ApiError::Forbidden);
+        };
+
+        let authorized = parts
+            .headers
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .is_some_and(|provided| provided == token);
+
+        if !authorized {
+            warn!("Rejected admin request to {}: invalid token", parts.uri);
+            return Err(ApiError::Forbidden);
+        }
+
+        Ok(Admin)
+    }
+}
@@ -0,0 +1,85 @@
+use crate::errors::api_error::ApiError;
+use axum::{
+    async_trait,
+    extract::FromRequestParts,
+    http::{header, request::Parts},
+};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::convert::Infallible;
+use tracing::warn;
+
+/// Builds the strong ETag of a resource from its JSON representation.
+///
+/// The same resource always gets the same tag, and any change to a returned field changes it.
+/// The tag is a SHA-256 digest, so it survives restarts and upgrades of the server.
+pub fn etag<T: Serialize>(resource: &T) -> String {
+    let json = serde_json::to_vec(resource).expect("Error serializing resource");
+    let digest: String = Sha256::digest(json)
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect();
+    format!("\"{digest}\"")
+}
+
+/// The `If-Match` header of an update, holding the ETags the client last read.
+///
+/// Updates are unconditional when the header is missing.
+pub struct IfMatch(Option<String>);
+
+impl IfMatch {
+    pub fn is_present(&self) -> bool {
+        self.0.is_some()
+    }
+
+    /// Fails with `412` unless the header is `*` or lists `current`.
+    pub fn check(&self, current: &str) -> Result<(), ApiError> {
+        let Some(expected) = &self.0 else {
+            return Ok(());
+        };
+
+        let matches = expected
+            .split(',')
+            .map(str::trim)
+            .any(|tag| tag == "*" || tag == current);
+
+        if !matches {
+            warn!("If-Match {expected} does not match the current ETag {current}");
+            return Err(ApiError::PreconditionFailed);
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for IfMatch
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let if_match = parts
+            .headers
+            .get(header::IF_MATCH)
+            .and_then(|value| value.to_str().ok())
+            .map(String::from);
+
+        Ok(IfMatch(if_match))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn etags_are_the_sha256_of_the_json() {
+        assert_eq!(
+            etag(&json!({ "name": "TN-1060" })),
+            "\"5bffae2516600ae3df3f9b1590a66be7c841901e8ca8a4bad82263c31eb261e9\""
+        );
+    }
+}
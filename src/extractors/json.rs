@@ -0,0 +1,137 @@
+use crate::errors::api_error::ApiError;
+use axum::{
+    async_trait,
+    body::{Body, Bytes},
+    extract::{rejection::JsonRejection, FromRequest, Request},
+    response::{IntoResponse, Response},
+};
+use config::Config;
+use serde::{de::DeserializeOwned, Serialize};
+use tracing::error;
+
+/// JSON extractor and response that reports errors in the API's `ErrorResponse` shape.
+///
+/// A body not sent as `application/json` is rejected with a `415`, and an empty one with a `400`.
+/// Unknown fields in the request body are ignored, unless `STRICT_JSON` is enabled,
+/// in which case they are rejected with a `400` naming them.
+pub struct Json<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequest<S> for Json<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let (parts, body) = req.into_parts();
+
+        let bytes = Bytes::from_request(Request::from_parts(parts.clone(), body), state)
+            .await
+            .map_err(|e| {
+                error!("Error reading request body: {e}");
+                ApiError::JsonRejection(JsonRejection::BytesRejection(e))
+            })?;
+
+        if bytes.is_empty() {
+            error!("Empty request body");
+            return Err(ApiError::EmptyBody);
+        }
+
+        let req = Request::from_parts(parts, Body::from(bytes));
+        let axum::Json(value) = axum::Json::<serde_json::Value>::from_request(req, state)
+            .await
+            .map_err(|e| match e {
+                JsonRejection::MissingJsonContentType(_) => {
+                    error!("Request body is not JSON: {e}");
+                    ApiError::UnsupportedMediaType
+                }
+                _ => {
+                    error!("Error parsing JSON body: {e}");
+                    ApiError::JsonRejection(e)
+                }
+            })?;
+
+        let mut unknown_fields = Vec::new();
+        let payload = serde_ignored::deserialize(value, |path| {
+            unknown_fields.push(path.to_string());
+        })
+        .map_err(|e| {
+            error!("Error deserializing JSON body: {e}");
+            ApiError::InvalidJson(e)
+        })?;
+
+        if Config::strict_json() && !unknown_fields.is_empty() {
+            error!("Unknown fields in JSON body: {}", unknown_fields.join(", "));
+            return Err(ApiError::UnknownFields(unknown_fields));
+        }
+
+        Ok(Json(payload))
+    }
+}
+
+impl<T: Serialize> IntoResponse for Json<T> {
+    fn into_response(self) -> Response {
+        axum::Json(self.0).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Json;
+    use crate::test_utils::{json_request, send};
+    use axum::{
+        http::{Method, StatusCode},
+        routing::post,
+        Router,
+    };
+    use serde::Deserialize;
+    use serde_json::json;
+    use std::env;
+
+    #[derive(Deserialize)]
+    struct NameRequest {
+        name: String,
+    }
+
+    fn echo_app() -> Router {
+        Router::new().route(
+            "/",
+            post(|Json(request): Json<NameRequest>| async move { Json(request.name) }),
+        )
+    }
+
+    #[tokio::test]
+    async fn unknown_fields_are_rejected_only_in_strict_mode() {
+        let app = echo_app();
+        let typo = || json_request(Method::POST, "/", json!({ "name": "TN-1060", "naem": "x" }));
+
+        env::set_var("STRICT_JSON", "true");
+        let (strict_status, strict_body) = send(&app, typo()).await;
+        env::set_var("STRICT_JSON", "false");
+        let (status, body) = send(&app, typo()).await;
+
+        assert_eq!(strict_status, StatusCode::BAD_REQUEST);
+        assert_eq!(strict_body["code"], "UNKNOWN_FIELDS");
+        assert!(strict_body["details"].as_str().unwrap().contains("naem"));
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, "TN-1060");
+    }
+
+    #[tokio::test]
+    async fn bodies_not_sent_as_json_are_unsupported() {
+        let request = axum::http::Request::builder()
+            .method(Method::POST)
+            .uri("/")
+            .header(axum::http::header::CONTENT_TYPE, "text/plain")
+            .body(axum::body::Body::from(r#"{ "name": "TN-1060" }"#))
+            .unwrap();
+
+        let (status, body) = send(&echo_app(), request).await;
+
+        assert_eq!(status, StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        assert_eq!(body["code"], "UNSUPPORTED_MEDIA_TYPE");
+        assert!(body["message"].is_string());
+    }
+}
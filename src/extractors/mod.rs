@@ -0,0 +1,5 @@
+pub mod admin;
+pub mod if_match;
+pub mod json;
+pub mod path;
+pub mod prefer;
@@ -0,0 +1,51 @@
+use crate::errors::api_error::ApiError;
+use axum::{async_trait, extract::FromRequestParts, http::request::Parts};
+use serde::de::DeserializeOwned;
+use tracing::error;
+
+/// Path extractor that reports malformed path parameters in the API's `ErrorResponse` shape.
+///
+/// A parameter that cannot be parsed, such as a non-UUID `id`, is rejected with a `400`.
+pub struct Path<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequestParts<S> for Path<T>
+where
+    T: DeserializeOwned + Send,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let axum::extract::Path(value) = axum::extract::Path::<T>::from_request_parts(parts, state)
+            .await
+            .map_err(|e| {
+                error!("Error parsing path parameters: {e}");
+                ApiError::PathRejection(e)
+            })?;
+
+        Ok(Path(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::{app, empty_request, send};
+    use axum::http::{Method, StatusCode};
+    use sqlx::PgPool;
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn malformed_ids_are_reported_as_invalid_uuids(pool: PgPool) {
+        let app = app(pool);
+
+        let (status, body) = send(
+            &app,
+            empty_request(Method::GET, "/api/v1/supplies/toners/not-a-uuid"),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["code"], "INVALID_UUID");
+        assert!(body["message"].is_string());
+    }
+}
@@ -0,0 +1,68 @@
+use crate::{extractors::json::Json, models::ReturnParams};
+use axum::{
+    async_trait,
+    extract::{FromRequestParts, Query},
+    http::{request::Parts, HeaderName},
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+use std::convert::Infallible;
+use uuid::Uuid;
+
+const PREFER: HeaderName = HeaderName::from_static("prefer");
+const PREFERENCE_APPLIED: HeaderName = HeaderName::from_static("preference-applied");
+const REPRESENTATION: &str = "return=representation";
+
+/// What a create endpoint answers with, chosen by `?return=representation` or a
+/// `Prefer: return=representation` header.
+///
+/// Defaults to the bare ID of the new resource, so existing clients are unaffected.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ReturnPreference {
+    Minimal,
+    Representation,
+}
+
+impl ReturnPreference {
+    pub fn is_representation(self) -> bool {
+        self == ReturnPreference::Representation
+    }
+
+    /// Builds the body of a create response: the new ID, or the created resource when it was requested.
+    pub fn body<T: Serialize>(self, id: Uuid, resource: Option<T>) -> Response {
+        match resource {
+            Some(resource) if self.is_representation() => {
+                ([(PREFERENCE_APPLIED, REPRESENTATION)], Json(resource)).into_response()
+            }
+            _ => Json(id).into_response(),
+        }
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for ReturnPreference
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        // Unknown or malformed preferences are ignored, as RFC 7240 asks
+        let in_query = Query::<ReturnParams>::try_from_uri(&parts.uri)
+            .is_ok_and(|Query(params)| params.preference.as_deref() == Some("representation"));
+
+        let in_header = parts
+            .headers
+            .get_all(PREFER)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .any(|preference| preference.trim() == REPRESENTATION);
+
+        if in_query || in_header {
+            Ok(ReturnPreference::Representation)
+        } else {
+            Ok(ReturnPreference::Minimal)
+        }
+    }
+}
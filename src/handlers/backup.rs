@@ -0,0 +1,399 @@
+use crate::{
+    errors::api_error::ApiError,
+    extractors::admin::Admin,
+    extractors::json::Json,
+    models::{
+        backup::{Catalog, CatalogCounts, ImportMode, ImportParams},
+        brand::Brand,
+        movement::Movement,
+        printer::Printer,
+        supplies::{drum::Drum, toner::Toner},
+    },
+};
+use axum::{
+    extract::{Query, State},
+    response::IntoResponse,
+};
+use infra::database::AppState;
+use sqlx::{Postgres, Transaction};
+use std::sync::Arc;
+use tracing::{error, info};
+
+/// Exports the whole catalog as a single JSON document.
+///
+/// This admin endpoint returns every brand, toner, drum, printer and movement, read from a single snapshot.
+/// The document can be restored with the import endpoint.
+#[utoipa::path(
+    get,
+    path = "/api/v1/export",
+    tags = ["Backup"],
+    summary = "Export the whole catalog.",
+    description = "This endpoint returns all brands, toners, drums, printers and movements in one consistent JSON document, suitable for `POST /api/v1/import`. Requires the admin token in the `Authorization: Bearer` header.",
+    responses(
+        (status = 200, description = "Catalog exported successfully", body = Catalog),
+        (status = 403, description = "Missing or invalid admin token"),
+        (status = 500, description = "An error occurred while exporting the catalog")
+    )
+)]
+pub async fn export_catalog(
+    _admin: Admin,
+    State(state): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, ApiError> {
+    let mut tx = state.db.begin().await.map_err(|e| {
+        error!("Error starting transaction: {e}");
+        ApiError::DatabaseError(e)
+    })?;
+
+    // Every table is read from the same snapshot
+    sqlx::query(r#"SET TRANSACTION ISOLATION LEVEL REPEATABLE READ READ ONLY;"#)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| {
+            error!("Error setting the export isolation level: {e}");
+            ApiError::DatabaseError(e)
+        })?;
+
+    let brands = sqlx::query_as::<_, Brand>(r#"SELECT id, name, updated_at FROM brands;"#)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| {
+            error!("Error exporting brands: {e}");
+            ApiError::DatabaseError(e)
+        })?;
+
+    let toners = sqlx::query_as::<_, Toner>(r#"SELECT * FROM toners;"#)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| {
+            error!("Error exporting toners: {e}");
+            ApiError::DatabaseError(e)
+        })?;
+
+    let drums = sqlx::query_as::<_, Drum>(r#"SELECT * FROM drums;"#)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| {
+            error!("Error exporting drums: {e}");
+            ApiError::DatabaseError(e)
+        })?;
+
+    let printers = sqlx::query_as::<_, Printer>(
+        r#"SELECT id, name, model, brand, toner, drum, updated_at FROM printers;"#,
+    )
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|e| {
+        error!("Error exporting printers: {e}");
+        ApiError::DatabaseError(e)
+    })?;
+
+    let movements = sqlx::query_as::<_, Movement>(
+        r#"
+        SELECT id, printer_id, item_id, quantity, kind, created_at, updated_at
+        FROM movements
+        ORDER BY created_at;
+        "#,
+    )
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|e| {
+        error!("Error exporting movements: {e}");
+        ApiError::DatabaseError(e)
+    })?;
+
+    tx.commit().await.map_err(|e| {
+        error!("Error committing export: {e}");
+        ApiError::DatabaseError(e)
+    })?;
+
+    let catalog = Catalog {
+        brands,
+        toners,
+        drums,
+        printers,
+        movements,
+    };
+
+    info!("Catalog exported!");
+    Ok(Json(catalog))
+}
+
+/// Restores a catalog previously exported.
+///
+/// This admin endpoint deletes all existing data first in `replace` mode. In `upsert` mode, the default,
+/// rows are inserted or overwritten by ID. Everything runs in a single transaction, so a failed import changes nothing.
+#[utoipa::path(
+    post,
+    path = "/api/v1/import",
+    tags = ["Backup"],
+    summary = "Import a catalog.",
+    description = "This endpoint restores a document produced by `GET /api/v1/export` in a single transaction. With `mode=replace` the existing data is wiped first; with `mode=upsert` (the default) existing rows with the same ID are overwritten. Returns the number of imported rows per entity. Requires the admin token in the `Authorization: Bearer` header.",
+    params(ImportParams),
+    request_body = Catalog,
+    responses(
+        (status = 200, description = "Catalog imported successfully", body = CatalogCounts),
+        (status = 400, description = "Invalid input"),
+        (status = 403, description = "Missing or invalid admin token"),
+        (status = 500, description = "An error occurred while importing the catalog")
+    )
+)]
+pub async fn import_catalog(
+    _admin: Admin,
+    Query(params): Query<ImportParams>,
+    State(state): State<Arc<AppState>>,
+    Json(catalog): Json<Catalog>,
+) -> Result<impl IntoResponse, ApiError> {
+    let mode = params.mode.unwrap_or_default();
+
+    let mut tx = state.db.begin().await.map_err(|e| {
+        error!("Error starting transaction: {e}");
+        ApiError::DatabaseError(e)
+    })?;
+
+    if let ImportMode::Replace = mode {
+        clear_catalog(&mut tx).await?;
+    }
+
+    for brand in &catalog.brands {
+        sqlx::query(
+            r#"
+            INSERT INTO brands (id, name, updated_at) VALUES ($1, $2, $3)
+            ON CONFLICT (id) DO UPDATE SET name = EXCLUDED.name, updated_at = EXCLUDED.updated_at;
+            "#,
+        )
+        .bind(brand.id)
+        .bind(&brand.name)
+        .bind(brand.updated_at)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| {
+            error!("Error importing brand {}: {e}", brand.id);
+            ApiError::DatabaseError(e)
+        })?;
+    }
+
+    for toner in &catalog.toners {
+        sqlx::query(
+            r#"
+            INSERT INTO toners (id, name, stock, price, brand_id, currency) VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (id) DO UPDATE SET
+                name = EXCLUDED.name,
+                stock = EXCLUDED.stock,
+                price = EXCLUDED.price,
+                brand_id = EXCLUDED.brand_id,
+                currency = EXCLUDED.currency;
+            "#,
+        )
+        .bind(toner.id)
+        .bind(&toner.name)
+        .bind(toner.stock)
+        .bind(toner.price)
+        .bind(toner.brand_id)
+        .bind(&toner.currency)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| {
+            error!("Error importing toner {}: {e}", toner.id);
+            ApiError::DatabaseError(e)
+        })?;
+    }
+
+    for drum in &catalog.drums {
+        sqlx::query(
+            r#"
+            INSERT INTO drums (id, name, stock, price, brand_id, currency) VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (id) DO UPDATE SET
+                name = EXCLUDED.name,
+                stock = EXCLUDED.stock,
+                price = EXCLUDED.price,
+                brand_id = EXCLUDED.brand_id,
+                currency = EXCLUDED.currency;
+            "#,
+        )
+        .bind(drum.id)
+        .bind(&drum.name)
+        .bind(drum.stock)
+        .bind(drum.price)
+        .bind(drum.brand_id)
+        .bind(&drum.currency)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| {
+            error!("Error importing drum {}: {e}", drum.id);
+            ApiError::DatabaseError(e)
+        })?;
+    }
+
+    for printer in &catalog.printers {
+        sqlx::query(
+            r#"
+            INSERT INTO printers (id, name, model, brand, toner, drum, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT (id) DO UPDATE SET
+                name = EXCLUDED.name,
+                model = EXCLUDED.model,
+                brand = EXCLUDED.brand,
+                toner = EXCLUDED.toner,
+                drum = EXCLUDED.drum,
+                updated_at = EXCLUDED.updated_at;
+            "#,
+        )
+        .bind(printer.id)
+        .bind(&printer.name)
+        .bind(&printer.model)
+        .bind(printer.brand)
+        .bind(printer.toner)
+        .bind(printer.drum)
+        .bind(printer.updated_at)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| {
+            error!("Error importing printer {}: {e}", printer.id);
+            ApiError::DatabaseError(e)
+        })?;
+    }
+
+    // Movements are imported as recorded: the stocks above already include them.
+    // Their item type is taken from the imported supplies.
+    for movement in &catalog.movements {
+        sqlx::query(
+            r#"
+            INSERT INTO movements (id, printer_id, item_id, item_type, quantity, kind, created_at, updated_at)
+            VALUES (
+                $1, $2, $3,
+                CASE
+                    WHEN EXISTS (SELECT 1 FROM toners WHERE id = $3) THEN 'toner'::item_type
+                    WHEN EXISTS (SELECT 1 FROM drums WHERE id = $3) THEN 'drum'::item_type
+                END,
+                $4, $5, $6, $7
+            )
+            ON CONFLICT (id) DO UPDATE SET
+                printer_id = EXCLUDED.printer_id,
+                item_id = EXCLUDED.item_id,
+                item_type = EXCLUDED.item_type,
+                quantity = EXCLUDED.quantity,
+                kind = EXCLUDED.kind,
+                created_at = EXCLUDED.created_at,
+                updated_at = EXCLUDED.updated_at;
+            "#,
+        )
+        .bind(movement.id)
+        .bind(movement.printer_id)
+        .bind(movement.item_id)
+        .bind(movement.quantity)
+        .bind(movement.kind)
+        .bind(movement.created_at)
+        .bind(movement.updated_at)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| {
+            error!("Error importing movement {}: {e}", movement.id);
+            ApiError::DatabaseError(e)
+        })?;
+    }
+
+    tx.commit().await.map_err(|e| {
+        error!("Error committing import: {e}");
+        ApiError::DatabaseError(e)
+    })?;
+    state.toner_count.invalidate();
+
+    info!("Catalog imported in {mode:?} mode!");
+    Ok(Json(CatalogCounts::from(&catalog)))
+}
+
+/// Deletes every reservation, movement, printer, brand, toner and drum, children first.
+async fn clear_catalog(tx: &mut Transaction<'_, Postgres>) -> Result<(), ApiError> {
+    for table in [
+        "reservations",
+        "movements",
+        "printers",
+        "brands",
+        "toners",
+        "drums",
+    ] {
+        sqlx::query(&format!("DELETE FROM {table};"))
+            .execute(&mut **tx)
+            .await
+            .map_err(|e| {
+                error!("Error clearing {table}: {e}");
+                ApiError::DatabaseError(e)
+            })?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::{
+        app, as_admin, create_printer, create_toner, empty_request, json_request, send,
+    };
+    use axum::{
+        http::{Method, StatusCode},
+        Router,
+    };
+    use serde_json::{json, Value};
+    use sqlx::PgPool;
+
+    async fn export(app: &Router) -> Value {
+        let (status, catalog) =
+            send(app, as_admin(empty_request(Method::GET, "/api/v1/export"))).await;
+        assert_eq!(status, StatusCode::OK);
+        catalog
+    }
+
+    async fn import(app: &Router, catalog: Value) -> Value {
+        let (status, counts) = send(
+            app,
+            as_admin(json_request(
+                Method::POST,
+                "/api/v1/import?mode=replace",
+                catalog,
+            )),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK, "import failed: {counts}");
+        counts
+    }
+
+    fn counts(catalog: &Value) -> Vec<usize> {
+        ["brands", "toners", "drums", "printers", "movements"]
+            .iter()
+            .map(|entity| catalog[entity].as_array().unwrap().len())
+            .collect()
+    }
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn export_wipe_and_import_restores_the_catalog(pool: PgPool) {
+        let app = app(pool);
+        create_printer(&app, "Backup Printer").await;
+        create_toner(&app, "Backup Toner", 7).await;
+
+        let original = export(&app).await;
+        assert_eq!(counts(&original), vec![1, 2, 1, 1, 1]);
+
+        let empty =
+            json!({ "brands": [], "toners": [], "drums": [], "printers": [], "movements": [] });
+        import(&app, empty).await;
+        assert_eq!(counts(&export(&app).await), vec![0, 0, 0, 0, 0]);
+
+        import(&app, original.clone()).await;
+        assert_eq!(counts(&export(&app).await), counts(&original));
+    }
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn import_requires_the_admin_token(pool: PgPool) {
+        let app = app(pool);
+        let empty =
+            json!({ "brands": [], "toners": [], "drums": [], "printers": [], "movements": [] });
+
+        let (status, _) = send(
+            &app,
+            json_request(Method::POST, "/api/v1/import?mode=replace", empty),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::FORBIDDEN);
+    }
+}
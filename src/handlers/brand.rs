@@ -1,19 +1,33 @@
 use crate::{
     errors::api_error::ApiError,
+    extractors::json::Json,
+    extractors::path::Path,
+    extractors::prefer::ReturnPreference,
     models::{
-        brand::{Brand, CreateBrandRequest, UpdateBrandRequest},
-        DeleteRequest,
+        brand::{
+            AssignPrintersRequest, Brand, BrandImportLine, BrandImportOutcome, BrandImportSummary,
+            BrandListParams, BrandWithCount, CreateBrandRequest, UpdateBrandRequest,
+        },
+        CompactItem, CompactParams, DeleteRequest, ExistsRequest, ReturnParams,
+    },
+    routes::location,
+    validations::{
+        existence::brand_exists,
+        uniqueness::{
+            conflict_or_database_error, is_brand_name_available, is_brand_unique, reject_taken_name,
+        },
     },
-    validations::{existence::brand_exists, uniqueness::is_brand_unique},
 };
 use axum::{
-    extract::{Path, State},
+    extract::{Query, State},
     http::StatusCode,
     response::IntoResponse,
-    Json,
 };
 use infra::database::AppState;
-use std::sync::Arc;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
 use tracing::{error, info};
 use uuid::Uuid;
 use validator::Validate;
@@ -70,7 +84,7 @@ pub async fn count_brands(
 pub async fn search_brand(
     Path(id): Path<Uuid>,
     State(state): State<Arc<AppState>>,
-) -> impl IntoResponse {
+) -> Result<impl IntoResponse, ApiError> {
     let brand = sqlx::query_as::<_, Brand>(r#"SELECT * FROM brands WHERE id = $1;"#)
         .bind(id)
         .fetch_optional(&state.db)
@@ -87,21 +101,102 @@ pub async fn search_brand(
         }
         None => {
             error!("No brand found with id: {id}");
-            Err(ApiError::IdNotFound)
+            Err(ApiError::IdNotFound { resource: "Brand" })
         }
     }
 }
 
+/// Checks whether a brand exists.
+///
+/// This endpoint answers with an empty body, using an existence query instead of fetching the brand.
+/// Useful for clients that only need to know whether the ID is valid.
+#[utoipa::path(
+    head,
+    path = "/api/v1/brands/{id}",
+    tags = ["Brands"],
+    summary = "Check whether a brand exists.",
+    description = "This endpoint checks whether a brand with the given ID exists, without returning its details.",
+    params(
+        ("id", description = "The unique identifier of the brand to check", example = "550e8400-e29b-41d4-a716-446655440000")
+    ),
+    responses(
+        (status = 200, description = "Brand exists"),
+        (status = 404, description = "No brand found with the specified ID"),
+        (status = 500, description = "An error occurred while checking the brand")
+    )
+)]
+pub async fn check_brand(
+    Path(id): Path<Uuid>,
+    State(state): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, ApiError> {
+    brand_exists(state, id).await?;
+
+    Ok(StatusCode::OK)
+}
+
+/// Checks which of several brands exist.
+///
+/// This endpoint looks up all the given IDs in a single query, so clients can validate them before bulk operations.
+/// Returns an object mapping each ID to whether a brand with that ID exists.
+#[utoipa::path(
+    post,
+    path = "/api/v1/brands/exists",
+    tags = ["Brands"],
+    summary = "Check whether several brands exist.",
+    description = "This endpoint answers with an object holding `true` or `false` for each of the given brand IDs. At most 1000 IDs can be checked at once.",
+    request_body = ExistsRequest,
+    responses(
+        (status = 200, description = "Existence checked successfully", body = HashMap<String, bool>),
+        (status = 400, description = "Too many IDs"),
+        (status = 500, description = "An error occurred while checking the brands")
+    )
+)]
+pub async fn check_brands(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<ExistsRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    // Validations
+    request.validate()?;
+
+    let existing: HashSet<Uuid> =
+        sqlx::query_scalar::<_, Uuid>(r#"SELECT id FROM brands WHERE id = ANY($1);"#)
+            .bind(&request.ids)
+            .fetch_all(&state.db)
+            .await
+            .map_err(|e| {
+                error!("Error checking brand IDs: {e}");
+                ApiError::DatabaseError(e)
+            })?
+            .into_iter()
+            .collect();
+
+    let exists: HashMap<Uuid, bool> = request
+        .ids
+        .iter()
+        .map(|id| (*id, existing.contains(id)))
+        .collect();
+
+    info!(
+        "Checked {} brand IDs, {} exist",
+        exists.len(),
+        existing.len()
+    );
+    Ok(Json(exists))
+}
+
 /// Retrieves a list of all brands.
 ///
 /// This endpoint fetches all brands stored in the database.
 /// If there are no brands, returns an empty array.
+/// When `with_counts` is set, each brand also reports how many printers it has,
+/// and with `compact` only the IDs and names are listed.
 #[utoipa::path(
     get,
     path = "/api/v1/brands",
     tags = ["Brands"],
     summary = "List all brands.",
-    description = "Fetches all brands stored in the database. If there are no brands, returns an empty array.",
+    description = "Fetches all brands stored in the database. If there are no brands, returns an empty array. With `with_counts=true`, each brand includes its `printer_count` (see `BrandWithCount`). With `compact=true` each brand is a `CompactItem` holding only its `id` and `name`, and `with_counts` is ignored.",
+    params(BrandListParams, CompactParams),
     responses(
         (status = 200, description = "Brands retrieved successfully", body = Vec<Brand>),
         (status = 404, description = "No brands found in the database"),
@@ -109,8 +204,44 @@ pub async fn search_brand(
     )
 )]
 pub async fn show_brands(
+    Query(params): Query<BrandListParams>,
+    Query(compact): Query<CompactParams>,
     State(state): State<Arc<AppState>>,
 ) -> Result<impl IntoResponse, ApiError> {
+    if compact.compact.unwrap_or(false) {
+        let brands =
+            sqlx::query_as::<_, CompactItem>(r#"SELECT id, name FROM brands ORDER BY name;"#)
+                .fetch_all(&state.db)
+                .await
+                .map_err(|e| {
+                    error!("Error listing compact brands: {e}");
+                    ApiError::DatabaseError(e)
+                })?;
+
+        info!("Brands listed successfully in compact form");
+        return Ok(Json(brands).into_response());
+    }
+
+    if params.with_counts.unwrap_or(false) {
+        let brands = sqlx::query_as::<_, BrandWithCount>(
+            r#"
+            SELECT b.id, b.name, b.updated_at, COUNT(p.id) AS printer_count
+            FROM brands b
+            LEFT JOIN printers p ON p.brand = b.id
+            GROUP BY b.id, b.name;
+            "#,
+        )
+        .fetch_all(&state.db)
+        .await
+        .map_err(|e| {
+            error!("Error listing brands with printer counts: {e}");
+            ApiError::DatabaseError(e)
+        })?;
+
+        info!("Brands listed successfully with printer counts");
+        return Ok(Json(brands).into_response());
+    }
+
     let brands = sqlx::query_as::<_, Brand>(r#"SELECT * FROM brands;"#)
         .fetch_all(&state.db)
         .await
@@ -120,7 +251,7 @@ pub async fn show_brands(
         })?;
 
     info!("Brands listed successfully");
-    Ok(Json(brands))
+    Ok(Json(brands).into_response())
 }
 
 /// Create a new brand.
@@ -135,35 +266,236 @@ pub async fn show_brands(
     summary = "Create a new brand.",
     description = "This endpoint creates a new brand in the database with the provided details.",
     request_body = CreateBrandRequest,
+    params(
+        ReturnParams,
+        ("Prefer" = Option<String>, Header, description = "`return=representation` returns the created brand instead of its ID")
+    ),
     responses(
-        (status = 201, description = "Brand created successfully", body = Uuid),
+        (status = 201, description = "Brand created successfully. Answers with the full brand instead of its ID when `return=representation` is requested", body = Uuid),
         (status = 400, description = "Invalid input, including empty name or name too short/long"),
         (status = 409, description = "Conflict: Brand with the same name already exists"),
         (status = 500, description = "An error occurred while creating the brand")
     )
 )]
 pub async fn create_brand(
+    preference: ReturnPreference,
     State(state): State<Arc<AppState>>,
     Json(request): Json<CreateBrandRequest>,
 ) -> Result<impl IntoResponse, ApiError> {
     // Validations
     request.validate()?;
-    is_brand_unique(state.clone(), request.name.clone()).await?;
+    let existing_id = is_brand_unique(state.clone(), request.name.clone()).await?;
+    reject_taken_name(request.name.clone(), existing_id)?;
 
     let new_brand = Brand::new(&request.name);
 
     // Creates the brand.
-    sqlx::query(r#"INSERT INTO brands (id, name) VALUES ($1, $2)"#)
-        .bind(new_brand.id)
-        .bind(&new_brand.name)
-        .execute(&state.db)
-        .await
-        .map_err(|e| {
+    let result = sqlx::query_as::<_, Brand>(
+        r#"INSERT INTO brands (id, name, updated_at) VALUES ($1, $2, $3) RETURNING id, name, updated_at"#,
+    )
+    .bind(new_brand.id)
+    .bind(&new_brand.name)
+    .bind(new_brand.updated_at)
+    .fetch_one(&state.db)
+    .await;
+
+    let created = match result {
+        Ok(brand) => brand,
+        Err(e) => {
             error!("Error creating brand: {e}");
-            ApiError::DatabaseError(e)
-        })?;
+            let check = is_brand_unique(state.clone(), request.name.clone());
+            return Err(conflict_or_database_error(e, request.name, check).await);
+        }
+    };
     info!("Brand created! ID: {}", &new_brand.id);
-    Ok((StatusCode::CREATED, Json(new_brand.id)))
+    Ok((
+        StatusCode::CREATED,
+        location(&format!("/brands/{}", new_brand.id)),
+        preference.body(new_brand.id, Some(created)),
+    ))
+}
+
+/// Imports brands from a list of names.
+///
+/// This endpoint creates a brand for each line of a plain text body, within a single transaction.
+/// Blank lines are ignored, and names of existing or repeated brands are skipped.
+#[utoipa::path(
+    post,
+    path = "/api/v1/brands/import",
+    tags = ["Brands"],
+    summary = "Import brands from a list of names.",
+    description = "This endpoint creates one brand per line of the `text/plain` body. Blank lines are ignored, and names that already exist or are repeated in the body are skipped, including brands created by a concurrent import. The outcome of each line is reported. A name that fails validation rejects the whole import.",
+    request_body(content = String, content_type = "text/plain"),
+    responses(
+        (status = 201, description = "Brands imported successfully", body = BrandImportSummary),
+        (status = 400, description = "Invalid input, including a name too short/long"),
+        (status = 500, description = "An error occurred while importing the brands")
+    )
+)]
+pub async fn import_brands(
+    State(state): State<Arc<AppState>>,
+    body: String,
+) -> Result<impl IntoResponse, ApiError> {
+    let names: Vec<(usize, &str)> = body
+        .lines()
+        .map(str::trim)
+        .enumerate()
+        .filter(|(_, name)| !name.is_empty())
+        .map(|(index, name)| (index + 1, name))
+        .collect();
+
+    // Validations
+    for (_, name) in &names {
+        CreateBrandRequest {
+            name: String::from(*name),
+        }
+        .validate()?;
+    }
+
+    let mut tx = state.db.begin().await.map_err(|e| {
+        error!("Error starting transaction: {e}");
+        ApiError::DatabaseError(e)
+    })?;
+
+    let mut created: Vec<Brand> = Vec::new();
+    let mut skipped: Vec<String> = Vec::new();
+    let mut lines: Vec<BrandImportLine> = Vec::new();
+    let mut seen: HashSet<&str> = HashSet::new();
+
+    for (line, name) in names {
+        let outcome = if !seen.insert(name) {
+            BrandImportOutcome::Repeated
+        } else {
+            let new_brand = Brand::new(name);
+
+            // Existing names, including ones committed by a concurrent import, are left
+            // untouched instead of aborting the transaction with a unique violation
+            let result = sqlx::query(
+                r#"INSERT INTO brands (id, name, updated_at) VALUES ($1, $2, $3)
+                ON CONFLICT (name) DO NOTHING"#,
+            )
+            .bind(new_brand.id)
+            .bind(&new_brand.name)
+            .bind(new_brand.updated_at)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| {
+                error!("Error importing brand: {e}");
+                ApiError::DatabaseError(e)
+            })?;
+
+            if result.rows_affected() == 0 {
+                BrandImportOutcome::Exists
+            } else {
+                created.push(new_brand);
+                BrandImportOutcome::Created
+            }
+        };
+
+        if outcome != BrandImportOutcome::Created {
+            skipped.push(String::from(name));
+        }
+        lines.push(BrandImportLine {
+            line,
+            name: String::from(name),
+            outcome,
+        });
+    }
+
+    tx.commit().await.map_err(|e| {
+        error!("Error committing brand import: {e}");
+        ApiError::DatabaseError(e)
+    })?;
+
+    info!(
+        "Brands imported! {} created, {} skipped",
+        created.len(),
+        skipped.len()
+    );
+    Ok((
+        StatusCode::CREATED,
+        Json(BrandImportSummary {
+            created,
+            skipped,
+            lines,
+        }),
+    ))
+}
+
+/// Assigns a brand to several printers at once.
+///
+/// This endpoint sets the brand of every listed printer within a single transaction.
+/// If any printer does not exist, nothing is changed and the unknown IDs are reported.
+#[utoipa::path(
+    post,
+    path = "/api/v1/brands/{id}/assign-printers",
+    tags = ["Brands"],
+    summary = "Assign a brand to several printers.",
+    description = "This endpoint updates the brand of all the listed printers in a single transaction and returns the number of printers updated. Unknown printer IDs reject the whole request and are listed in the error details.",
+    params(
+        ("id", description = "The unique identifier of the brand to assign", example = "550e8400-e29b-41d4-a716-446655440000")
+    ),
+    request_body = AssignPrintersRequest,
+    responses(
+        (status = 200, description = "Printers updated successfully", body = u64),
+        (status = 400, description = "Invalid input, including an empty list of printers"),
+        (status = 404, description = "Brand or printer IDs not found"),
+        (status = 500, description = "An error occurred while assigning the brand")
+    )
+)]
+pub async fn assign_printers(
+    Path(id): Path<Uuid>,
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<AssignPrintersRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    // Validations
+    request.validate()?;
+    brand_exists(state.clone(), id).await?;
+
+    let mut tx = state.db.begin().await.map_err(|e| {
+        error!("Error starting transaction: {e}");
+        ApiError::DatabaseError(e)
+    })?;
+
+    let unknown_ids = sqlx::query_scalar::<_, Uuid>(
+        r#"
+        SELECT ids.id
+        FROM UNNEST($1::uuid[]) AS ids(id)
+        WHERE NOT EXISTS(SELECT 1 FROM printers p WHERE p.id = ids.id);
+        "#,
+    )
+    .bind(&request.printer_ids)
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|e| {
+        error!("Error fetching printers by ID: {e}");
+        ApiError::DatabaseError(e)
+    })?;
+
+    if !unknown_ids.is_empty() {
+        error!("Printers not found: {unknown_ids:?}");
+        return Err(ApiError::IdsNotFound(unknown_ids));
+    }
+
+    let updated =
+        sqlx::query(r#"UPDATE printers SET brand = $1, updated_at = NOW() WHERE id = ANY($2);"#)
+            .bind(id)
+            .bind(&request.printer_ids)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| {
+                error!("Error assigning brand to printers: {e}");
+                ApiError::DatabaseError(e)
+            })?
+            .rows_affected();
+
+    tx.commit().await.map_err(|e| {
+        error!("Error committing brand assignment: {e}");
+        ApiError::DatabaseError(e)
+    })?;
+
+    info!("Brand {id} assigned to {updated} printers");
+    Ok(Json(updated))
 }
 
 /// Updates an existing brand.
@@ -198,21 +530,94 @@ pub async fn update_brand(
 
     let brand_id = request.id;
     let new_name = request.name;
+    let existing_id = is_brand_name_available(state.clone(), new_name.clone(), brand_id).await?;
+    reject_taken_name(new_name.clone(), existing_id)?;
 
     // Update the brand
-    sqlx::query(r#"UPDATE brands SET name = $1 WHERE id = $2;"#)
+    let result = sqlx::query(r#"UPDATE brands SET name = $1, updated_at = NOW() WHERE id = $2;"#)
         .bind(&new_name)
         .bind(brand_id)
         .execute(&state.db)
-        .await
-        .map_err(|e| {
-            error!("Error updating brand name: {e}");
-            ApiError::DatabaseError(e)
-        })?;
+        .await;
+
+    if let Err(e) = result {
+        error!("Error updating brand name: {e}");
+        let check = is_brand_name_available(state.clone(), new_name.clone(), brand_id);
+        return Err(conflict_or_database_error(e, new_name, check).await);
+    }
     info!("Brand updated! ID: {}", &brand_id);
     Ok((StatusCode::OK, Json(brand_id)).into_response())
 }
 
+/// Creates or updates a brand with the given ID.
+///
+/// This endpoint renames the brand with the ID in the path, or creates it with that exact ID if it does not exist.
+/// Repeating the same request leaves the brand unchanged, which makes it safe for sync tooling.
+#[utoipa::path(
+    put,
+    path = "/api/v1/brands/{id}",
+    tags = ["Brands"],
+    summary = "Create or update a brand by ID.",
+    description = "This endpoint updates the name of the brand with the given ID, or creates the brand with that ID if it does not exist.",
+    params(
+        ("id", description = "The unique identifier of the brand to create or update", example = "550e8400-e29b-41d4-a716-446655440000")
+    ),
+    request_body = CreateBrandRequest,
+    responses(
+        (status = 200, description = "Brand updated successfully", body = Uuid),
+        (status = 201, description = "Brand created successfully", body = Uuid),
+        (status = 400, description = "Invalid input, including empty name or name too short/long"),
+        (status = 409, description = "Conflict: Another brand with the same name already exists"),
+        (status = 500, description = "An error occurred while saving the brand")
+    )
+)]
+pub async fn upsert_brand(
+    Path(id): Path<Uuid>,
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<CreateBrandRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    // Validations
+    request.validate()?;
+    let existing_id = is_brand_name_available(state.clone(), request.name.clone(), id).await?;
+    reject_taken_name(request.name.clone(), existing_id)?;
+
+    // Creates the brand, or renames it if the ID is taken.
+    // `xmax` is only zero for freshly inserted rows.
+    let created = sqlx::query_scalar::<_, bool>(
+        r#"
+        INSERT INTO brands (id, name, updated_at) VALUES ($1, $2, NOW())
+        ON CONFLICT (id) DO UPDATE SET name = EXCLUDED.name, updated_at = NOW()
+        RETURNING (xmax = 0) AS created;
+        "#,
+    )
+    .bind(id)
+    .bind(&request.name)
+    .fetch_one(&state.db)
+    .await;
+
+    let created = match created {
+        Ok(created) => created,
+        Err(e) => {
+            error!("Error saving brand: {e}");
+            let check = is_brand_name_available(state.clone(), request.name.clone(), id);
+            return Err(conflict_or_database_error(e, request.name, check).await);
+        }
+    };
+
+    if created {
+        info!("Brand created! ID: {id}");
+        Ok((
+            StatusCode::CREATED,
+            location(&format!("/brands/{id}")),
+            Json(id),
+        )
+            .into_response())
+    } else {
+        info!("Brand updated! ID: {id}");
+        Ok((StatusCode::OK, Json(id)).into_response())
+    }
+}
+
 /// Deletes an existing brand.
 ///
 /// This endpoint allows users to delete a specific brand by its ID.
@@ -251,3 +656,316 @@ pub async fn delete_brand(
     info!("Brand deleted! ID: {}", &request.id);
     Ok((StatusCode::OK, Json("Brand deleted!")).into_response())
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::{
+        app, create_brand, create_drum, create_printer, create_toner, empty_request, json_request,
+        send,
+    };
+    use axum::http::{Method, StatusCode};
+    use serde_json::json;
+    use sqlx::PgPool;
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn renaming_to_a_taken_name_conflicts(pool: PgPool) {
+        let app = app(pool);
+        let taken = create_brand(&app, "Brother").await;
+        let renamed = create_brand(&app, "Canon").await;
+
+        let (status, body) = send(
+            &app,
+            json_request(
+                Method::PUT,
+                "/api/v1/brands",
+                json!({ "id": renamed, "name": "Brother" }),
+            ),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(body["code"], "ALREADY_EXISTS");
+        assert!(body["details"]
+            .as_str()
+            .unwrap()
+            .contains(&taken.to_string()));
+    }
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn creating_a_taken_name_reports_the_existing_brand(pool: PgPool) {
+        let app = app(pool);
+        let existing = create_brand(&app, "Epson").await;
+
+        let (status, body) = send(
+            &app,
+            json_request(Method::POST, "/api/v1/brands", json!({ "name": "Epson" })),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(body["code"], "ALREADY_EXISTS");
+        assert_eq!(body["id"], existing.to_string());
+        assert_eq!(body["name"], "Epson");
+    }
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn compact_brands_are_listed_by_name(pool: PgPool) {
+        let app = app(pool);
+        for name in ["Xerox", "Brother", "Lexmark"] {
+            create_brand(&app, name).await;
+        }
+
+        let (status, body) = send(
+            &app,
+            empty_request(Method::GET, "/api/v1/brands?compact=true"),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        let names: Vec<_> = body
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|brand| brand["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, ["Brother", "Lexmark", "Xerox"]);
+        assert!(body[0].get("updated_at").is_none());
+    }
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn names_longer_than_the_column_are_rejected_before_the_insert(pool: PgPool) {
+        let app = app(pool);
+        let longest = "a".repeat(50);
+
+        let create =
+            |name: String| json_request(Method::POST, "/api/v1/brands", json!({ "name": name }));
+
+        let (status, body) = send(&app, create(format!("{longest} "))).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(body["details"].as_str().unwrap().contains("NAME_TOO_LONG"));
+
+        let (status, _) = send(&app, create(longest)).await;
+        assert_eq!(status, StatusCode::CREATED);
+    }
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn brands_are_listed_with_their_printer_count_on_request(pool: PgPool) {
+        let app = app(pool);
+        let brand = create_brand(&app, "Kyocera").await;
+        create_brand(&app, "Ricoh").await;
+        let toner = create_toner(&app, "TK-1170", 0).await;
+        let drum = create_drum(&app, "DK-1150", 0).await;
+        for name in ["ECOSYS M2040", "ECOSYS M2540"] {
+            let (status, _) = send(
+                &app,
+                json_request(
+                    Method::POST,
+                    "/api/v1/printers",
+                    json!({ "name": name, "model": "M2040dn", "brand": brand, "toner": toner, "drum": drum }),
+                ),
+            )
+            .await;
+            assert_eq!(status, StatusCode::CREATED);
+        }
+
+        let (status, brands) = send(
+            &app,
+            empty_request(Method::GET, "/api/v1/brands?with_counts=true"),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let count_of = |name: &str| {
+            brands
+                .as_array()
+                .unwrap()
+                .iter()
+                .find(|brand| brand["name"] == name)
+                .map(|brand| brand["printer_count"].clone())
+        };
+        assert_eq!(count_of("Kyocera"), Some(json!(2)));
+        assert_eq!(count_of("Ricoh"), Some(json!(0)));
+
+        let (_, brands) = send(&app, empty_request(Method::GET, "/api/v1/brands")).await;
+        assert!(brands[0].get("printer_count").is_none());
+    }
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn updates_advance_the_updated_at(pool: PgPool) {
+        let app = app(pool);
+        let brand = create_brand(&app, "Brother").await;
+        let updated_at = |brand: serde_json::Value| {
+            brand["updated_at"]
+                .as_str()
+                .unwrap()
+                .parse::<chrono::DateTime<chrono::Utc>>()
+                .unwrap()
+        };
+
+        let (_, created) = send(
+            &app,
+            empty_request(Method::GET, &format!("/api/v1/brands/{brand}")),
+        )
+        .await;
+        let (status, _) = send(
+            &app,
+            json_request(
+                Method::PUT,
+                "/api/v1/brands",
+                json!({ "id": brand, "name": "Brother Industries" }),
+            ),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let (_, updated) = send(
+            &app,
+            empty_request(Method::GET, &format!("/api/v1/brands/{brand}")),
+        )
+        .await;
+
+        assert_eq!(updated["name"], "Brother Industries");
+        assert!(updated_at(updated) > updated_at(created));
+    }
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn putting_an_unknown_id_creates_the_brand_with_it(pool: PgPool) {
+        let app = app(pool);
+        let id = "550e8400-e29b-41d4-a716-446655440000";
+
+        let (status, created) = send(
+            &app,
+            json_request(
+                Method::PUT,
+                &format!("/api/v1/brands/{id}"),
+                json!({ "name": "Brother" }),
+            ),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(created, id);
+
+        let (status, brand) = send(
+            &app,
+            empty_request(Method::GET, &format!("/api/v1/brands/{id}")),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(brand["name"], "Brother");
+    }
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn putting_a_known_id_renames_the_brand(pool: PgPool) {
+        let app = app(pool);
+        let brand = create_brand(&app, "Brother").await;
+        create_brand(&app, "Canon").await;
+        let put = |name: &str| {
+            json_request(
+                Method::PUT,
+                &format!("/api/v1/brands/{brand}"),
+                json!({ "name": name }),
+            )
+        };
+
+        let (status, updated) = send(&app, put("Brother Industries")).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(updated, json!(brand));
+        let (_, renamed) = send(
+            &app,
+            empty_request(Method::GET, &format!("/api/v1/brands/{brand}")),
+        )
+        .await;
+        assert_eq!(renamed["name"], "Brother Industries");
+
+        let (status, _) = send(&app, put("Canon")).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+    }
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn importing_skips_blank_lines_and_existing_brands(pool: PgPool) {
+        let app = app(pool);
+        create_brand(&app, "Canon").await;
+        let request = axum::http::Request::builder()
+            .method(Method::POST)
+            .uri("/api/v1/brands/import")
+            .header(axum::http::header::CONTENT_TYPE, "text/plain")
+            .body(axum::body::Body::from("Brother\n\nCanon\nRicoh\n"))
+            .unwrap();
+
+        let (status, summary) = send(&app, request).await;
+
+        assert_eq!(status, StatusCode::CREATED);
+        let created: Vec<_> = summary["created"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|brand| brand["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(created, ["Brother", "Ricoh"]);
+        assert_eq!(summary["skipped"], json!(["Canon"]));
+        assert_eq!(
+            summary["lines"][1],
+            json!({ "line": 3, "name": "Canon", "outcome": "exists" })
+        );
+    }
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn printers_can_be_reassigned_to_a_brand(pool: PgPool) {
+        let app = app(pool);
+        let brand = create_brand(&app, "Kyocera").await;
+        let first = create_printer(&app, "ECOSYS M2040").await;
+        let second = create_printer(&app, "ECOSYS M2540").await;
+        let assign = |printer_ids: serde_json::Value| {
+            json_request(
+                Method::POST,
+                &format!("/api/v1/brands/{brand}/assign-printers"),
+                json!({ "printer_ids": printer_ids }),
+            )
+        };
+
+        let unknown = "550e8400-e29b-41d4-a716-446655440000";
+        let (status, body) = send(&app, assign(json!([first, unknown]))).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert!(body["details"].as_str().unwrap().contains(unknown));
+
+        let (status, updated) = send(&app, assign(json!([first, second]))).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(updated, 2);
+        for printer in [first, second] {
+            let (_, details) = send(
+                &app,
+                empty_request(Method::GET, &format!("/api/v1/printers/{printer}")),
+            )
+            .await;
+            assert_eq!(details["brand"]["id"], json!(brand));
+        }
+    }
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn repeated_and_existing_names_are_skipped_on_import(pool: PgPool) {
+        let app = app(pool);
+        create_brand(&app, "Brother").await;
+        let request = axum::http::Request::builder()
+            .method(Method::POST)
+            .uri("/api/v1/brands/import")
+            .header(axum::http::header::CONTENT_TYPE, "text/plain")
+            .body(axum::body::Body::from("Kyocera\n\nKyocera\nBrother\n"))
+            .unwrap();
+
+        let (status, summary) = send(&app, request).await;
+
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(summary["created"].as_array().unwrap().len(), 1);
+        assert_eq!(summary["created"][0]["name"], "Kyocera");
+        assert_eq!(summary["skipped"], json!(["Kyocera", "Brother"]));
+        assert_eq!(
+            summary["lines"],
+            json!([
+                { "line": 1, "name": "Kyocera", "outcome": "created" },
+                { "line": 3, "name": "Kyocera", "outcome": "repeated" },
+                { "line": 4, "name": "Brother", "outcome": "exists" },
+            ])
+        );
+
+        let (_, brands) = send(&app, empty_request(Method::GET, "/api/v1/brands")).await;
+        assert_eq!(brands.as_array().unwrap().len(), 2);
+    }
+}
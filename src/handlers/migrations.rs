@@ -1,39 +1,106 @@
-use crate::errors::api_error::ApiError;
+use crate::{errors::api_error::ApiError, models::migration::MigrationReport};
 use axum::{extract::State, response::IntoResponse, Json};
 use infra::database::AppState;
-use sqlx::migrate;
+use sqlx::{
+    migrate::{Migrate, MigrateError, Migrator},
+    PgConnection,
+};
 use std::sync::Arc;
 use tracing::{error, info};
 
+static MIGRATOR: Migrator = sqlx::migrate!("./libs/infra/src/database/migrations");
+
+/// Maps a migration failure, reporting changed migration files as a conflict.
+fn migration_error(e: MigrateError) -> ApiError {
+    error!("Error applying migrations: {e}");
+    match e {
+        MigrateError::VersionMismatch(version) => ApiError::MigrationChanged(version),
+        e => ApiError::DatabaseError(e.into()),
+    }
+}
+
+/// Lists the versions of the migrations not applied yet, in order.
+pub async fn pending_migrations(conn: &mut PgConnection) -> Result<Vec<i64>, MigrateError> {
+    let applied = conn.list_applied_migrations().await?;
+
+    Ok(MIGRATOR
+        .iter()
+        .filter(|m| !m.migration_type.is_down_migration())
+        .filter(|m| !applied.iter().any(|a| a.version == m.version))
+        .map(|m| m.version)
+        .collect())
+}
+
 pub async fn dry_run() {
     todo!("Dry run mode is planned but has not been implemented yet.");
 }
 
 /// Executes pending database migrations.
 ///
-/// This endpoint allows users to apply any pending database migrations.
-/// It checks for migrations that need to be applied and executes them.
-/// If the migrations are applied successfully, a confirmation message is returned.
+/// This endpoint applies only the migrations that have not been run yet, so calling it again is safe.
+/// It returns the versions applied by this run, or reports that the database was already up to date.
+/// If an applied migration file was changed afterwards, nothing is applied and a conflict is returned.
 #[utoipa::path(
     post,
     path = "/api/v1/migrations",
     tags = ["Migrations"],
     summary = "Execute pending database migrations.",
-    description = "This endpoint executes any pending migrations in the database. It applies migrations that have not yet been run and provides confirmation upon success.",
+    description = "This endpoint executes the migrations that have not yet been applied and returns their versions. Running it on an up-to-date database applies nothing and sets `already_up_to_date`.",
     responses(
-        (status = 200, description = "Migrations applied successfully", body = String),
+        (status = 200, description = "Migrations applied successfully", body = MigrationReport),
+        (status = 409, description = "An applied migration was modified"),
         (status = 500, description = "An error occurred while applying migrations")
     )
 )]
 pub async fn live_run(State(state): State<Arc<AppState>>) -> Result<impl IntoResponse, ApiError> {
-    migrate!("./libs/infra/src/database/migrations")
-        .run(&state.db)
+    let mut conn = state.db.acquire().await.map_err(|e| {
+        error!("Error acquiring a connection for migrations: {e}");
+        ApiError::DatabaseError(e)
+    })?;
+
+    conn.ensure_migrations_table()
+        .await
+        .map_err(migration_error)?;
+    let pending = pending_migrations(&mut conn)
         .await
-        .map_err(|e| {
-            error!("Error applying migrations: {e}");
-            ApiError::DatabaseError(e.into())
-        })?;
+        .map_err(migration_error)?;
+    drop(conn);
+
+    MIGRATOR.run(&state.db).await.map_err(migration_error)?;
+
+    if pending.is_empty() {
+        info!("No pending migrations to apply");
+    } else {
+        info!("Migrations applied successfully: {pending:?}");
+    }
+
+    Ok(Json(MigrationReport {
+        already_up_to_date: pending.is_empty(),
+        applied: pending,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::{app, empty_request, send};
+    use axum::http::{Method, StatusCode};
+    use serde_json::json;
+    use sqlx::PgPool;
+
+    #[sqlx::test(migrations = false)]
+    async fn a_second_run_is_already_up_to_date(pool: PgPool) {
+        let app = app(pool);
+
+        let (status, first) = send(&app, empty_request(Method::POST, "/api/v1/migrations")).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(first["already_up_to_date"], false);
+        assert_eq!(
+            first["applied"].as_array().unwrap().len(),
+            super::MIGRATOR.iter().count()
+        );
 
-    info!("Migrations applied successfully!");
-    Ok(Json("Migrations applied successfully!"))
+        let (status, second) = send(&app, empty_request(Method::POST, "/api/v1/migrations")).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(second, json!({ "applied": [], "already_up_to_date": true }));
+    }
 }
@@ -1,6 +1,9 @@
+pub mod backup;
 pub mod brand;
 pub mod migrations;
 pub mod movement;
 pub mod printer;
+pub mod reports;
+pub mod search;
 pub mod status;
 pub mod supplies;
@@ -1,45 +1,104 @@
 use crate::{
     errors::api_error::ApiError,
+    extractors::admin::Admin,
+    extractors::json::Json,
+    extractors::path::Path,
     models::{
         movement::{
-            CreateMovementRequest, ItemDetails, Movement, MovementDetails, MovementView,
-            PrinterDetails, UpdateMovementRequest,
+            CreateMovementRequest, CreatedMovement, ItemType, Movement, MovementCountParams,
+            MovementDetails, MovementFilterParams, MovementView, PurgeParams, PurgeResult,
+            UpdateMovementRequest,
         },
         DeleteRequest,
     },
-    validations::existence::movement_exists,
+    routes::location,
+    validations::{
+        consumption::check_consumption_rate, existence::movement_exists, timezone::parse_utc_offset,
+    },
+    webhooks::{notify_stock_change, StockChangeEvent},
 };
 use axum::{
-    extract::{Path, State},
-    http::StatusCode,
+    body::Body,
+    extract::{Query, State},
+    http::{header, StatusCode},
     response::IntoResponse,
-    Json,
 };
+use chrono::{DateTime, Offset, Utc};
+use config::Config;
 use infra::database::AppState;
-use std::{str::FromStr, sync::Arc};
+use sqlx::{Postgres, Transaction};
+use std::{path::PathBuf, str::FromStr, sync::Arc};
+use tokio::{fs, io::AsyncWriteExt, sync::mpsc};
+use tokio_stream::{wrappers::ReceiverStream, StreamExt};
 use tracing::{error, info};
 use uuid::Uuid;
 use validator::Validate;
 
+/// Movements deleted per statement while purging, so no single statement holds locks for long.
+const PURGE_BATCH_SIZE: i64 = 1000;
+
+/// Movements with their printer and item details, filtered by the optional
+/// `from` (`$1`), `to` (`$2`) and `printer_id` (`$3`) parameters.
+const FILTERED_MOVEMENTS_QUERY: &str = r#"
+    SELECT 
+        m.id AS movement_id,
+        p.id AS printer_id,
+        p.name AS printer_name,
+        p.model AS printer_model,
+        CASE
+            WHEN t.id IS NOT NULL THEN t.id
+            ELSE d.id
+        END AS item_id,
+        CASE
+            WHEN t.id IS NOT NULL THEN t.name
+            ELSE d.name
+        END AS item_name,
+        m.quantity AS quantity,
+        m.kind AS kind,
+        m.created_at AS created_at,
+        m.updated_at AS updated_at
+    FROM movements m
+    LEFT JOIN printers p ON m.printer_id = p.id
+    LEFT JOIN toners t ON m.item_id = t.id
+    LEFT JOIN drums d ON m.item_id = d.id
+    WHERE ($1::timestamptz IS NULL OR m.created_at >= $1)
+        AND ($2::timestamptz IS NULL OR m.created_at <= $2)
+        AND ($3::uuid IS NULL OR m.printer_id = $3)
+    ORDER BY m.created_at
+"#;
+
 /// Retrieves the total count of movements.
 ///
 /// This endpoint counts all movements stored in the database and returns the count as an integer.
+/// With `item_type`, only the movements whose item is a toner or a drum are counted.
 /// If no movements are found, 0 is returned.
 #[utoipa::path(
     get,
     path = "/api/v1/movements/count",
     tags = ["Movements"],
     summary = "Get the total count of movements.",
-    description = "This endpoint retrieves the total number of movements stored in the database.",
+    description = "This endpoint retrieves the total number of movements stored in the database, optionally only those of toners or of drums.",
+    params(MovementCountParams),
     responses(
         (status = 200, description = "Movement count retrieved successfully", body = i32),
         (status = 500, description = "An error occurred while retrieving the movement count")
     )
 )]
 pub async fn count_movements(
+    Query(params): Query<MovementCountParams>,
     State(state): State<Arc<AppState>>,
 ) -> Result<impl IntoResponse, ApiError> {
-    let count = sqlx::query_scalar::<_, i64>(r#"SELECT COUNT(*) FROM movements;"#)
+    let count_query = match params.item_type {
+        None => r#"SELECT COUNT(*) FROM movements;"#,
+        Some(ItemType::Toner) => {
+            r#"SELECT COUNT(*) FROM movements m WHERE EXISTS (SELECT 1 FROM toners t WHERE t.id = m.item_id);"#
+        }
+        Some(ItemType::Drum) => {
+            r#"SELECT COUNT(*) FROM movements m WHERE EXISTS (SELECT 1 FROM drums d WHERE d.id = m.item_id);"#
+        }
+    };
+
+    let count = sqlx::query_scalar::<_, i64>(count_query)
         .fetch_one(&state.db)
         .await
         .map_err(|e| {
@@ -90,9 +149,11 @@ pub async fn search_movement(
                 ELSE d.name
             END AS item_name,
             m.quantity AS quantity,
-            m.created_at AS created_at
+            m.kind AS kind,
+            m.created_at AS created_at,
+            m.updated_at AS updated_at
         FROM movements m
-        JOIN printers p ON m.printer_id = p.id
+        LEFT JOIN printers p ON m.printer_id = p.id
         LEFT JOIN toners t ON m.item_id = t.id
         LEFT JOIN drums d ON m.item_id = d.id
         WHERE m.id = $1
@@ -108,105 +169,145 @@ pub async fn search_movement(
 
     match movement {
         Some(row) => {
-            let movement = MovementDetails {
-                id: row.0,
-                printer: PrinterDetails {
-                    id: row.1,
-                    name: row.2,
-                    model: row.3,
-                },
-                item: ItemDetails {
-                    id: row.4,
-                    name: row.5,
-                },
-                quantity: row.6,
-                created_at: row.7,
-            };
+            let movement = MovementDetails::from(row);
 
             info!("Movement found: {id}");
             Ok((StatusCode::OK, Json(Some(movement))))
         }
         None => {
             error!("No movement found.");
-            Err(ApiError::IdNotFound)
+            Err(ApiError::IdNotFound {
+                resource: "Movement",
+            })
         }
     }
 }
 
 /// Retrieves a list of all movements.
 ///
-/// This endpoint fetches all movements stored in the database.
+/// This endpoint fetches all movements stored in the database matching the optional filters.
 /// If there are no movements, returns an empty array.
 #[utoipa::path(
     get,
     path = "/api/v1/movements",
     tags = ["Movements"],
     summary = "List all movements.",
-    description = "Fetches all movements stored in the database. If there are no movements, returns an empty array.",
+    description = "Fetches all movements stored in the database, optionally filtered by creation time and printer. If there are no movements, returns an empty array.",
+    params(MovementFilterParams),
     responses(
         (status = 200, description = "Movements retrieved successfully", body = Vec<MovementDetails>),
+        (status = 400, description = "Invalid timezone"),
         (status = 404, description = "No movements found in the database"),
         (status = 500, description = "An error occurred while retrieving the movements")
     )
 )]
 pub async fn show_movements(
+    Query(params): Query<MovementFilterParams>,
     State(state): State<Arc<AppState>>,
 ) -> Result<impl IntoResponse, ApiError> {
-    let movements = sqlx::query_as::<_, MovementView>(
-        r#"
-        SELECT 
-            m.id AS movement_id,
-            p.id AS printer_id,
-            p.name AS printer_name,
-            p.model AS printer_model,
-            CASE
-                WHEN t.id IS NOT NULL THEN t.id
-                ELSE d.id
-            END AS item_id,
-            CASE
-                WHEN t.id IS NOT NULL THEN t.name
-                ELSE d.name
-            END AS item_name,
-            m.quantity AS quantity,
-            m.created_at AS created_at
-        FROM movements m
-        JOIN printers p ON m.printer_id = p.id
-        LEFT JOIN toners t ON m.item_id = t.id
-        LEFT JOIN drums d ON m.item_id = d.id
-        "#,
-    )
-    .fetch_all(&state.db)
-    .await
-    .map_err(|e| {
-        error!("Error listing printers: {e}");
-        ApiError::DatabaseError(e)
-    })?;
+    // Validations
+    params.validate()?;
+    let offset = params
+        .tz
+        .as_deref()
+        .and_then(parse_utc_offset)
+        .unwrap_or(Utc.fix());
+
+    let movements = sqlx::query_as::<_, MovementView>(FILTERED_MOVEMENTS_QUERY)
+        .bind(params.from)
+        .bind(params.to)
+        .bind(params.printer_id)
+        .fetch_all(&state.db)
+        .await
+        .map_err(|e| {
+            error!("Error listing printers: {e}");
+            ApiError::DatabaseError(e)
+        })?;
 
     let movements: Vec<MovementDetails> = movements
         .into_iter()
-        .map(|row| MovementDetails {
-            id: row.0,
-            printer: PrinterDetails {
-                id: row.1,
-                name: row.2,
-                model: row.3,
-            },
-            item: ItemDetails {
-                id: row.4,
-                name: row.5,
-            },
-            quantity: row.6,
-            created_at: row.7,
-        })
+        .map(|row| MovementDetails::from(row).with_offset(offset))
         .collect();
 
     info!("Movements listed successfully");
     Ok(Json(movements))
 }
 
+/// Exports movements as newline-delimited JSON.
+///
+/// This endpoint streams every movement matching the optional filters as one JSON object per line.
+/// Rows are written as they are read from the database, so the result set is never held in memory.
+#[utoipa::path(
+    get,
+    path = "/api/v1/movements/export.jsonl",
+    tags = ["Movements"],
+    summary = "Export movements as NDJSON.",
+    description = "Streams the movements stored in the database as newline-delimited JSON, optionally filtered by creation time and printer.",
+    params(MovementFilterParams),
+    responses(
+        (status = 200, description = "Movements streamed successfully", body = MovementDetails, content_type = "application/x-ndjson"),
+        (status = 400, description = "Invalid timezone")
+    )
+)]
+pub async fn export_movements(
+    Query(params): Query<MovementFilterParams>,
+    State(state): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, ApiError> {
+    // Validations
+    params.validate()?;
+    let offset = params
+        .tz
+        .as_deref()
+        .and_then(parse_utc_offset)
+        .unwrap_or(Utc.fix());
+
+    let (sender, receiver) = mpsc::channel::<Result<String, sqlx::Error>>(64);
+
+    tokio::spawn(async move {
+        let mut rows = sqlx::query_as::<_, MovementView>(FILTERED_MOVEMENTS_QUERY)
+            .bind(params.from)
+            .bind(params.to)
+            .bind(params.printer_id)
+            .fetch(&state.db);
+
+        while let Some(row) = rows.next().await {
+            let line = match row {
+                Ok(row) => {
+                    let mut line =
+                        serde_json::to_string(&MovementDetails::from(row).with_offset(offset))
+                            .expect("Error serializing movement");
+                    line.push('\n');
+                    Ok(line)
+                }
+                Err(e) => {
+                    // The response has already started, so the stream is aborted instead
+                    error!("Error exporting movements: {e}");
+                    let _ = sender.send(Err(e)).await;
+                    return;
+                }
+            };
+
+            if sender.send(line).await.is_err() {
+                info!("Movement export cancelled by the client");
+                return;
+            }
+        }
+
+        info!("Movements exported successfully");
+    });
+
+    Ok((
+        [(header::CONTENT_TYPE, "application/x-ndjson")],
+        Body::from_stream(ReceiverStream::new(receiver)),
+    ))
+}
+
 /// Create a new movement.
 ///
 /// This endpoint creates a new movement by providing its details.
+/// The item's stock is updated and the movement is recorded in a single transaction.
+/// Consumptions (negative quantities) are limited to `MAX_CONSUMPTION_PER_MINUTE` per item.
+/// Returns the movement's ID along with the item's resulting stock.
 #[utoipa::path(
     post,
     path = "/api/v1/movements",
@@ -215,8 +316,11 @@ pub async fn show_movements(
     description = "This endpoint creates a new movement in the database with the provided details.",
     request_body = CreateMovementRequest,
     responses(
-        (status = 201, description = "Movement created successfully", body = Uuid),
+        (status = 201, description = "Movement created successfully", body = CreatedMovement),
         (status = 400, description = "Invalid input"),
+        (status = 404, description = "Printer or item ID not found"),
+        (status = 422, description = "Insufficient stock for the requested quantity"),
+        (status = 429, description = "Too many consumption movements for the item in the last minute"),
         (status = 500, description = "An error occurred while creating the movement")
     )
 )]
@@ -231,91 +335,205 @@ pub async fn create_movement(
         Uuid::from_str(&request.printer_id).unwrap(),
         Uuid::from_str(&request.item_id).unwrap(),
         request.quantity,
+        request.created_at,
     );
 
-    // Check if the item exists in toners or drums
-    let item_exists: (bool, bool) = sqlx::query_as(
-        r#"
-        SELECT 
-            EXISTS(SELECT 1 FROM toners WHERE id = $1) AS toner_exists,
-            EXISTS(SELECT 1 FROM drums WHERE id = $1) AS drum_exists;
-        "#,
+    let mut tx = state.db.begin().await.map_err(|e| {
+        error!("Error starting transaction: {e}");
+        ApiError::DatabaseError(e)
+    })?;
+
+    let new_stock = apply_movement(&mut tx, &new_movement, request.item_type).await?;
+
+    // Only consumptions of existing items count, the transaction is rolled back if throttled
+    if new_movement.quantity < 0 {
+        check_consumption_rate(new_movement.item_id)?;
+    }
+
+    tx.commit().await.map_err(|e| {
+        error!("Error committing movement: {e}");
+        ApiError::DatabaseError(e)
+    })?;
+
+    notify_stock_change(StockChangeEvent {
+        item_id: new_movement.item_id,
+        new_stock,
+        movement_id: new_movement.id,
+    });
+
+    info!("Movement created! ID: {}", &new_movement.id);
+    Ok((
+        StatusCode::CREATED,
+        location(&format!("/movements/{}", new_movement.id)),
+        Json(CreatedMovement {
+            id: new_movement.id,
+            item_id: new_movement.item_id,
+            new_stock,
+        }),
+    ))
+}
+
+/// Create several movements at once.
+///
+/// This endpoint creates every movement in the batch within a single transaction.
+/// If any item is invalid, the whole batch is rolled back and the index of the failing item is returned.
+/// Consumptions count towards the `MAX_CONSUMPTION_PER_MINUTE` of their item, as in single movements.
+#[utoipa::path(
+    post,
+    path = "/api/v1/movements/batch",
+    tags = ["Movements"],
+    summary = "Create a batch of movements.",
+    description = "This endpoint applies all stock changes and records all movements of the batch in a single transaction. A single invalid item rolls back the entire batch.",
+    request_body = Vec<CreateMovementRequest>,
+    responses(
+        (status = 201, description = "Movements created successfully", body = Vec<Uuid>),
+        (status = 400, description = "Invalid input in one of the batch items"),
+        (status = 404, description = "Printer or item ID not found in one of the batch items"),
+        (status = 422, description = "Insufficient stock in one of the batch items"),
+        (status = 429, description = "Too many consumption movements for the item of one of the batch items"),
+        (status = 500, description = "An error occurred while creating the movements")
     )
-    .bind(&new_movement.item_id)
-    .fetch_one(&state.db)
-    .await
-    .map_err(|e| {
-        error!("Database error: {}", e);
+)]
+pub async fn create_movement_batch(
+    State(state): State<Arc<AppState>>,
+    Json(requests): Json<Vec<CreateMovementRequest>>,
+) -> Result<impl IntoResponse, ApiError> {
+    let mut tx = state.db.begin().await.map_err(|e| {
+        error!("Error starting transaction: {e}");
         ApiError::DatabaseError(e)
     })?;
 
-    let (toner_exists, drum_exists) = item_exists;
+    let mut events = Vec::with_capacity(requests.len());
 
-    // Check if the item exists
-    if !(toner_exists || drum_exists) {
-        error!(
-            "Item with ID '{}' not found in toners or drums.",
-            &new_movement.item_id
-        );
-        return Err(ApiError::IdNotFound);
+    for (index, request) in requests.into_iter().enumerate() {
+        let result = async {
+            request.validate()?;
+
+            let new_movement = Movement::new(
+                Uuid::from_str(&request.printer_id).unwrap(),
+                Uuid::from_str(&request.item_id).unwrap(),
+                request.quantity,
+                request.created_at,
+            );
+
+            let new_stock = apply_movement(&mut tx, &new_movement, request.item_type).await?;
+            if new_movement.quantity < 0 {
+                check_consumption_rate(new_movement.item_id)?;
+            }
+
+            Ok::<_, ApiError>(StockChangeEvent {
+                item_id: new_movement.item_id,
+                new_stock,
+                movement_id: new_movement.id,
+            })
+        }
+        .await;
+
+        match result {
+            Ok(event) => events.push(event),
+            Err(e) => {
+                error!("Movement batch rolled back at index {index}");
+                return Err(ApiError::BatchItemFailed {
+                    index,
+                    source: Box::new(e),
+                });
+            }
+        }
     }
 
-    // Update stock
-    let update_stock_query = if toner_exists {
-        r#"UPDATE toners SET stock = stock + $1 WHERE id = $2;"#
-    } else {
-        r#"UPDATE drums SET stock = stock + $1 WHERE id = $2;"#
-    };
+    tx.commit().await.map_err(|e| {
+        error!("Error committing movement batch: {e}");
+        ApiError::DatabaseError(e)
+    })?;
 
-    sqlx::query(update_stock_query)
-        .bind(new_movement.quantity)
-        .bind(new_movement.item_id)
-        .execute(&state.db)
+    let movement_ids: Vec<Uuid> = events.iter().map(|event| event.movement_id).collect();
+    events.into_iter().for_each(notify_stock_change);
+
+    info!("Movement batch created! {} movements", movement_ids.len());
+    Ok((StatusCode::CREATED, Json(movement_ids)))
+}
+
+/// Applies a movement to its item's stock and records it within the given transaction.
+///
+/// Checks that the printer exists, that the item exists as the given type of supply,
+/// and that it has enough stock for outgoing quantities, not counting the stock held by
+/// active reservations. Returns the item's resulting stock.
+pub async fn apply_movement(
+    tx: &mut Transaction<'_, Postgres>,
+    movement: &Movement,
+    item_type: ItemType,
+) -> Result<i64, ApiError> {
+    // Check if the printer exists
+    if let Some(printer_id) = movement.printer_id {
+        let printer_exists = sqlx::query_scalar::<_, bool>(
+            r#"SELECT EXISTS(SELECT 1 FROM printers WHERE id = $1);"#,
+        )
+        .bind(printer_id)
+        .fetch_one(&mut **tx)
         .await
         .map_err(|e| {
-            error!("Error updating stock: {}", e);
+            error!("Error fetching printer by ID: {e}");
             ApiError::DatabaseError(e)
         })?;
 
+        if !printer_exists {
+            error!("Printer with ID '{printer_id}' not found.");
+            return Err(ApiError::IdNotFound {
+                resource: "Printer",
+            });
+        }
+    }
+
+    let new_stock = change_stock(tx, movement.item_id, item_type, movement.quantity).await?;
+
     // Create the movement
     sqlx::query(
         r#"
-        INSERT INTO movements (id, printer_id, item_id, quantity, created_at) 
-        VALUES ($1, $2, $3, $4, $5);
+        INSERT INTO movements (id, printer_id, item_id, item_type, quantity, kind, created_at, updated_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8);
         "#,
     )
-    .bind(new_movement.id)
-    .bind(new_movement.printer_id)
-    .bind(new_movement.item_id)
-    .bind(new_movement.quantity)
-    .bind(new_movement.created_at)
-    .execute(&state.db)
+    .bind(movement.id)
+    .bind(movement.printer_id)
+    .bind(movement.item_id)
+    .bind(item_type)
+    .bind(movement.quantity)
+    .bind(movement.kind)
+    .bind(movement.created_at)
+    .bind(movement.updated_at)
+    .execute(&mut **tx)
     .await
     .map_err(|e| {
         error!("Error creating movement: {}", e);
         ApiError::DatabaseError(e)
     })?;
 
-    info!("Movement created! ID: {}", &new_movement.id);
-    Ok((StatusCode::CREATED, Json(new_movement.id)))
+    Ok(new_stock)
 }
 
 /// Updates an existing movement.
 ///
 /// This endpoint updates the details of an existing movement.
 /// It accepts the movement ID and the new details for the movement.
+/// When `updated_at` is provided, the update is rejected if the movement
+/// was modified after that time by someone else.
+/// Changing the item or quantity moves the stock change accordingly, and is rejected if a stock would become negative,
+/// if it would take stock held by active reservations, or if the movement's item no longer exists.
+/// A new item must exist as the given `item_type`, or as the type of the current item when it is omitted.
 /// If the movement is successfully updated, it returns the UUID of the updated movement.
 #[utoipa::path(
     put,
     path = "/api/v1/movements",
     tags = ["Movements"],
     summary = "Update an existing movement.",
-    description = "This endpoint updates the details of an existing movement in the database.",
+    description = "This endpoint updates the details of an existing movement in the database. Send the movement's last known `updated_at` to avoid overwriting concurrent changes. Changing `item_id` or `quantity` updates the affected items' stock accordingly.",
     request_body = UpdateMovementRequest,
     responses(
         (status = 200, description = "Movement updated successfully", body = Uuid),
         (status = 400, description = "Invalid input"),
-        (status = 404, description = "Movement ID not found"),
+        (status = 404, description = "Movement, printer or item ID not found"),
+        (status = 409, description = "The movement was modified since the provided `updated_at`"),
+        (status = 422, description = "The change would leave an item with negative stock"),
         (status = 500, description = "An error occurred while updating the movement")
     )
 )]
@@ -325,28 +543,74 @@ pub async fn update_movement(
 ) -> Result<impl IntoResponse, ApiError> {
     // Validations
     request.validate()?;
-    movement_exists(
-        state.clone(),
-        Uuid::parse_str(request.id.clone().as_str()).unwrap(),
-    )
-    .await?;
-
-    let movement_id = Uuid::parse_str(request.id.clone().as_str()).unwrap();
-    let new_printer_id = request
-        .printer_id
-        .map(|d| Uuid::from_str(&d).ok())
-        .flatten();
-    let new_item_id = request.item_id.map(|d| Uuid::from_str(&d).ok()).flatten();
+
+    let movement_id = Uuid::parse_str(&request.id).unwrap();
+    let new_printer_id = request.printer_id.and_then(|d| Uuid::from_str(&d).ok());
+    let new_item_id = request.item_id.and_then(|d| Uuid::from_str(&d).ok());
     let new_quantity = request.quantity;
 
+    let mut tx = state.db.begin().await.map_err(|e| {
+        error!("Error starting transaction: {e}");
+        ApiError::DatabaseError(e)
+    })?;
+
+    // Lock the movement and check that it has not been modified concurrently
+    let (stored_updated_at, old_item_id, old_item_type, old_quantity) =
+        sqlx::query_as::<_, (DateTime<Utc>, Uuid, Option<ItemType>, i64)>(
+            r#"
+            SELECT updated_at, item_id, item_type, quantity
+            FROM movements
+            WHERE id = $1
+            FOR UPDATE;
+            "#,
+        )
+        .bind(movement_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| {
+            error!("Error fetching movement by ID: {e}");
+            ApiError::DatabaseError(e)
+        })?
+        .ok_or_else(|| {
+            error!("Movement ID not found.");
+            ApiError::IdNotFound {
+                resource: "Movement",
+            }
+        })?;
+
+    if let Some(expected) = request.updated_at {
+        if stored_updated_at > expected {
+            error!("Movement {movement_id} was modified at {stored_updated_at}, after {expected}");
+            return Err(ApiError::Outdated);
+        }
+    }
+
     let mut updated = false;
 
     // Update printer if provided
     if let Some(printer) = new_printer_id {
+        let printer_exists = sqlx::query_scalar::<_, bool>(
+            r#"SELECT EXISTS(SELECT 1 FROM printers WHERE id = $1);"#,
+        )
+        .bind(printer)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| {
+            error!("Error fetching printer by ID: {e}");
+            ApiError::DatabaseError(e)
+        })?;
+
+        if !printer_exists {
+            error!("Printer with ID '{printer}' not found.");
+            return Err(ApiError::IdNotFound {
+                resource: "Printer",
+            });
+        }
+
         sqlx::query(r#"UPDATE movements SET printer_id = $1 WHERE id = $2;"#)
             .bind(printer)
-            .bind(&movement_id)
-            .execute(&state.db)
+            .bind(movement_id)
+            .execute(&mut *tx)
             .await
             .map_err(|e| {
                 error!("Error updating movement printer: {e}");
@@ -355,60 +619,59 @@ pub async fn update_movement(
         updated = true;
     }
 
-    // Check if the item is a toner
-    let toner_exists =
-        sqlx::query_scalar::<_, bool>(r#"SELECT EXISTS(SELECT 1 FROM toners WHERE id = $1);"#)
-            .bind(&new_item_id)
-            .fetch_one(&state.db)
+    // Update item if provided, as long as it exists as the given or current type of supply
+    let mut new_item_type = None;
+    if let Some(item) = new_item_id {
+        let item_type = request.item_type.or(old_item_type).ok_or_else(|| {
+            error!("The type of the item of movement {movement_id} is unknown, `item_type` is required.");
+            ApiError::IdNotFound { resource: "Supply" }
+        })?;
+
+        let (item_query, resource) = match item_type {
+            ItemType::Toner => (
+                r#"SELECT EXISTS(SELECT 1 FROM toners WHERE id = $1);"#,
+                "Toner",
+            ),
+            ItemType::Drum => (
+                r#"SELECT EXISTS(SELECT 1 FROM drums WHERE id = $1);"#,
+                "Drum",
+            ),
+        };
+
+        let item_exists = sqlx::query_scalar::<_, bool>(item_query)
+            .bind(item)
+            .fetch_one(&mut *tx)
             .await
             .map_err(|e| {
-                error!("Error updating printer name: {e}");
+                error!("Error fetching item by ID: {e}");
                 ApiError::DatabaseError(e)
             })?;
 
-    if toner_exists {
-        sqlx::query(r#"UPDATE movements SET item_id = $1 WHERE id = $2;"#)
-            .bind(&new_item_id)
-            .bind(&movement_id)
-            .execute(&state.db)
+        if !item_exists {
+            error!("Item with ID '{item}' not found in {item_type:?} supplies.");
+            return Err(ApiError::IdNotFound { resource });
+        }
+
+        sqlx::query(r#"UPDATE movements SET item_id = $1, item_type = $2 WHERE id = $3;"#)
+            .bind(item)
+            .bind(item_type)
+            .bind(movement_id)
+            .execute(&mut *tx)
             .await
             .map_err(|e| {
-                error!("Error updating movement toner: {e}");
+                error!("Error updating movement item: {e}");
                 ApiError::DatabaseError(e)
             })?;
+        new_item_type = Some(item_type);
         updated = true;
-    } else {
-        // If a toner does not exist, check if a drum exists and, if possible, update it.
-        let drum_exists =
-            sqlx::query_scalar::<_, bool>(r#"SELECT EXISTS(SELECT 1 FROM drums WHERE id = $1);"#)
-                .bind(&new_item_id)
-                .fetch_one(&state.db)
-                .await
-                .map_err(|e| {
-                    error!("Error updating printer name: {e}");
-                    ApiError::DatabaseError(e)
-                })?;
-
-        if drum_exists {
-            sqlx::query(r#"UPDATE movements SET item_id = $1 WHERE id = $2;"#)
-                .bind(&new_item_id)
-                .bind(&movement_id)
-                .execute(&state.db)
-                .await
-                .map_err(|e| {
-                    error!("Error updating movement drum: {e}");
-                    ApiError::DatabaseError(e)
-                })?;
-            updated = true;
-        }
     }
 
     // Update quantity if provided
     if let Some(quantity) = new_quantity {
         sqlx::query(r#"UPDATE movements SET quantity = $1 WHERE id = $2;"#)
-            .bind(&quantity)
-            .bind(&movement_id)
-            .execute(&state.db)
+            .bind(quantity)
+            .bind(movement_id)
+            .execute(&mut *tx)
             .await
             .map_err(|e| {
                 error!("Error updating movement quantity: {e}");
@@ -417,18 +680,138 @@ pub async fn update_movement(
         updated = true;
     }
 
+    // Nothing to change, the movement is left as it was
     if !updated {
-        error!(
-            "No updates were made for the provided movement ID: {}",
-            &movement_id
-        );
-        return Err(ApiError::NotModified);
+        info!("No fields to update for movement ID: {}", &movement_id);
+        return Ok(Json(movement_id));
+    }
+
+    // Move the stock change from the old item and quantity to the new ones
+    let item_id = new_item_id.unwrap_or(old_item_id);
+    let quantity = new_quantity.unwrap_or(old_quantity);
+    let mut events = Vec::new();
+
+    if item_id != old_item_id || quantity != old_quantity {
+        let old_item_type = old_item_type.ok_or_else(|| {
+            error!("Item '{old_item_id}' of movement {movement_id} no longer exists, its stock can't be changed.");
+            ApiError::IdNotFound { resource: "Supply" }
+        })?;
+
+        if item_id == old_item_id {
+            let delta = quantity - old_quantity;
+            let new_stock = change_stock(&mut tx, item_id, old_item_type, delta).await?;
+            events.push((item_id, new_stock));
+        } else {
+            let item_type = new_item_type.unwrap_or(old_item_type);
+            let old_stock =
+                change_stock(&mut tx, old_item_id, old_item_type, -old_quantity).await?;
+            let new_stock = change_stock(&mut tx, item_id, item_type, quantity).await?;
+            events.push((old_item_id, old_stock));
+            events.push((item_id, new_stock));
+        }
+    }
+
+    sqlx::query(r#"UPDATE movements SET updated_at = NOW() WHERE id = $1;"#)
+        .bind(movement_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| {
+            error!("Error updating movement timestamp: {e}");
+            ApiError::DatabaseError(e)
+        })?;
+
+    tx.commit().await.map_err(|e| {
+        error!("Error committing movement update: {e}");
+        ApiError::DatabaseError(e)
+    })?;
+
+    for (item_id, new_stock) in events {
+        notify_stock_change(StockChangeEvent {
+            item_id,
+            new_stock,
+            movement_id,
+        });
     }
 
     info!("Movement updated! ID: {}", &movement_id);
     Ok(Json(movement_id))
 }
 
+/// Adds `delta` to the stock of a toner or drum within the given transaction.
+///
+/// The item is locked first, and the change is rejected with `InsufficientStock` if it would leave
+/// the stock negative or below the quantity held by active reservations.
+/// Returns the item's resulting stock.
+async fn change_stock(
+    tx: &mut Transaction<'_, Postgres>,
+    item_id: Uuid,
+    item_type: ItemType,
+    delta: i64,
+) -> Result<i64, ApiError> {
+    // Lock the item's stock for the rest of the transaction
+    let (stock_query, update_stock_query, resource) = match item_type {
+        ItemType::Toner => (
+            r#"SELECT stock FROM toners WHERE id = $1 FOR UPDATE;"#,
+            r#"UPDATE toners SET stock = COALESCE(stock, 0) + $1 WHERE id = $2 RETURNING stock;"#,
+            "Toner",
+        ),
+        ItemType::Drum => (
+            r#"SELECT stock FROM drums WHERE id = $1 FOR UPDATE;"#,
+            r#"UPDATE drums SET stock = COALESCE(stock, 0) + $1 WHERE id = $2 RETURNING stock;"#,
+            "Drum",
+        ),
+    };
+
+    let stock = sqlx::query_scalar::<_, Option<i64>>(stock_query)
+        .bind(item_id)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(|e| {
+            error!("Error fetching stock: {e}");
+            ApiError::DatabaseError(e)
+        })?
+        .ok_or_else(|| {
+            error!("Item with ID '{item_id}' not found in {item_type:?} supplies.");
+            ApiError::IdNotFound { resource }
+        })?
+        .unwrap_or(0);
+
+    // Reservations of the item are only made while holding its lock, so their sum can't change meanwhile
+    let reserved = if delta < 0 {
+        sqlx::query_scalar::<_, i64>(
+            r#"SELECT COALESCE(SUM(quantity), 0)::bigint FROM reservations WHERE item_id = $1 AND expires_at > NOW();"#,
+        )
+        .bind(item_id)
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(|e| {
+            error!("Error fetching reserved quantity: {e}");
+            ApiError::DatabaseError(e)
+        })?
+    } else {
+        0
+    };
+
+    let available = stock - reserved;
+    if available + delta < 0 {
+        error!(
+            "Insufficient stock for item '{item_id}': {available} available, {} requested.",
+            -delta
+        );
+        return Err(ApiError::InsufficientStock);
+    }
+
+    sqlx::query_scalar::<_, i64>(update_stock_query)
+        .bind(delta)
+        .bind(item_id)
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(|e| {
+            error!("Error updating stock: {e}");
+            ApiError::DatabaseError(e)
+        })
+}
+
 /// Deletes an existing movement.
 ///
 /// This endpoint allows users to delete a specific movement by its ID.
@@ -467,3 +850,662 @@ pub async fn delete_movement(
     info!("Movement deleted! ID: {}", &request.id);
     Ok(Json("Movement deleted!"))
 }
+
+/// Purges old movements.
+///
+/// This admin endpoint deletes the movements created before the given day, in batches,
+/// archiving each deleted batch to a JSON lines file in `ARCHIVE_DIR` when it is set.
+/// Stocks are left unchanged, since only the history is removed.
+#[utoipa::path(
+    post,
+    path = "/api/v1/movements/purge",
+    tags = ["Movements"],
+    summary = "Purge old movements.",
+    description = "Deletes the movements created before `before` in batches and returns how many were deleted. When `ARCHIVE_DIR` is set, each batch is appended to a JSON lines file once its deletion is committed, so only deleted movements are archived. Requires the admin token in the `Authorization: Bearer` header.",
+    params(PurgeParams),
+    responses(
+        (status = 200, description = "Movements purged successfully", body = PurgeResult),
+        (status = 403, description = "Missing or invalid admin token"),
+        (status = 500, description = "An error occurred while archiving or purging the movements")
+    )
+)]
+pub async fn purge_movements(
+    _admin: Admin,
+    Query(params): Query<PurgeParams>,
+    State(state): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, ApiError> {
+    let before = params.before.and_time(chrono::NaiveTime::MIN).and_utc();
+
+    let archive_path = Config::archive_dir().map(|dir| {
+        PathBuf::from(dir).join(format!(
+            "movements-before-{}-{}.jsonl",
+            params.before,
+            Utc::now().format("%Y%m%dT%H%M%S")
+        ))
+    });
+
+    let mut archive = match &archive_path {
+        Some(path) => Some(open_archive(path).await?),
+        None => None,
+    };
+
+    let mut deleted = 0;
+
+    loop {
+        let mut tx = state.db.begin().await.map_err(|e| {
+            error!("Error starting transaction: {e}");
+            ApiError::DatabaseError(e)
+        })?;
+
+        let batch = sqlx::query_as::<_, Movement>(
+            r#"
+            DELETE FROM movements
+            WHERE id IN (SELECT id FROM movements WHERE created_at < $1 LIMIT $2)
+            RETURNING id, printer_id, item_id, quantity, kind, created_at, updated_at;
+            "#,
+        )
+        .bind(before)
+        .bind(PURGE_BATCH_SIZE)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| {
+            error!("Error purging movements: {e}");
+            ApiError::DatabaseError(e)
+        })?;
+
+        if batch.is_empty() {
+            break;
+        }
+
+        tx.commit().await.map_err(|e| {
+            error!("Error committing movement purge: {e}");
+            ApiError::DatabaseError(e)
+        })?;
+
+        // Only batches that were actually deleted are archived
+        if let Some(file) = archive.as_mut() {
+            let mut lines = String::new();
+            for movement in &batch {
+                lines.push_str(
+                    &serde_json::to_string(movement).expect("Error serializing movement"),
+                );
+                lines.push('\n');
+            }
+
+            let written = async {
+                file.write_all(lines.as_bytes()).await?;
+                file.sync_data().await
+            }
+            .await;
+
+            if let Err(e) = written {
+                error!(
+                    "Error archiving {} purged movements, they are deleted but not archived: {e}",
+                    batch.len()
+                );
+                return Err(ApiError::ArchiveError(e));
+            }
+        }
+
+        deleted += batch.len() as u64;
+    }
+
+    // An archive is only reported if something was written to it
+    let archive = match archive_path {
+        Some(path) if deleted > 0 => Some(path.display().to_string()),
+        Some(path) => {
+            let _ = fs::remove_file(&path).await;
+            None
+        }
+        None => None,
+    };
+
+    info!(
+        "Purged {deleted} movements created before {}",
+        params.before
+    );
+    Ok(Json(PurgeResult { deleted, archive }))
+}
+
+/// Creates the archive file for purged movements, along with its directory.
+async fn open_archive(path: &PathBuf) -> Result<fs::File, ApiError> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).await.map_err(|e| {
+            error!("Error creating archive directory: {e}");
+            ApiError::ArchiveError(e)
+        })?;
+    }
+
+    fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .await
+        .map_err(|e| {
+            error!("Error creating archive file: {e}");
+            ApiError::ArchiveError(e)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::{
+        app, as_admin, create_drum, create_printer, create_toner, empty_request, json_request,
+        send, stock_of,
+    };
+    use axum::{
+        body::to_bytes,
+        http::{header, Method, StatusCode},
+    };
+    use chrono::{DateTime, Duration, SubsecRound, Utc};
+    use serde_json::{json, Value};
+    use sqlx::PgPool;
+    use tower::ServiceExt;
+    use uuid::Uuid;
+
+    async fn create_movement(
+        app: &axum::Router,
+        printer: Uuid,
+        toner: Uuid,
+        quantity: i64,
+    ) -> Value {
+        let (status, movement) = send(
+            app,
+            json_request(
+                Method::POST,
+                "/api/v1/movements",
+                json!({ "printer_id": printer, "item_id": toner, "item_type": "toner", "quantity": quantity }),
+            ),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        movement["id"].clone()
+    }
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn batch_with_an_invalid_last_item_is_rolled_back(pool: PgPool) {
+        let app = app(pool);
+        let printer = create_printer(&app, "Batch Printer").await;
+        let toner = create_toner(&app, "Batch Toner", 10).await;
+
+        let (_, movements_before) =
+            send(&app, empty_request(Method::GET, "/api/v1/movements/count")).await;
+
+        let (status, body) = send(
+            &app,
+            json_request(
+                Method::POST,
+                "/api/v1/movements/batch",
+                json!([
+                    { "printer_id": printer, "item_id": toner, "item_type": "toner", "quantity": -3 },
+                    { "printer_id": printer, "item_id": toner, "item_type": "toner", "quantity": -100 },
+                ]),
+            ),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(body["code"], "BATCH_ITEM_FAILED");
+        assert!(body["message"].as_str().unwrap().contains("index 1"));
+
+        let (_, movements_after) =
+            send(&app, empty_request(Method::GET, "/api/v1/movements/count")).await;
+        assert_eq!(movements_after, movements_before);
+        assert_eq!(stock_of(&app, toner).await, 10);
+    }
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn withdrawals_cannot_take_reserved_stock(pool: PgPool) {
+        let app = app(pool);
+        let printer = create_printer(&app, "Reserving Printer").await;
+        let toner = create_toner(&app, "Reserved Toner", 10).await;
+
+        let (status, _) = send(
+            &app,
+            json_request(
+                Method::POST,
+                &format!("/api/v1/supplies/{toner}/reserve"),
+                json!({ "quantity": 8, "ttl_seconds": 600 }),
+            ),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+
+        let withdraw = |quantity: i64| {
+            json_request(
+                Method::POST,
+                "/api/v1/movements",
+                json!({ "printer_id": printer, "item_id": toner, "item_type": "toner", "quantity": quantity }),
+            )
+        };
+
+        let (status, body) = send(&app, withdraw(-3)).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(body["code"], "INSUFFICIENT_STOCK");
+
+        let (status, _) = send(&app, withdraw(-2)).await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(stock_of(&app, toner).await, 8);
+    }
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn stale_updates_are_rejected(pool: PgPool) {
+        let app = app(pool);
+        let printer = create_printer(&app, "Updating Printer").await;
+        let toner = create_toner(&app, "Updated Toner", 10).await;
+        let movement = create_movement(&app, printer, toner, -2).await;
+
+        let (_, details) = send(
+            &app,
+            empty_request(
+                Method::GET,
+                &format!("/api/v1/movements/{}", movement.as_str().unwrap()),
+            ),
+        )
+        .await;
+        let read_at = details["updated_at"].clone();
+
+        let update = |quantity: i64| {
+            json_request(
+                Method::PUT,
+                "/api/v1/movements",
+                json!({ "id": movement, "quantity": quantity, "updated_at": read_at }),
+            )
+        };
+
+        let (status, _) = send(&app, update(-3)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(stock_of(&app, toner).await, 7);
+
+        let (status, body) = send(&app, update(-4)).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(body["code"], "OUTDATED");
+        assert_eq!(stock_of(&app, toner).await, 7);
+    }
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn updates_to_unknown_printers_or_items_of_another_type_are_not_found(pool: PgPool) {
+        let app = app(pool);
+        let printer = create_printer(&app, "Moving Printer").await;
+        let toner = create_toner(&app, "Moving Toner", 10).await;
+        let drum = create_drum(&app, "Moving Drum", 10).await;
+        let movement = create_movement(&app, printer, toner, -2).await;
+
+        let update = |body: Value| json_request(Method::PUT, "/api/v1/movements", body);
+
+        let (status, body) = send(
+            &app,
+            update(json!({ "id": movement, "printer_id": Uuid::new_v4() })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["code"], "ID_NOT_FOUND");
+
+        // The drum is checked against the type of the current item, a toner
+        let (status, _) = send(&app, update(json!({ "id": movement, "item_id": drum }))).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(stock_of(&app, toner).await, 8);
+
+        let (status, _) = send(
+            &app,
+            update(json!({ "id": movement, "item_id": drum, "item_type": "drum" })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(stock_of(&app, toner).await, 10);
+        assert_eq!(stock_of(&app, drum).await, 8);
+    }
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn created_movements_report_the_new_stock(pool: PgPool) {
+        let app = app(pool);
+        let printer = create_printer(&app, "ECOSYS M2040").await;
+        let toner = create_toner(&app, "TN-1060", 10).await;
+
+        let (status, movement) = send(
+            &app,
+            json_request(
+                Method::POST,
+                "/api/v1/movements",
+                json!({ "printer_id": printer, "item_id": toner, "item_type": "toner", "quantity": 5 }),
+            ),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::CREATED);
+        assert!(Uuid::parse_str(movement["id"].as_str().unwrap()).is_ok());
+        assert_eq!(movement["item_id"], json!(toner));
+        assert_eq!(movement["new_stock"], 15);
+    }
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn toners_declared_as_drums_are_not_found(pool: PgPool) {
+        let app = app(pool);
+        let printer = create_printer(&app, "ECOSYS M2040").await;
+        let toner = create_toner(&app, "TN-1060", 10).await;
+
+        let (status, body) = send(
+            &app,
+            json_request(
+                Method::POST,
+                "/api/v1/movements",
+                json!({ "printer_id": printer, "item_id": toner, "item_type": "drum", "quantity": -1 }),
+            ),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["code"], "ID_NOT_FOUND");
+        assert_eq!(stock_of(&app, toner).await, 10);
+    }
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn quantities_outside_the_configured_range_are_rejected(pool: PgPool) {
+        let app = app(pool);
+        let printer = create_printer(&app, "Bulk Printer").await;
+        let toner = create_toner(&app, "Bulk Toner", 0).await;
+
+        let restock = |quantity: i64| {
+            json_request(
+                Method::POST,
+                "/api/v1/movements",
+                json!({ "printer_id": printer, "item_id": toner, "item_type": "toner", "quantity": quantity }),
+            )
+        };
+
+        let (status, _) = send(&app, restock(1_000_000)).await;
+        assert_eq!(status, StatusCode::CREATED);
+
+        for quantity in [1_000_001, -1_000_001, 1_000_000_000_000] {
+            let (status, body) = send(&app, restock(quantity)).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST);
+            assert_eq!(body["code"], "VALIDATION_ERROR");
+            assert!(body["details"]
+                .to_string()
+                .contains("Quantity must be between -1000000 and 1000000"));
+        }
+
+        assert_eq!(stock_of(&app, toner).await, 1_000_000);
+    }
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn consumptions_past_the_limit_are_throttled(pool: PgPool) {
+        let app = app(pool);
+        let printer = create_printer(&app, "Busy Printer").await;
+        let toner = create_toner(&app, "Busy Toner", 10).await;
+
+        // The test environment allows 5 consumptions per item and minute
+        for _ in 0..5 {
+            create_movement(&app, printer, toner, -1).await;
+        }
+
+        let (status, body) = send(
+            &app,
+            json_request(
+                Method::POST,
+                "/api/v1/movements",
+                json!({ "printer_id": printer, "item_id": toner, "item_type": "toner", "quantity": -1 }),
+            ),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(body["code"], "RATE_LIMITED");
+        assert_eq!(stock_of(&app, toner).await, 5);
+
+        // Restocks are not limited
+        create_movement(&app, printer, toner, 1).await;
+    }
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn batches_past_the_consumption_limit_are_rolled_back(pool: PgPool) {
+        let app = app(pool);
+        let printer = create_printer(&app, "Batching Printer").await;
+        let toner = create_toner(&app, "Batched Toner", 10).await;
+
+        let consumption = json!({ "printer_id": printer, "item_id": toner, "item_type": "toner", "quantity": -1 });
+        let (status, body) = send(
+            &app,
+            json_request(
+                Method::POST,
+                "/api/v1/movements/batch",
+                Value::Array(vec![consumption; 6]),
+            ),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(body["code"], "BATCH_ITEM_FAILED");
+        assert!(body["message"].as_str().unwrap().contains("index 5"));
+        assert_eq!(stock_of(&app, toner).await, 10);
+    }
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn editing_the_quantity_applies_the_difference(pool: PgPool) {
+        let app = app(pool);
+        let printer = create_printer(&app, "Editing Printer").await;
+        let toner = create_toner(&app, "Edited Toner", 10).await;
+        let movement = create_movement(&app, printer, toner, -2).await;
+
+        let update = |quantity: i64| {
+            json_request(
+                Method::PUT,
+                "/api/v1/movements",
+                json!({ "id": movement, "quantity": quantity }),
+            )
+        };
+
+        let (status, _) = send(&app, update(-5)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(stock_of(&app, toner).await, 5);
+
+        let (status, _) = send(&app, update(4)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(stock_of(&app, toner).await, 14);
+
+        let (status, _) = send(&app, update(-15)).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(stock_of(&app, toner).await, 14);
+    }
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn edits_cannot_take_reserved_stock(pool: PgPool) {
+        let app = app(pool);
+        let printer = create_printer(&app, "Careful Printer").await;
+        let toner = create_toner(&app, "Reserved Edit Toner", 10).await;
+        let movement = create_movement(&app, printer, toner, -2).await;
+
+        let (status, _) = send(
+            &app,
+            json_request(
+                Method::POST,
+                &format!("/api/v1/supplies/{toner}/reserve"),
+                json!({ "quantity": 6, "ttl_seconds": 600 }),
+            ),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+
+        let (status, _) = send(
+            &app,
+            json_request(
+                Method::PUT,
+                "/api/v1/movements",
+                json!({ "id": movement, "quantity": -5 }),
+            ),
+        )
+        .await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(stock_of(&app, toner).await, 8);
+    }
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn edits_of_movements_of_deleted_items_are_rejected(pool: PgPool) {
+        let app = app(pool.clone());
+        let printer = create_printer(&app, "Orphaning Printer").await;
+        let toner = create_toner(&app, "Deleted Toner", 10).await;
+        let movement = create_movement(&app, printer, toner, -2).await;
+
+        sqlx::query("DELETE FROM toners WHERE id = $1;")
+            .bind(toner)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let (status, body) = send(
+            &app,
+            json_request(
+                Method::PUT,
+                "/api/v1/movements",
+                json!({ "id": movement, "quantity": -3 }),
+            ),
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["code"], "ID_NOT_FOUND");
+    }
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn only_movements_before_the_day_are_purged(pool: PgPool) {
+        let app = app(pool.clone());
+        let printer = create_printer(&app, "ECOSYS M2040").await;
+        let toner = create_toner(&app, "TN-1060", 10).await;
+        let old = create_movement(&app, printer, toner, -2).await;
+        let recent = create_movement(&app, printer, toner, -3).await;
+        sqlx::query("UPDATE movements SET created_at = '2020-01-01' WHERE id = $1;")
+            .bind(Uuid::parse_str(old.as_str().unwrap()).unwrap())
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let (status, result) = send(
+            &app,
+            as_admin(empty_request(
+                Method::POST,
+                "/api/v1/movements/purge?before=2021-01-01",
+            )),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(result["deleted"], 1);
+        let (status, _) = send(
+            &app,
+            empty_request(
+                Method::GET,
+                &format!("/api/v1/movements/{}", old.as_str().unwrap()),
+            ),
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, _) = send(
+            &app,
+            empty_request(
+                Method::GET,
+                &format!("/api/v1/movements/{}", recent.as_str().unwrap()),
+            ),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(stock_of(&app, toner).await, 5);
+    }
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn the_export_has_one_json_movement_per_line(pool: PgPool) {
+        let app = app(pool);
+        let printer = create_printer(&app, "ECOSYS M2040").await;
+        let other_printer = create_printer(&app, "ECOSYS M2540").await;
+        let toner = create_toner(&app, "TN-1060", 10).await;
+        let first = create_movement(&app, printer, toner, -1).await;
+        let second = create_movement(&app, printer, toner, -2).await;
+        create_movement(&app, other_printer, toner, -3).await;
+
+        let response = app
+            .oneshot(empty_request(
+                Method::GET,
+                &format!("/api/v1/movements/export.jsonl?printer_id={printer}"),
+            ))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            "application/x-ndjson"
+        );
+        let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body = String::from_utf8(bytes.to_vec()).unwrap();
+        assert!(body.ends_with('\n'));
+        let mut ids: Vec<Value> = body
+            .lines()
+            .map(|line| serde_json::from_str::<Value>(line).unwrap()["id"].clone())
+            .collect();
+        ids.sort_by_key(|id| id.to_string());
+        let mut expected = vec![first, second];
+        expected.sort_by_key(|id| id.to_string());
+        assert_eq!(ids, expected);
+    }
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn movements_can_be_backdated_but_not_postdated(pool: PgPool) {
+        let app = app(pool);
+        let printer = create_printer(&app, "ECOSYS M2040").await;
+        let toner = create_toner(&app, "TN-1060", 10).await;
+        let create = |created_at: DateTime<Utc>| {
+            json_request(
+                Method::POST,
+                "/api/v1/movements",
+                json!({ "printer_id": printer, "item_id": toner, "item_type": "toner", "quantity": 2, "created_at": created_at }),
+            )
+        };
+        let last_month = (Utc::now() - Duration::days(30)).trunc_subsecs(0);
+
+        let (status, movement) = send(&app, create(last_month)).await;
+        assert_eq!(status, StatusCode::CREATED);
+        let (_, stored) = send(
+            &app,
+            empty_request(
+                Method::GET,
+                &format!("/api/v1/movements/{}", movement["id"].as_str().unwrap()),
+            ),
+        )
+        .await;
+        let created_at: DateTime<Utc> =
+            serde_json::from_value(stored["created_at"].clone()).unwrap();
+        assert_eq!(created_at, last_month);
+
+        let (status, _) = send(&app, create(Utc::now() + Duration::days(1))).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn movements_are_counted_by_item_type(pool: PgPool) {
+        let app = app(pool);
+        let printer = create_printer(&app, "ECOSYS M2040").await;
+        let toner = create_toner(&app, "TN-1060", 0).await;
+        let drum = create_drum(&app, "DK-1150", 0).await;
+        for (item_id, item_type) in [(toner, "toner"), (toner, "toner"), (drum, "drum")] {
+            let (status, _) = send(
+                &app,
+                json_request(
+                    Method::POST,
+                    "/api/v1/movements",
+                    json!({ "printer_id": printer, "item_id": item_id, "item_type": item_type, "quantity": 1 }),
+                ),
+            )
+            .await;
+            assert_eq!(status, StatusCode::CREATED);
+        }
+
+        for (query, expected) in [("", 3), ("?item_type=toner", 2), ("?item_type=drum", 1)] {
+            let (status, count) = send(
+                &app,
+                empty_request(Method::GET, &format!("/api/v1/movements/count{query}")),
+            )
+            .await;
+
+            assert_eq!(status, StatusCode::OK);
+            assert_eq!(count, expected, "{query}");
+        }
+    }
+}
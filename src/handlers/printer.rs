@@ -1,27 +1,51 @@
 use crate::{
     errors::api_error::ApiError,
+    extractors::if_match::{etag, IfMatch},
+    extractors::json::Json,
+    extractors::path::Path,
+    extractors::prefer::ReturnPreference,
     models::{
-        brand::Brand,
         printer::{
-            CreatePrinterRequest, Printer, PrinterDetails, PrinterView, UpdatePrinterRequest,
+            CompatibleSupply, CreatePrinterRequest, CurrencyAmount, ItemConsumption, Printer,
+            PrinterConsumption, PrinterDetails, PrinterView, UpdatePrinterRequest,
+        },
+        CompactItem, CompactParams, DeleteRequest, ExistsRequest, ReturnParams,
+    },
+    routes::location,
+    validations::{
+        existence::printer_exists,
+        model::normalize_model,
+        uniqueness::{
+            conflict_or_database_error, is_printer_name_available, is_printer_unique,
+            reject_taken_name,
         },
-        supplies::{drum::Drum, toner::Toner},
-        DeleteRequest,
     },
-    validations::{existence::printer_exists, uniqueness::is_printer_unique},
 };
 use axum::{
-    extract::{Path, State},
-    http::StatusCode,
+    extract::{Query, State},
+    http::{header, StatusCode},
     response::IntoResponse,
-    Json,
 };
+use chrono::{DateTime, Utc};
+use config::Config;
 use infra::database::AppState;
-use std::{str::FromStr, sync::Arc};
+use rust_decimal::Decimal;
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    str::FromStr,
+    sync::Arc,
+};
 use tracing::{error, info};
 use uuid::Uuid;
 use validator::Validate;
 
+/// Builds the ETag of a printer, which changes whenever the printer is updated.
+///
+/// The related brand, toner and drum are left out, so their stock changes do not invalidate it.
+fn printer_etag(id: Uuid, updated_at: DateTime<Utc>) -> String {
+    etag(&(id, updated_at))
+}
+
 /// Retrieves the total count of printers.
 ///
 /// This endpoint counts all printers stored in the database and returns the count as an integer.
@@ -55,7 +79,7 @@ pub async fn count_printers(
 /// Retrieves a specific printer by its ID.
 ///
 /// This endpoint searches for a printer with the specified ID.
-/// If the printer is found, it returns the printer details.
+/// If the printer is found, it returns the printer details along with their `ETag`.
 #[utoipa::path(
     get,
     path = "/api/v1/printers/{id}",
@@ -66,7 +90,8 @@ pub async fn count_printers(
         ("id", description = "The unique identifier of the printer to retrieve", example = "550e8400-e29b-41d4-a716-446655440000")
     ),
     responses(
-        (status = 200, description = "Printer retrieved successfully", body = PrinterDetails),
+        (status = 200, description = "Printer retrieved successfully", body = PrinterDetails,
+            headers(("ETag" = String, description = "Tag of the current state of the printer, for `If-Match` updates"))),
         (status = 404, description = "No printer found with the specified ID"),
         (status = 500, description = "An error occurred while retrieving the printer")
     )
@@ -81,20 +106,26 @@ pub async fn search_printer(
             p.id AS printer_id, 
             p.name AS printer_name, 
             p.model AS printer_model,
-            p.brand AS brand_id, 
+            b.id AS brand_id, 
             b.name AS brand_name,
-            p.toner AS toner_id, 
+            t.id AS toner_id, 
             t.name AS toner_name, 
             t.stock AS toner_stock,
             t.price AS toner_price,
-            p.drum AS drum_id,
+            t.brand_id AS toner_brand_id,
+            t.currency AS toner_currency,
+            d.id AS drum_id,
             d.name AS drum_name, 
             d.stock AS drum_stock,
-            d.price AS drum_price
+            d.price AS drum_price,
+            d.brand_id AS drum_brand_id,
+            d.currency AS drum_currency,
+            p.updated_at AS printer_updated_at,
+            b.updated_at AS brand_updated_at
         FROM printers p
-        JOIN toners t ON p.toner = t.id
-        JOIN drums d ON p.drum = d.id
-        JOIN brands b ON p.brand = b.id
+        LEFT JOIN toners t ON p.toner = t.id
+        LEFT JOIN drums d ON p.drum = d.id
+        LEFT JOIN brands b ON p.brand = b.id
         WHERE p.id = $1
         "#,
     )
@@ -108,48 +139,111 @@ pub async fn search_printer(
 
     match printer {
         Some(row) => {
-            let printer = PrinterDetails {
-                id: row.0,
-                name: row.1,
-                model: row.2,
-                brand: Brand {
-                    id: row.3,
-                    name: row.4,
-                },
-                toner: Toner {
-                    id: row.5,
-                    name: row.6,
-                    stock: row.7,
-                    price: row.8,
-                },
-                drum: Drum {
-                    id: row.9,
-                    name: row.10,
-                    stock: row.11,
-                    price: row.12,
-                },
-            };
+            let printer = PrinterDetails::from(row);
+            let etag = printer_etag(printer.id, printer.updated_at);
 
             info!("Printer found: {id}");
-            Ok((StatusCode::OK, Json(Some(printer))))
+            Ok((StatusCode::OK, [(header::ETAG, etag)], Json(Some(printer))))
         }
         None => {
             error!("No printer found.");
-            Err(ApiError::IdNotFound)
+            Err(ApiError::IdNotFound {
+                resource: "Printer",
+            })
         }
     }
 }
 
+/// Checks whether a printer exists.
+///
+/// This endpoint answers with an empty body, using an existence query instead of fetching the printer.
+/// Useful for clients that only need to know whether the ID is valid.
+#[utoipa::path(
+    head,
+    path = "/api/v1/printers/{id}",
+    tags = ["Printers"],
+    summary = "Check whether a printer exists.",
+    description = "This endpoint checks whether a printer with the given ID exists, without returning its details.",
+    params(
+        ("id", description = "The unique identifier of the printer to check", example = "550e8400-e29b-41d4-a716-446655440000")
+    ),
+    responses(
+        (status = 200, description = "Printer exists"),
+        (status = 404, description = "No printer found with the specified ID"),
+        (status = 500, description = "An error occurred while checking the printer")
+    )
+)]
+pub async fn check_printer(
+    Path(id): Path<Uuid>,
+    State(state): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, ApiError> {
+    printer_exists(state, id).await?;
+
+    Ok(StatusCode::OK)
+}
+
+/// Checks which of several printers exist.
+///
+/// This endpoint looks up all the given IDs in a single query, so clients can validate them before bulk operations.
+/// Returns an object mapping each ID to whether a printer with that ID exists.
+#[utoipa::path(
+    post,
+    path = "/api/v1/printers/exists",
+    tags = ["Printers"],
+    summary = "Check whether several printers exist.",
+    description = "This endpoint answers with an object holding `true` or `false` for each of the given printer IDs. At most 1000 IDs can be checked at once.",
+    request_body = ExistsRequest,
+    responses(
+        (status = 200, description = "Existence checked successfully", body = HashMap<String, bool>),
+        (status = 400, description = "Too many IDs"),
+        (status = 500, description = "An error occurred while checking the printers")
+    )
+)]
+pub async fn check_printers(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<ExistsRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    // Validations
+    request.validate()?;
+
+    let existing: HashSet<Uuid> =
+        sqlx::query_scalar::<_, Uuid>(r#"SELECT id FROM printers WHERE id = ANY($1);"#)
+            .bind(&request.ids)
+            .fetch_all(&state.db)
+            .await
+            .map_err(|e| {
+                error!("Error checking printer IDs: {e}");
+                ApiError::DatabaseError(e)
+            })?
+            .into_iter()
+            .collect();
+
+    let exists: HashMap<Uuid, bool> = request
+        .ids
+        .iter()
+        .map(|id| (*id, existing.contains(id)))
+        .collect();
+
+    info!(
+        "Checked {} printer IDs, {} exist",
+        exists.len(),
+        existing.len()
+    );
+    Ok(Json(exists))
+}
+
 /// Retrieves a list of all printers.
 ///
-/// This endpoint fetches all printers stored in the database.
+/// This endpoint fetches all printers stored in the database, with their brand and supplies
+/// unless `compact` is set, in which case only their IDs and names are listed.
 /// If there are no printers, returns an empty array.
 #[utoipa::path(
     get,
     path = "/api/v1/printers",
     tags = ["Printers"],
     summary = "List all printers.",
-    description = "Fetches all printers stored in the database. If there are no printers, returns an empty array.",
+    description = "Fetches all printers stored in the database. If there are no printers, returns an empty array. With `compact=true` each printer is a `CompactItem` holding only its `id` and `name`.",
+    params(CompactParams),
     responses(
         (status = 200, description = "Printers retrieved successfully", body = Vec<PrinterDetails>),
         (status = 404, description = "No printers found in the database"),
@@ -157,28 +251,49 @@ pub async fn search_printer(
     )
 )]
 pub async fn show_printers(
+    Query(compact): Query<CompactParams>,
     State(state): State<Arc<AppState>>,
 ) -> Result<impl IntoResponse, ApiError> {
+    if compact.compact.unwrap_or(false) {
+        let printers =
+            sqlx::query_as::<_, CompactItem>(r#"SELECT id, name FROM printers ORDER BY name;"#)
+                .fetch_all(&state.db)
+                .await
+                .map_err(|e| {
+                    error!("Error listing compact printers: {e}");
+                    ApiError::DatabaseError(e)
+                })?;
+
+        info!("Printers listed successfully in compact form");
+        return Ok(Json(printers).into_response());
+    }
+
     let printers = sqlx::query_as::<_, PrinterView>(
         r#"
         SELECT 
             p.id AS printer_id, 
             p.name AS printer_name, 
             p.model AS printer_model,
-            p.brand AS brand_id, 
+            b.id AS brand_id, 
             b.name AS brand_name,
-            p.toner AS toner_id, 
+            t.id AS toner_id, 
             t.name AS toner_name, 
             t.stock AS toner_stock,
             t.price AS toner_price,
-            p.drum AS drum_id,
+            t.brand_id AS toner_brand_id,
+            t.currency AS toner_currency,
+            d.id AS drum_id,
             d.name AS drum_name, 
             d.stock AS drum_stock,
-            d.price AS drum_price
+            d.price AS drum_price,
+            d.brand_id AS drum_brand_id,
+            d.currency AS drum_currency,
+            p.updated_at AS printer_updated_at,
+            b.updated_at AS brand_updated_at
         FROM printers p
-        JOIN toners t ON p.toner = t.id
-        JOIN drums d ON p.drum = d.id
-        JOIN brands b ON p.brand = b.id
+        LEFT JOIN toners t ON p.toner = t.id
+        LEFT JOIN drums d ON p.drum = d.id
+        LEFT JOIN brands b ON p.brand = b.id
         "#,
     )
     .fetch_all(&state.db)
@@ -188,39 +303,16 @@ pub async fn show_printers(
         ApiError::DatabaseError(e)
     })?;
 
-    let printers: Vec<PrinterDetails> = printers
-        .into_iter()
-        .map(|row| PrinterDetails {
-            id: row.0,
-            name: row.1,
-            model: row.2,
-            brand: Brand {
-                id: row.3,
-                name: row.4,
-            },
-            toner: Toner {
-                id: row.5,
-                name: row.6,
-                stock: row.7,
-                price: row.8,
-            },
-            drum: Drum {
-                id: row.9,
-                name: row.10,
-                stock: row.11,
-                price: row.12,
-            },
-        })
-        .collect();
+    let printers: Vec<PrinterDetails> = printers.into_iter().map(PrinterDetails::from).collect();
 
     info!("Printers listed successfully");
-    Ok(Json(printers))
+    Ok(Json(printers).into_response())
 }
 
 /// Create a new printer.
 ///
 /// This endpoint creates a new printer by providing its details.
-/// Validates the printer's name for length and emptiness, checks for duplicates,
+/// Validates the printer's name for length and emptiness, normalizes its model, checks for duplicates,
 /// and inserts the new printer into the database if all validations pass.
 #[utoipa::path(
     post,
@@ -229,30 +321,36 @@ pub async fn show_printers(
     summary = "Create a new printer.",
     description = "This endpoint creates a new printer in the database with the provided details.",
     request_body = CreatePrinterRequest,
+    params(
+        ReturnParams,
+        ("Prefer" = Option<String>, Header, description = "`return=representation` returns the created printer instead of its ID")
+    ),
     responses(
-        (status = 201, description = "Printer created successfully", body = Uuid),
-        (status = 400, description = "Invalid input, including empty name or name too short/long"),
+        (status = 201, description = "Printer created successfully. Answers with the full printer instead of its ID when `return=representation` is requested", body = Uuid),
+        (status = 400, description = "Invalid input, including empty name or name or model too short/long"),
         (status = 409, description = "Conflict: Printer with the same name already exists"),
         (status = 500, description = "An error occurred while creating the printer")
     )
 )]
 pub async fn create_printer(
+    preference: ReturnPreference,
     State(state): State<Arc<AppState>>,
     Json(request): Json<CreatePrinterRequest>,
 ) -> Result<impl IntoResponse, ApiError> {
     // Validations
     request.validate()?;
-    is_printer_unique(state.clone(), request.name.clone()).await?;
+    let existing_id = is_printer_unique(state.clone(), request.name.clone()).await?;
+    reject_taken_name(request.name.clone(), existing_id)?;
 
     let new_printer = Printer::new(
         &request.name,
-        &request.model,
+        &normalize_model(&request.model),
         Uuid::from_str(&request.brand).unwrap(),
         Uuid::from_str(&request.toner).unwrap(),
         Uuid::from_str(&request.drum).unwrap(),
     );
 
-    sqlx::query(r#"INSERT INTO printers (id, name, model, brand, toner, drum) VALUES ($1, $2, $3, $4, $5, $6);"#,
+    let result = sqlx::query_as::<_, Printer>(r#"INSERT INTO printers (id, name, model, brand, toner, drum, updated_at) VALUES ($1, $2, $3, $4, $5, $6, $7) RETURNING id, name, model, brand, toner, drum, updated_at;"#,
     )
     .bind(new_printer.id)
     .bind(new_printer.name)
@@ -260,15 +358,25 @@ pub async fn create_printer(
     .bind(new_printer.brand)
     .bind(new_printer.toner)
     .bind(new_printer.drum)
-    .execute(&state.db)
-    .await
-    .map_err(|e| {
-        error!("Error creating printer: {e}");
-        ApiError::DatabaseError(e)
-    })?;
+    .bind(new_printer.updated_at)
+    .fetch_one(&state.db)
+    .await;
+
+    let created = match result {
+        Ok(printer) => printer,
+        Err(e) => {
+            error!("Error creating printer: {e}");
+            let check = is_printer_unique(state.clone(), request.name.clone());
+            return Err(conflict_or_database_error(e, request.name, check).await);
+        }
+    };
 
     info!("Printer created! ID: {}", &new_printer.id);
-    Ok((StatusCode::CREATED, Json(new_printer.id)))
+    Ok((
+        StatusCode::CREATED,
+        location(&format!("/printers/{}", new_printer.id)),
+        preference.body(new_printer.id, Some(created)),
+    ))
 }
 
 /// Updates an existing printer.
@@ -283,18 +391,22 @@ pub async fn create_printer(
     path = "/api/v1/printers",
     tags = ["Printers"],
     summary = "Update an existing printer.",
-    description = "This endpoint updates the details of an existing printer in the database.",
+    description = "This endpoint updates the details of an existing printer in the database. With an `If-Match` header holding the `ETag` of a previous GET, the update is rejected with `412` if the printer changed since.",
     request_body = UpdatePrinterRequest,
+    params(
+        ("If-Match" = Option<String>, Header, description = "ETag the printer must still have for the update to apply")
+    ),
     responses(
         (status = 200, description = "Printer updated successfully", body = Uuid),
-        (status = 400, description = "Invalid input, including empty name or name too short/long"),
+        (status = 400, description = "Invalid input, including empty name or name or model too short/long"),
         (status = 404, description = "Printer ID not found"),
-        (status = 304, description = "Printer not modified"),
         (status = 409, description = "Conflict: Printer with the same name already exists"),
+        (status = 412, description = "The printer no longer matches the `If-Match` ETag"),
         (status = 500, description = "An error occurred while updating the printer")
     )
 )]
 pub async fn update_printer(
+    if_match: IfMatch,
     State(state): State<Arc<AppState>>,
     Json(request): Json<UpdatePrinterRequest>,
 ) -> Result<impl IntoResponse, ApiError> {
@@ -304,24 +416,50 @@ pub async fn update_printer(
 
     let printer_id = request.id;
     let new_name = request.name;
-    let new_model = request.model;
+    let new_model = request.model.as_deref().map(normalize_model);
     let new_brand_id = request.brand.map(|b| Uuid::from_str(&b).ok()).flatten();
     let new_toner_id = request.toner.map(|t| Uuid::from_str(&t).ok()).flatten();
     let new_drum_id = request.drum.map(|d| Uuid::from_str(&d).ok()).flatten();
 
+    let mut tx = state.db.begin().await.map_err(|e| {
+        error!("Error starting transaction: {e}");
+        ApiError::DatabaseError(e)
+    })?;
+
+    // Lock the printer and check that it still matches the ETag the client read
+    if if_match.is_present() {
+        let updated_at = sqlx::query_scalar::<_, DateTime<Utc>>(
+            r#"SELECT updated_at FROM printers WHERE id = $1 FOR UPDATE;"#,
+        )
+        .bind(printer_id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| {
+            error!("Error fetching printer by ID: {e}");
+            ApiError::DatabaseError(e)
+        })?;
+        if_match.check(&printer_etag(printer_id, updated_at))?;
+    }
+
     let mut updated = false;
 
     // Update name if provided
     if let Some(name) = new_name {
-        sqlx::query(r#"UPDATE printers SET name = $1 WHERE id = $2;"#)
+        let existing_id =
+            is_printer_name_available(state.clone(), name.clone(), printer_id).await?;
+        reject_taken_name(name.clone(), existing_id)?;
+
+        let result = sqlx::query(r#"UPDATE printers SET name = $1 WHERE id = $2;"#)
             .bind(&name)
             .bind(printer_id)
-            .execute(&state.db)
-            .await
-            .map_err(|e| {
-                error!("Error updating printer name: {e}");
-                ApiError::DatabaseError(e)
-            })?;
+            .execute(&mut *tx)
+            .await;
+
+        if let Err(e) = result {
+            error!("Error updating printer name: {e}");
+            let check = is_printer_name_available(state.clone(), name.clone(), printer_id);
+            return Err(conflict_or_database_error(e, name, check).await);
+        }
         updated = true;
     }
 
@@ -330,7 +468,7 @@ pub async fn update_printer(
         sqlx::query(r#"UPDATE printers SET model = $1 WHERE id = $2;"#)
             .bind(&model)
             .bind(printer_id)
-            .execute(&state.db)
+            .execute(&mut *tx)
             .await
             .map_err(|e| {
                 error!("Error updating printer model: {e}");
@@ -344,7 +482,7 @@ pub async fn update_printer(
         sqlx::query(r#"UPDATE printers SET brand = $1 WHERE id = $2;"#)
             .bind(brand)
             .bind(printer_id)
-            .execute(&state.db)
+            .execute(&mut *tx)
             .await
             .map_err(|e| {
                 error!("Error updating printer brand: {e}");
@@ -358,7 +496,7 @@ pub async fn update_printer(
         sqlx::query(r#"UPDATE printers SET toner = $1 WHERE id = $2;"#)
             .bind(toner)
             .bind(printer_id)
-            .execute(&state.db)
+            .execute(&mut *tx)
             .await
             .map_err(|e| {
                 error!("Error updating printer toner: {e}");
@@ -372,7 +510,7 @@ pub async fn update_printer(
         sqlx::query(r#"UPDATE printers SET drum = $1 WHERE id = $2;"#)
             .bind(drum)
             .bind(printer_id)
-            .execute(&state.db)
+            .execute(&mut *tx)
             .await
             .map_err(|e| {
                 error!("Error updating printer drum: {e}");
@@ -381,14 +519,26 @@ pub async fn update_printer(
         updated = true;
     }
 
+    // Nothing to change, the printer is left as it was
     if !updated {
-        error!(
-            "No updates were made for the provided printer ID: {}",
-            &printer_id
-        );
-        return Err(ApiError::NotModified);
+        info!("No fields to update for printer ID: {}", &printer_id);
+        return Ok(Json(printer_id));
     }
 
+    sqlx::query(r#"UPDATE printers SET updated_at = NOW() WHERE id = $1;"#)
+        .bind(printer_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| {
+            error!("Error updating printer timestamp: {e}");
+            ApiError::DatabaseError(e)
+        })?;
+
+    tx.commit().await.map_err(|e| {
+        error!("Error committing printer update: {e}");
+        ApiError::DatabaseError(e)
+    })?;
+
     info!("Printer updated! ID: {}", &printer_id);
     Ok(Json(printer_id))
 }
@@ -431,3 +581,528 @@ pub async fn delete_printer(
     info!("Printer deleted! ID: {}", &request.id);
     Ok(Json("Printer deleted!"))
 }
+
+/// Lists the supplies compatible with a printer.
+///
+/// This endpoint returns the toners and drums registered as compatible with the printer,
+/// besides the toner and drum it is configured with.
+#[utoipa::path(
+    get,
+    path = "/api/v1/printers/{id}/compatible",
+    tags = ["Printers"],
+    summary = "List the supplies compatible with a printer.",
+    description = "This endpoint lists the toners and drums registered as compatible with the printer. If there are none, returns an empty array.",
+    params(
+        ("id", description = "The unique identifier of the printer", example = "550e8400-e29b-41d4-a716-446655440000")
+    ),
+    responses(
+        (status = 200, description = "Compatible supplies retrieved successfully", body = Vec<CompatibleSupply>),
+        (status = 404, description = "No printer found with the specified ID"),
+        (status = 500, description = "An error occurred while retrieving the compatible supplies")
+    )
+)]
+pub async fn show_compatible_supplies(
+    Path(id): Path<Uuid>,
+    State(state): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, ApiError> {
+    // Validations
+    printer_exists(state.clone(), id).await?;
+
+    let supplies = sqlx::query_as::<_, CompatibleSupply>(
+        r#"
+        SELECT 'toner' AS type, t.id, t.name
+        FROM printer_compatible_supplies c
+        JOIN toners t ON c.supply_id = t.id
+        WHERE c.printer_id = $1
+        UNION ALL
+        SELECT 'drum' AS type, d.id, d.name
+        FROM printer_compatible_supplies c
+        JOIN drums d ON c.supply_id = d.id
+        WHERE c.printer_id = $1;
+        "#,
+    )
+    .bind(id)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| {
+        error!("Error listing compatible supplies of printer {id}: {e}");
+        ApiError::DatabaseError(e)
+    })?;
+
+    info!("Compatible supplies of printer {id} listed successfully");
+    Ok(Json(supplies))
+}
+
+/// Retrieves the lifetime consumption of a printer.
+///
+/// This endpoint sums the movements of the printer by item, estimating their cost with the items' current prices.
+/// A printer without movements gets zeroes and empty lists.
+#[utoipa::path(
+    get,
+    path = "/api/v1/printers/{id}/consumption",
+    tags = ["Printers"],
+    summary = "Get the lifetime consumption of a printer.",
+    description = "Returns the net quantity of each toner and drum used by the printer, as recorded by its movements, and its cost at the item's current price. Costs are totaled per currency.",
+    params(
+        ("id", description = "The unique identifier of the printer", example = "550e8400-e29b-41d4-a716-446655440000")
+    ),
+    responses(
+        (status = 200, description = "Consumption retrieved successfully", body = PrinterConsumption),
+        (status = 404, description = "No printer found with the specified ID"),
+        (status = 500, description = "An error occurred while retrieving the consumption")
+    )
+)]
+pub async fn show_consumption(
+    Path(id): Path<Uuid>,
+    State(state): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, ApiError> {
+    // Validations
+    printer_exists(state.clone(), id).await?;
+
+    // Movements out of stock have negative quantities, so consumption is their negated sum
+    let items = sqlx::query_as::<_, ItemConsumption>(
+        r#"
+        SELECT
+            'toner' AS type,
+            t.id AS item_id,
+            t.name,
+            (-SUM(m.quantity))::bigint AS quantity,
+            -SUM(m.quantity) * t.price AS cost,
+            COALESCE(t.currency, $2)::text AS currency
+        FROM movements m
+        JOIN toners t ON m.item_id = t.id
+        WHERE m.printer_id = $1
+        GROUP BY t.id
+        UNION ALL
+        SELECT
+            'drum' AS type,
+            d.id AS item_id,
+            d.name,
+            (-SUM(m.quantity))::bigint AS quantity,
+            -SUM(m.quantity) * d.price AS cost,
+            COALESCE(d.currency, $2)::text AS currency
+        FROM movements m
+        JOIN drums d ON m.item_id = d.id
+        WHERE m.printer_id = $1
+        GROUP BY d.id
+        ORDER BY name;
+        "#,
+    )
+    .bind(id)
+    .bind(Config::default_currency())
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| {
+        error!("Error retrieving consumption of printer {id}: {e}");
+        ApiError::DatabaseError(e)
+    })?;
+
+    let mut costs = BTreeMap::<&str, Decimal>::new();
+    for item in &items {
+        if let Some(cost) = item.cost {
+            *costs.entry(&item.currency).or_default() += cost;
+        }
+    }
+
+    let consumption = PrinterConsumption {
+        printer_id: id,
+        total_quantity: items.iter().map(|item| item.quantity).sum(),
+        estimated_cost: costs
+            .into_iter()
+            .map(|(currency, amount)| CurrencyAmount {
+                currency: String::from(currency),
+                amount,
+            })
+            .collect(),
+        items,
+    };
+
+    info!("Consumption of printer {id} retrieved");
+    Ok(Json(consumption))
+}
+
+/// Registers a supply as compatible with a printer.
+///
+/// This endpoint links a toner or drum to the printer.
+/// Adding a supply that is already compatible leaves it unchanged.
+#[utoipa::path(
+    post,
+    path = "/api/v1/printers/{id}/compatible/{supply_id}",
+    tags = ["Printers"],
+    summary = "Add a compatible supply to a printer.",
+    description = "This endpoint registers a toner or drum as compatible with the printer. Returns `201` when the supply is added, or `200` if it was already compatible.",
+    params(
+        ("id", description = "The unique identifier of the printer", example = "550e8400-e29b-41d4-a716-446655440000"),
+        ("supply_id", description = "The unique identifier of the toner or drum", example = "550e8400-e29b-41d4-a716-446655440000")
+    ),
+    responses(
+        (status = 200, description = "Supply was already compatible", body = String),
+        (status = 201, description = "Compatible supply added successfully", body = String),
+        (status = 404, description = "Printer or supply ID not found"),
+        (status = 500, description = "An error occurred while adding the compatible supply")
+    )
+)]
+pub async fn add_compatible_supply(
+    Path((id, supply_id)): Path<(Uuid, Uuid)>,
+    State(state): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, ApiError> {
+    // Validations
+    printer_exists(state.clone(), id).await?;
+
+    let supply_exists = sqlx::query_scalar::<_, bool>(
+        r#"
+        SELECT EXISTS(SELECT 1 FROM toners WHERE id = $1)
+            OR EXISTS(SELECT 1 FROM drums WHERE id = $1);
+        "#,
+    )
+    .bind(supply_id)
+    .fetch_one(&state.db)
+    .await
+    .map_err(|e| {
+        error!("Error fetching supply by ID: {e}");
+        ApiError::DatabaseError(e)
+    })?;
+
+    if !supply_exists {
+        error!("Supply with ID '{supply_id}' not found in toners or drums.");
+        return Err(ApiError::IdNotFound { resource: "Supply" });
+    }
+
+    let result = sqlx::query(
+        r#"
+        INSERT INTO printer_compatible_supplies (printer_id, supply_id) VALUES ($1, $2)
+        ON CONFLICT DO NOTHING;
+        "#,
+    )
+    .bind(id)
+    .bind(supply_id)
+    .execute(&state.db)
+    .await
+    .map_err(|e| {
+        error!("Error adding compatible supply: {e}");
+        ApiError::DatabaseError(e)
+    })?;
+
+    if result.rows_affected() == 0 {
+        info!("Supply {supply_id} was already compatible with printer {id}");
+        return Ok((StatusCode::OK, Json("Supply already compatible!")));
+    }
+
+    info!("Compatible supply added! Printer: {id}, supply: {supply_id}");
+    Ok((StatusCode::CREATED, Json("Compatible supply added!")))
+}
+
+/// Removes a compatible supply from a printer.
+///
+/// This endpoint unlinks a toner or drum from the printer's compatible supplies.
+#[utoipa::path(
+    delete,
+    path = "/api/v1/printers/{id}/compatible/{supply_id}",
+    tags = ["Printers"],
+    summary = "Remove a compatible supply from a printer.",
+    description = "This endpoint removes a toner or drum from the supplies compatible with the printer.",
+    params(
+        ("id", description = "The unique identifier of the printer", example = "550e8400-e29b-41d4-a716-446655440000"),
+        ("supply_id", description = "The unique identifier of the toner or drum", example = "550e8400-e29b-41d4-a716-446655440000")
+    ),
+    responses(
+        (status = 200, description = "Compatible supply removed successfully", body = String),
+        (status = 404, description = "The supply is not compatible with the printer"),
+        (status = 500, description = "An error occurred while removing the compatible supply")
+    )
+)]
+pub async fn remove_compatible_supply(
+    Path((id, supply_id)): Path<(Uuid, Uuid)>,
+    State(state): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, ApiError> {
+    let result = sqlx::query(
+        r#"DELETE FROM printer_compatible_supplies WHERE printer_id = $1 AND supply_id = $2;"#,
+    )
+    .bind(id)
+    .bind(supply_id)
+    .execute(&state.db)
+    .await
+    .map_err(|e| {
+        error!("Error removing compatible supply: {e}");
+        ApiError::DatabaseError(e)
+    })?;
+
+    if result.rows_affected() == 0 {
+        error!("Supply {supply_id} is not compatible with printer {id}.");
+        return Err(ApiError::IdNotFound {
+            resource: "Compatible supply",
+        });
+    }
+
+    info!("Compatible supply removed! Printer: {id}, supply: {supply_id}");
+    Ok(Json("Compatible supply removed!"))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::{
+        app, create_brand, create_drum, create_printer, create_toner, empty_request, json_request,
+        send,
+    };
+    use axum::http::{Method, StatusCode};
+    use serde_json::{json, Value};
+    use sqlx::PgPool;
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn printer_reports_the_brand_and_currency_of_its_supplies(pool: PgPool) {
+        let app = app(pool);
+        let brand = create_brand(&app, "Kyocera").await;
+        let drum = create_drum(&app, "DK-1150", 0).await;
+
+        let (_, toner) = send(
+            &app,
+            json_request(
+                Method::POST,
+                "/api/v1/supplies/toners",
+                json!({ "name": "TK-1170", "brand_id": brand, "price": 45.5, "currency": "EUR" }),
+            ),
+        )
+        .await;
+        let (status, printer) = send(
+            &app,
+            json_request(
+                Method::POST,
+                "/api/v1/printers",
+                json!({ "name": "ECOSYS M2040", "model": "M2040dn", "brand": brand, "toner": toner, "drum": drum }),
+            ),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+
+        let printer = printer.as_str().unwrap();
+        let (status, details) = send(
+            &app,
+            empty_request(Method::GET, &format!("/api/v1/printers/{printer}")),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(details["toner"]["brand_id"], json!(brand));
+        assert_eq!(details["toner"]["currency"], "EUR");
+        assert_eq!(details["drum"]["brand_id"], Value::Null);
+    }
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn updates_without_changes_answer_with_the_printer(pool: PgPool) {
+        let app = app(pool);
+        let printer = create_printer(&app, "ECOSYS M2040").await;
+
+        let (status, body) = send(
+            &app,
+            json_request(Method::PUT, "/api/v1/printers", json!({ "id": printer })),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, json!(printer));
+    }
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn updates_advance_the_updated_at(pool: PgPool) {
+        let app = app(pool);
+        let printer = create_printer(&app, "ECOSYS M2040").await;
+        let updated_at = |printer: Value| {
+            printer["updated_at"]
+                .as_str()
+                .unwrap()
+                .parse::<chrono::DateTime<chrono::Utc>>()
+                .unwrap()
+        };
+
+        let (_, created) = send(
+            &app,
+            empty_request(Method::GET, &format!("/api/v1/printers/{printer}")),
+        )
+        .await;
+        let (status, _) = send(
+            &app,
+            json_request(
+                Method::PUT,
+                "/api/v1/printers",
+                json!({ "id": printer, "name": "ECOSYS M2540" }),
+            ),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let (_, updated) = send(
+            &app,
+            empty_request(Method::GET, &format!("/api/v1/printers/{printer}")),
+        )
+        .await;
+
+        assert_eq!(updated["name"], "ECOSYS M2540");
+        assert!(updated_at(updated) > updated_at(created));
+    }
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn printers_with_a_missing_brand_are_still_returned(pool: PgPool) {
+        let app = app(pool.clone());
+        let printer = create_printer(&app, "ECOSYS M2040").await;
+
+        // Force-delete the brand, skipping the foreign key checks
+        let mut conn = pool.acquire().await.unwrap();
+        for statement in [
+            "SET session_replication_role = replica;",
+            "DELETE FROM brands WHERE name = 'ECOSYS M2040 Brand';",
+            "RESET session_replication_role;",
+        ] {
+            sqlx::query(statement).execute(&mut *conn).await.unwrap();
+        }
+        drop(conn);
+
+        let (status, details) = send(
+            &app,
+            empty_request(Method::GET, &format!("/api/v1/printers/{printer}")),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(details["brand"], Value::Null);
+        assert_eq!(details["toner"]["name"], "ECOSYS M2040 Toner");
+    }
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn compatible_supplies_can_be_added_listed_and_removed(pool: PgPool) {
+        let app = app(pool);
+        let printer = create_printer(&app, "ECOSYS M2040").await;
+        let drum = create_drum(&app, "DK-1150", 0).await;
+        let compatible = format!("/api/v1/printers/{printer}/compatible");
+        let supply = format!("{compatible}/{drum}");
+
+        let (status, _) = send(&app, empty_request(Method::POST, &supply)).await;
+        assert_eq!(status, StatusCode::CREATED);
+        let (status, _) = send(&app, empty_request(Method::POST, &supply)).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let (status, supplies) = send(&app, empty_request(Method::GET, &compatible)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(
+            supplies,
+            json!([{ "type": "drum", "id": drum, "name": "DK-1150" }])
+        );
+
+        let (status, _) = send(&app, empty_request(Method::DELETE, &supply)).await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, _) = send(&app, empty_request(Method::DELETE, &supply)).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (_, supplies) = send(&app, empty_request(Method::GET, &compatible)).await;
+        assert_eq!(supplies, json!([]));
+    }
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn models_are_stored_with_collapsed_whitespace(pool: PgPool) {
+        let app = app(pool);
+        let brand = create_brand(&app, "Kyocera").await;
+        let toner = create_toner(&app, "TK-1170", 0).await;
+        let drum = create_drum(&app, "DK-1150", 0).await;
+        let create = |model: &str| {
+            json_request(
+                Method::POST,
+                "/api/v1/printers",
+                json!({ "name": "ECOSYS M2040", "model": model, "brand": brand, "toner": toner, "drum": drum }),
+            )
+        };
+
+        let (status, _) = send(&app, create("  M  ")).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (status, printer) = send(&app, create("  ECOSYS \t M2040dn  ")).await;
+        assert_eq!(status, StatusCode::CREATED);
+        let uri = format!("/api/v1/printers/{}", printer.as_str().unwrap());
+        let (_, details) = send(&app, empty_request(Method::GET, &uri)).await;
+        assert_eq!(details["model"], "ECOSYS M2040dn");
+
+        let (status, _) = send(
+            &app,
+            json_request(
+                Method::PUT,
+                "/api/v1/printers",
+                json!({ "id": printer, "model": " ECOSYS   M2540dn" }),
+            ),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let (_, details) = send(&app, empty_request(Method::GET, &uri)).await;
+        assert_eq!(details["model"], "ECOSYS M2540dn");
+    }
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn consumption_is_costed_at_the_price_of_each_item(pool: PgPool) {
+        let app = app(pool);
+        let printer = create_printer(&app, "ECOSYS M2040").await;
+        let mut items = Vec::new();
+        for (collection, name, price) in [("toners", "TK-1170", 40.0), ("drums", "DK-1150", 100.0)]
+        {
+            let (status, id) = send(
+                &app,
+                json_request(
+                    Method::POST,
+                    &format!("/api/v1/supplies/{collection}"),
+                    json!({ "name": name, "stock": 10, "price": price }),
+                ),
+            )
+            .await;
+            assert_eq!(status, StatusCode::CREATED);
+            items.push(id);
+        }
+        for (item_id, item_type, quantity) in [(&items[0], "toner", -2), (&items[1], "drum", -1)] {
+            let (status, _) = send(
+                &app,
+                json_request(
+                    Method::POST,
+                    "/api/v1/movements",
+                    json!({ "printer_id": printer, "item_id": item_id, "item_type": item_type, "quantity": quantity }),
+                ),
+            )
+            .await;
+            assert_eq!(status, StatusCode::CREATED);
+        }
+
+        let (status, consumption) = send(
+            &app,
+            empty_request(
+                Method::GET,
+                &format!("/api/v1/printers/{printer}/consumption"),
+            ),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(consumption["total_quantity"], 3);
+        assert_eq!(
+            consumption["estimated_cost"],
+            json!([{ "currency": "USD", "amount": 180.0 }])
+        );
+        assert_eq!(
+            consumption["items"],
+            json!([
+                { "type": "drum", "item_id": items[1], "name": "DK-1150", "quantity": 1, "cost": 100.0, "currency": "USD" },
+                { "type": "toner", "item_id": items[0], "name": "TK-1170", "quantity": 2, "cost": 80.0, "currency": "USD" },
+            ])
+        );
+
+        let idle = create_printer(&app, "ECOSYS P2235").await;
+        let (_, consumption) = send(
+            &app,
+            empty_request(Method::GET, &format!("/api/v1/printers/{idle}/consumption")),
+        )
+        .await;
+        assert_eq!(consumption["total_quantity"], 0);
+        assert_eq!(consumption["items"], json!([]));
+
+        let (status, _) = send(
+            &app,
+            empty_request(
+                Method::GET,
+                "/api/v1/printers/550e8400-e29b-41d4-a716-446655440000/consumption",
+            ),
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+}
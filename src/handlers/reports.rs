@@ -0,0 +1,478 @@
+use crate::{
+    errors::api_error::ApiError,
+    models::report::{
+        BrandReport, BrokenReference, MovementsByDay, ReportRangeParams, StockDiscrepancy,
+    },
+    validations::timezone::parse_utc_offset,
+};
+use axum::{
+    extract::{Query, State},
+    response::IntoResponse,
+    Json,
+};
+use config::Config;
+use infra::database::AppState;
+use std::sync::Arc;
+use tracing::{error, info};
+use validator::Validate;
+
+/// Retrieves the movements aggregated by day.
+///
+/// This endpoint sums the quantities and counts the movements of each day in the range,
+/// by UTC date unless a `tz` offset is given.
+/// Days without movements are included with zeroes, so the result can be plotted directly.
+#[utoipa::path(
+    get,
+    path = "/api/v1/reports/movements-by-day",
+    tags = ["Reports"],
+    summary = "Get movement totals by day.",
+    description = "Returns one entry per day between `from` and `to` (inclusive) with the total quantity and number of movements of that day. The range must not cover more than 366 days.",
+    params(ReportRangeParams),
+    responses(
+        (status = 200, description = "Report retrieved successfully", body = Vec<MovementsByDay>),
+        (status = 400, description = "Invalid or too wide date range"),
+        (status = 500, description = "An error occurred while retrieving the report")
+    )
+)]
+pub async fn movements_by_day(
+    Query(params): Query<ReportRangeParams>,
+    State(state): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, ApiError> {
+    // Validations
+    params.validate()?;
+    let offset = params
+        .tz
+        .as_deref()
+        .and_then(parse_utc_offset)
+        .map_or(0, |offset| offset.local_minus_utc());
+
+    let report = sqlx::query_as::<_, MovementsByDay>(
+        r#"
+        SELECT
+            day::date AS date,
+            COALESCE(SUM(m.quantity), 0)::bigint AS total_quantity,
+            COUNT(m.id) AS count
+        FROM generate_series($1::date, $2::date, INTERVAL '1 day') AS day
+        LEFT JOIN movements m
+            ON ((m.created_at AT TIME ZONE 'UTC') + $3 * INTERVAL '1 second')::date = day::date
+        GROUP BY day
+        ORDER BY day;
+        "#,
+    )
+    .bind(params.from)
+    .bind(params.to)
+    .bind(offset)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| {
+        error!("Error retrieving movements by day: {e}");
+        ApiError::DatabaseError(e)
+    })?;
+
+    info!("Movements by day report retrieved");
+    Ok(Json(report))
+}
+
+/// Retrieves the printer count and stock value of each brand.
+///
+/// This endpoint counts the printers of each brand and sums the stock value of the toners and drums they use.
+/// Values in different currencies are never added up: a brand gets one entry per currency of its supplies.
+/// Brands without printers are included with zeroes, in the default currency.
+#[utoipa::path(
+    get,
+    path = "/api/v1/reports/by-brand",
+    tags = ["Reports"],
+    summary = "Get printer counts and stock value by brand.",
+    description = "Returns one entry per brand and currency with the brand's number of printers and the total value (`stock * price`) of the distinct supplies in that currency those printers use. Supplies without a currency are counted in the `DEFAULT_CURRENCY`.",
+    responses(
+        (status = 200, description = "Report retrieved successfully", body = Vec<BrandReport>),
+        (status = 500, description = "An error occurred while retrieving the report")
+    )
+)]
+pub async fn by_brand(State(state): State<Arc<AppState>>) -> Result<impl IntoResponse, ApiError> {
+    let report = sqlx::query_as::<_, BrandReport>(
+        r#"
+        WITH brand_supplies AS (
+            SELECT p.brand, t.id, t.stock, t.price, t.currency
+            FROM toners t
+            JOIN printers p ON p.toner = t.id
+            UNION
+            SELECT p.brand, d.id, d.stock, d.price, d.currency
+            FROM drums d
+            JOIN printers p ON p.drum = d.id
+        )
+        SELECT
+            b.id AS brand_id,
+            b.name AS brand_name,
+            (SELECT COUNT(*) FROM printers p WHERE p.brand = b.id) AS printer_count,
+            COALESCE(s.currency, $1)::text AS currency,
+            COALESCE(SUM(COALESCE(s.stock, 0) * COALESCE(s.price, 0)), 0)::numeric AS stock_value
+        FROM brands b
+        LEFT JOIN brand_supplies s ON s.brand = b.id
+        GROUP BY b.id, b.name, COALESCE(s.currency, $1)
+        ORDER BY b.name, COALESCE(s.currency, $1);
+        "#,
+    )
+    .bind(Config::default_currency())
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| {
+        error!("Error retrieving brand report: {e}");
+        ApiError::DatabaseError(e)
+    })?;
+
+    info!("Brand report retrieved");
+    Ok(Json(report))
+}
+
+/// Retrieves the broken references in the dataset.
+///
+/// This endpoint looks for printers referencing missing brands, toners or drums,
+/// and movements referencing missing printers or items. Returns an empty array when the data is consistent.
+#[utoipa::path(
+    get,
+    path = "/api/v1/reports/integrity",
+    tags = ["Reports"],
+    summary = "Check the referential integrity of the data.",
+    description = "Scans printers and movements for references to rows that no longer exist and returns one entry per broken reference. Returns an empty array if none is found.",
+    responses(
+        (status = 200, description = "Integrity report retrieved successfully", body = Vec<BrokenReference>),
+        (status = 500, description = "An error occurred while retrieving the report")
+    )
+)]
+pub async fn integrity(State(state): State<Arc<AppState>>) -> Result<impl IntoResponse, ApiError> {
+    let report = sqlx::query_as::<_, BrokenReference>(
+        r#"
+        SELECT 'printer' AS type, p.id, 'brand' AS reference, p.brand AS missing_id
+        FROM printers p
+        WHERE NOT EXISTS(SELECT 1 FROM brands WHERE id = p.brand)
+        UNION ALL
+        SELECT 'printer', p.id, 'toner', p.toner
+        FROM printers p
+        WHERE NOT EXISTS(SELECT 1 FROM toners WHERE id = p.toner)
+        UNION ALL
+        SELECT 'printer', p.id, 'drum', p.drum
+        FROM printers p
+        WHERE NOT EXISTS(SELECT 1 FROM drums WHERE id = p.drum)
+        UNION ALL
+        SELECT 'movement', m.id, 'printer', m.printer_id
+        FROM movements m
+        WHERE m.printer_id IS NOT NULL
+            AND NOT EXISTS(SELECT 1 FROM printers WHERE id = m.printer_id)
+        UNION ALL
+        SELECT 'movement', m.id, 'item', m.item_id
+        FROM movements m
+        WHERE NOT EXISTS(SELECT 1 FROM toners WHERE id = m.item_id)
+            AND NOT EXISTS(SELECT 1 FROM drums WHERE id = m.item_id);
+        "#,
+    )
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| {
+        error!("Error retrieving integrity report: {e}");
+        ApiError::DatabaseError(e)
+    })?;
+
+    info!(
+        "Integrity report retrieved: {} broken references",
+        report.len()
+    );
+    Ok(Json(report))
+}
+
+/// Retrieves the items whose stock differs from the net of their movements.
+///
+/// This endpoint compares the stored stock of each toner and drum with the sum of its movement quantities.
+/// Returns an empty array when every item is balanced.
+#[utoipa::path(
+    get,
+    path = "/api/v1/reports/balance",
+    tags = ["Reports"],
+    summary = "Check item stocks against their movements.",
+    description = "Returns one entry per toner or drum whose stock is not equal to the sum of its movement quantities, with the expected and actual values. Items without stock count as zero.",
+    responses(
+        (status = 200, description = "Balance report retrieved successfully", body = Vec<StockDiscrepancy>),
+        (status = 500, description = "An error occurred while retrieving the report")
+    )
+)]
+pub async fn balance(State(state): State<Arc<AppState>>) -> Result<impl IntoResponse, ApiError> {
+    let report = sqlx::query_as::<_, StockDiscrepancy>(
+        r#"
+        WITH items AS (
+            SELECT 'toner' AS type, id, name, COALESCE(stock, 0)::bigint AS stock FROM toners
+            UNION ALL
+            SELECT 'drum', id, name, COALESCE(stock, 0)::bigint FROM drums
+        ),
+        totals AS (
+            SELECT item_id, SUM(quantity)::bigint AS quantity
+            FROM movements
+            GROUP BY item_id
+        )
+        SELECT
+            i.type,
+            i.id,
+            i.name,
+            COALESCE(t.quantity, 0) AS expected,
+            i.stock AS actual,
+            i.stock - COALESCE(t.quantity, 0) AS delta
+        FROM items i
+        LEFT JOIN totals t ON t.item_id = i.id
+        WHERE i.stock <> COALESCE(t.quantity, 0)
+        ORDER BY i.type, i.name;
+        "#,
+    )
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| {
+        error!("Error retrieving balance report: {e}");
+        ApiError::DatabaseError(e)
+    })?;
+
+    info!(
+        "Balance report retrieved: {} unbalanced items",
+        report.len()
+    );
+    Ok(Json(report))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::{
+        app, create_brand, create_drum, create_printer, create_toner, empty_request, json_request,
+        send,
+    };
+    use axum::http::{Method, StatusCode};
+    use serde_json::json;
+    use sqlx::PgPool;
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn movements_are_grouped_by_day_with_empty_days_as_zero(pool: PgPool) {
+        let app = app(pool.clone());
+        let printer = create_printer(&app, "ECOSYS M2040").await;
+        let toner = create_toner(&app, "TN-1060", 10).await;
+        for quantity in [-1, -2, -4] {
+            let (status, _) = send(
+                &app,
+                json_request(
+                    Method::POST,
+                    "/api/v1/movements",
+                    json!({ "printer_id": printer, "item_id": toner, "item_type": "toner", "quantity": quantity }),
+                ),
+            )
+            .await;
+            assert_eq!(status, StatusCode::CREATED);
+        }
+        sqlx::query(
+            r#"
+            UPDATE movements SET created_at = CASE
+                WHEN quantity IN (-1, -2) THEN TIMESTAMPTZ '2024-03-01 12:00Z'
+                WHEN quantity = -4 THEN TIMESTAMPTZ '2024-03-03 08:00Z'
+                ELSE created_at
+            END;
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let (status, report) = send(
+            &app,
+            empty_request(
+                Method::GET,
+                "/api/v1/reports/movements-by-day?from=2024-03-01&to=2024-03-03",
+            ),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(
+            report,
+            json!([
+                { "date": "2024-03-01", "total_quantity": -3, "count": 2 },
+                { "date": "2024-03-02", "total_quantity": 0, "count": 0 },
+                { "date": "2024-03-03", "total_quantity": -4, "count": 1 },
+            ])
+        );
+    }
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn ranges_wider_than_a_year_are_rejected(pool: PgPool) {
+        let app = app(pool);
+
+        let (status, _) = send(
+            &app,
+            empty_request(
+                Method::GET,
+                "/api/v1/reports/movements-by-day?from=2023-01-01&to=2024-12-31",
+            ),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn orphaned_references_are_reported(pool: PgPool) {
+        let app = app(pool.clone());
+        let printer = create_printer(&app, "ECOSYS M2040").await;
+
+        let (status, report) = send(
+            &app,
+            empty_request(Method::GET, "/api/v1/reports/integrity"),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(report, json!([]));
+
+        // Skipping the triggers also skips the foreign key checks, as before they were enforced
+        let mut conn = pool.acquire().await.unwrap();
+        sqlx::query("SET session_replication_role = replica;")
+            .execute(&mut *conn)
+            .await
+            .unwrap();
+        let toner = sqlx::query_scalar::<_, uuid::Uuid>(
+            "DELETE FROM toners WHERE id = (SELECT toner FROM printers WHERE id = $1) RETURNING id;",
+        )
+        .bind(printer)
+        .fetch_one(&mut *conn)
+        .await
+        .unwrap();
+        sqlx::query("RESET session_replication_role;")
+            .execute(&mut *conn)
+            .await
+            .unwrap();
+        drop(conn);
+
+        let (_, report) = send(
+            &app,
+            empty_request(Method::GET, "/api/v1/reports/integrity"),
+        )
+        .await;
+        assert_eq!(
+            report,
+            json!([{ "type": "printer", "id": printer, "reference": "toner", "missing_id": toner }])
+        );
+    }
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn movements_are_grouped_by_the_local_day_of_the_offset(pool: PgPool) {
+        let app = app(pool.clone());
+        let printer = create_printer(&app, "ECOSYS M2040").await;
+        let toner = create_toner(&app, "TN-1060", 0).await;
+        let (status, _) = send(
+            &app,
+            json_request(
+                Method::POST,
+                "/api/v1/movements",
+                json!({ "printer_id": printer, "item_id": toner, "item_type": "toner", "quantity": 4 }),
+            ),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        // 22:30 of March 1st at -03:00
+        sqlx::query("UPDATE movements SET created_at = TIMESTAMPTZ '2024-03-02 01:30Z';")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let report = |tz: &str| {
+            empty_request(
+                Method::GET,
+                &format!("/api/v1/reports/movements-by-day?from=2024-03-01&to=2024-03-02{tz}"),
+            )
+        };
+
+        let (_, utc) = send(&app, report("")).await;
+        assert_eq!(utc[0]["count"], 0);
+        assert_eq!(utc[1]["count"], 1);
+
+        let (_, local) = send(&app, report("&tz=-03:00")).await;
+        assert_eq!(local[0]["count"], 1);
+        assert_eq!(local[1]["count"], 0);
+
+        let (status, _) = send(&app, report("&tz=Mars")).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn brands_report_their_printers_and_stock_value(pool: PgPool) {
+        let app = app(pool);
+        let kyocera = create_brand(&app, "Kyocera").await;
+        let lexmark = create_brand(&app, "Lexmark").await;
+        let (status, toner) = send(
+            &app,
+            json_request(
+                Method::POST,
+                "/api/v1/supplies/toners",
+                json!({ "name": "TN-1060", "stock": 3, "price": 25.5 }),
+            ),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        let drum = create_drum(&app, "DK-1150", 0).await;
+        // Both printers share the toner, which is counted once
+        for name in ["ECOSYS M2040", "ECOSYS P2235"] {
+            let (status, _) = send(
+                &app,
+                json_request(
+                    Method::POST,
+                    "/api/v1/printers",
+                    json!({ "name": name, "model": "Model 100", "brand": kyocera, "toner": toner, "drum": drum }),
+                ),
+            )
+            .await;
+            assert_eq!(status, StatusCode::CREATED);
+        }
+
+        let (status, report) =
+            send(&app, empty_request(Method::GET, "/api/v1/reports/by-brand")).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(
+            report,
+            json!([
+                { "brand_id": kyocera, "brand_name": "Kyocera", "printer_count": 2, "currency": "USD", "stock_value": 76.5 },
+                { "brand_id": lexmark, "brand_name": "Lexmark", "printer_count": 0, "currency": "USD", "stock_value": 0.0 },
+            ])
+        );
+    }
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn stock_edited_outside_movements_is_unbalanced(pool: PgPool) {
+        let app = app(pool.clone());
+        let printer = create_printer(&app, "ECOSYS M2040").await;
+        let toner = create_toner(&app, "TN-1060", 0).await;
+        let (status, _) = send(
+            &app,
+            json_request(
+                Method::POST,
+                "/api/v1/movements",
+                json!({ "printer_id": printer, "item_id": toner, "item_type": "toner", "quantity": 5 }),
+            ),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+
+        let (status, report) =
+            send(&app, empty_request(Method::GET, "/api/v1/reports/balance")).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(report, json!([]));
+
+        let (status, _) = send(
+            &app,
+            json_request(
+                Method::PUT,
+                "/api/v1/supplies/toners",
+                json!({ "id": toner, "stock": 8 }),
+            ),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+
+        let (_, report) = send(&app, empty_request(Method::GET, "/api/v1/reports/balance")).await;
+        assert_eq!(
+            report,
+            json!([{ "type": "toner", "id": toner, "name": "TN-1060", "expected": 5, "actual": 8, "delta": 3 }])
+        );
+    }
+}
@@ -0,0 +1,130 @@
+use crate::{
+    errors::api_error::ApiError,
+    models::search::{SearchParams, SearchResult, DEFAULT_SEARCH_LIMIT, MAX_SEARCH_LIMIT},
+};
+use axum::{
+    extract::{Query, State},
+    response::IntoResponse,
+    Json,
+};
+use infra::database::AppState;
+use std::sync::Arc;
+use tracing::{error, info};
+
+/// Escapes the `LIKE` wildcards of a user-provided value.
+pub fn escape_like(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+/// Searches brands, toners, drums and printers by name.
+///
+/// This endpoint runs a case-insensitive name match across all entities
+/// and returns a unified list, limited per entity type.
+/// An empty query returns an empty list.
+#[utoipa::path(
+    get,
+    path = "/api/v1/search",
+    tags = ["Search"],
+    summary = "Search across entities by name.",
+    description = "Runs a case-insensitive name match across brands, toners, drums, and printers, returning up to `limit` results per type. An empty `q` returns an empty array.",
+    params(SearchParams),
+    responses(
+        (status = 200, description = "Search completed successfully", body = Vec<SearchResult>),
+        (status = 500, description = "An error occurred while searching")
+    )
+)]
+pub async fn search(
+    Query(params): Query<SearchParams>,
+    State(state): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, ApiError> {
+    let query = params.q.unwrap_or_default();
+    let query = query.trim();
+
+    if query.is_empty() {
+        return Ok(Json(Vec::<SearchResult>::new()));
+    }
+
+    let pattern = format!("%{}%", escape_like(query));
+    let limit = params
+        .limit
+        .unwrap_or(DEFAULT_SEARCH_LIMIT)
+        .clamp(1, MAX_SEARCH_LIMIT);
+
+    let results = sqlx::query_as::<_, SearchResult>(
+        r#"
+        (SELECT 'brand' AS type, id, name FROM brands WHERE name ILIKE $1 ORDER BY name LIMIT $2)
+        UNION ALL
+        (SELECT 'toner' AS type, id, name FROM toners WHERE name ILIKE $1 ORDER BY name LIMIT $2)
+        UNION ALL
+        (SELECT 'drum' AS type, id, name FROM drums WHERE name ILIKE $1 ORDER BY name LIMIT $2)
+        UNION ALL
+        (SELECT 'printer' AS type, id, name FROM printers WHERE name ILIKE $1 ORDER BY name LIMIT $2);
+        "#,
+    )
+    .bind(&pattern)
+    .bind(limit)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| {
+        error!("Error searching for '{query}': {e}");
+        ApiError::DatabaseError(e)
+    })?;
+
+    info!("Search for '{query}' returned {} results", results.len());
+    Ok(Json(results))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::{app, create_printer, empty_request, send};
+    use axum::http::{Method, StatusCode};
+    use serde_json::json;
+    use sqlx::PgPool;
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn every_entity_type_is_searched(pool: PgPool) {
+        let app = app(pool);
+        // Also creates the "Zenith Brand", "Zenith Toner" and "Zenith Drum" it uses
+        create_printer(&app, "Zenith").await;
+
+        let (status, results) =
+            send(&app, empty_request(Method::GET, "/api/v1/search?q=zENITH")).await;
+
+        assert_eq!(status, StatusCode::OK);
+        let mut found: Vec<_> = results
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|result| {
+                (
+                    result["type"].as_str().unwrap(),
+                    result["name"].as_str().unwrap(),
+                )
+            })
+            .collect();
+        found.sort();
+        assert_eq!(
+            found,
+            [
+                ("brand", "Zenith Brand"),
+                ("drum", "Zenith Drum"),
+                ("printer", "Zenith"),
+                ("toner", "Zenith Toner"),
+            ]
+        );
+    }
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn an_empty_query_finds_nothing(pool: PgPool) {
+        let app = app(pool);
+        create_printer(&app, "Zenith").await;
+
+        let (status, results) = send(&app, empty_request(Method::GET, "/api/v1/search?q=")).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(results, json!([]));
+    }
+}
@@ -1,44 +1,93 @@
 use crate::{
-    errors::api_error::ApiError,
-    models::status::{Database, Dependencies, Status},
+    handlers::migrations::pending_migrations,
+    models::status::{
+        Database, Dependencies, DiskInfo, HealthStatus, MigrationInfo, PoolStatus, Status,
+    },
 };
-use axum::{extract::State, response::IntoResponse, Json};
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
 use chrono::Utc;
+use config::Config;
 use infra::database::AppState;
 use std::{env, sync::Arc};
-use tracing::{error, info};
+use tracing::{error, info, warn};
 
 /// Retrieves the current status of the API, including the database connection status.
-/// Provides information on the database version, maximum connections, and currently open connections.
+/// Provides information on the database version, maximum connections, and currently open connections,
+/// and on the migrations not applied yet.
+/// When `MIN_FREE_DISK_MB` is set, also reports the free space in the log directory.
+/// The checks are rolled up into a single `status`: `unhealthy` when the database is unreachable,
+/// `degraded` when migrations are pending or disk space is low, `healthy` otherwise.
+/// Responds with `503` when unhealthy or when disk space is low.
 /// Useful for health checks and monitoring API dependencies.
 #[utoipa::path(
     get,
     path = "/api/v1/status",
     tags = ["Status"],
     summary = "Get API and database status",
-    description = "Fetches the current operational status of the API, including database information such as version, max connections, and active connections.",
+    description = "Fetches the current operational status of the API, including database information such as version, max connections, and active connections, the pending migrations, and the free disk space of the log directory. The top-level `status` rolls the checks up into `healthy`, `degraded` or `unhealthy`.",
     responses(
-        (status = 200, description = "Status retrieved successfully", body = Status)
+        (status = 200, description = "Status retrieved successfully", body = Status),
+        (status = 503, description = "The database is unreachable or the log directory is running out of disk space", body = Status)
     )
 )]
-pub async fn show_status(
-    State(state): State<Arc<AppState>>,
-) -> Result<impl IntoResponse, ApiError> {
+pub async fn show_status(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let database = database_info(&state)
+        .await
+        .map_err(|e| error!("Database unreachable: {e}"))
+        .ok();
+
+    let migrations = match database {
+        Some(_) => migration_info(&state).await,
+        None => None,
+    };
+
+    let disk = disk_info();
+
+    let low_disk = match &disk {
+        Some(disk) if disk.available_bytes < disk.min_free_bytes => {
+            warn!(
+                "Low disk space in '{}': {} bytes available, {} required",
+                disk.log_directory, disk.available_bytes, disk.min_free_bytes
+            );
+            true
+        }
+        _ => false,
+    };
+
+    let status = HealthStatus::from_checks(
+        database.is_some(),
+        migrations.as_ref().is_some_and(|m| m.up_to_date),
+        !low_disk,
+    );
+
+    let status_code = if status == HealthStatus::Unhealthy || low_disk {
+        StatusCode::SERVICE_UNAVAILABLE
+    } else {
+        StatusCode::OK
+    };
+
+    info!("Status queried: {status:?}");
+    (
+        status_code,
+        Json(Status {
+            status,
+            updated_at: Utc::now(),
+            dependencies: Dependencies { database },
+            migrations,
+            disk,
+        }),
+    )
+}
+
+/// Reads the version and connection counts of the database, failing when it is unreachable.
+async fn database_info(state: &AppState) -> Result<Database, sqlx::Error> {
     let version = sqlx::query_scalar::<_, String>(r#"SHOW server_version;"#)
         .fetch_one(&state.db)
-        .await
-        .map_err(|e| {
-            error!("Error retrieving database version: {e}");
-            ApiError::DatabaseError(e)
-        })?;
+        .await?;
 
     let max_connections = sqlx::query_scalar::<_, String>(r#"SHOW max_connections;"#)
         .fetch_one(&state.db)
-        .await
-        .map_err(|e| {
-            error!("Error retrieving database max connections: {e}");
-            ApiError::DatabaseError(e)
-        })?
+        .await?
         .parse::<i64>()
         .expect("Error parsing max_connections as i64");
 
@@ -47,21 +96,155 @@ pub async fn show_status(
     )
     .bind(env::var("POSTGRES_DB").unwrap())
     .fetch_one(&state.db)
-    .await
-    .map_err(|e| {
-        error!("Error retrieving database opened connections: {e}");
-        ApiError::DatabaseError(e)
-    })?;
+    .await?;
 
-    let database = Database {
+    Ok(Database {
         version,
         max_connections,
         opened_connections,
-    };
+    })
+}
+
+/// Lists the pending migrations.
+///
+/// Returns `None` when the applied migrations could not be read, such as before the first run.
+async fn migration_info(state: &AppState) -> Option<MigrationInfo> {
+    let mut conn = state
+        .db
+        .acquire()
+        .await
+        .map_err(|e| error!("Error acquiring a connection to check migrations: {e}"))
+        .ok()?;
+
+    let pending = pending_migrations(&mut conn)
+        .await
+        .map_err(|e| error!("Error reading applied migrations: {e}"))
+        .ok()?;
+
+    Some(MigrationInfo {
+        up_to_date: pending.is_empty(),
+        pending,
+    })
+}
+
+/// Checks the free space of the log directory.
+///
+/// Returns `None` when the check is disabled or the space could not be read.
+fn disk_info() -> Option<DiskInfo> {
+    let min_free_mb = Config::min_free_disk_mb()?;
+    let log_directory = Config::log_directory();
+
+    let available_bytes = fs2::available_space(log_directory)
+        .map_err(|e| error!("Error reading free disk space of '{log_directory}': {e}"))
+        .ok()?;
+
+    Some(DiskInfo {
+        log_directory: String::from(log_directory),
+        available_bytes,
+        min_free_bytes: min_free_mb * 1024 * 1024,
+    })
+}
+
+/// Retrieves the statistics of the database connection pool.
+///
+/// This endpoint only inspects the pool, without running any query,
+/// so it is cheap enough to poll for capacity planning.
+#[utoipa::path(
+    get,
+    path = "/api/v1/status/pool",
+    tags = ["Status"],
+    summary = "Get database pool statistics",
+    description = "Returns the number of open and idle connections of the API's database pool, along with its configured bounds.",
+    responses(
+        (status = 200, description = "Pool statistics retrieved successfully", body = PoolStatus)
+    )
+)]
+pub async fn show_pool_status(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let options = state.db.options();
+
+    Json(PoolStatus {
+        size: state.db.size(),
+        idle: state.db.num_idle(),
+        min_connections: options.get_min_connections(),
+        max_connections: options.get_max_connections(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::{app, empty_request, send};
+    use axum::http::{Method, StatusCode};
+    use config::Config;
+    use infra::database::connection::{create_pool, PoolSettings};
+    use sqlx::PgPool;
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn the_free_disk_space_of_the_log_directory_is_reported(pool: PgPool) {
+        let app = app(pool);
+        // The logger creates it on startup, which the tests skip
+        std::fs::create_dir_all(Config::log_directory()).unwrap();
+
+        let (_, status) = send(&app, empty_request(Method::GET, "/api/v1/status")).await;
+
+        let disk = &status["disk"];
+        assert_eq!(disk["log_directory"], Config::log_directory());
+        assert!(disk["available_bytes"].as_u64().unwrap() > 0);
+        assert_eq!(disk["min_free_bytes"], 100 * 1024 * 1024);
+    }
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn the_configured_pool_bounds_are_reported(pool: PgPool) {
+        // Building the app loads the test environment the pool is configured from
+        let _ = app(pool);
+        let (min_connections, max_connections) = Config::database_connections().unwrap();
+        let configured = create_pool(PoolSettings {
+            min_connections,
+            max_connections,
+            statement_timeout_ms: Config::database_statement_timeout_ms(),
+            slow_query_ms: Config::slow_query_ms(),
+        })
+        .await
+        .unwrap();
+        let app = app(configured);
+
+        let (_, pool_status) = send(&app, empty_request(Method::GET, "/api/v1/status/pool")).await;
+
+        assert_eq!(pool_status["max_connections"], max_connections);
+        assert_eq!(pool_status["min_connections"], min_connections);
+        assert!(pool_status["size"].as_u64().unwrap() <= u64::from(max_connections));
+    }
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn a_migrated_database_is_healthy(pool: PgPool) {
+        let app = app(pool);
+        std::fs::create_dir_all(Config::log_directory()).unwrap();
+
+        let (status, body) = send(&app, empty_request(Method::GET, "/api/v1/status")).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["status"], "healthy");
+    }
+
+    #[sqlx::test(migrations = false)]
+    async fn pending_migrations_are_degraded(pool: PgPool) {
+        let app = app(pool);
+        std::fs::create_dir_all(Config::log_directory()).unwrap();
+
+        let (status, body) = send(&app, empty_request(Method::GET, "/api/v1/status")).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["status"], "degraded");
+    }
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn an_unreachable_database_is_unhealthy(pool: PgPool) {
+        pool.close().await;
+        let app = app(pool);
+
+        let (status, body) = send(&app, empty_request(Method::GET, "/api/v1/status")).await;
 
-    info!("Status queried");
-    Ok(Json(Status {
-        updated_at: Utc::now(),
-        dependencies: Dependencies { database },
-    }))
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body["status"], "unhealthy");
+        assert_eq!(body["dependencies"]["database"], serde_json::Value::Null);
+    }
 }
@@ -1,19 +1,36 @@
 use crate::{
     errors::api_error::ApiError,
+    extractors::if_match::{etag, IfMatch},
+    extractors::json::Json,
+    extractors::path::Path,
+    extractors::prefer::ReturnPreference,
     models::{
-        supplies::drum::{CreateDrumRequest, Drum, UpdateDrumRequest},
-        DeleteRequest,
+        supplies::{
+            drum::{CreateDrumRequest, Drum, UpdateDrumRequest},
+            SupplyCount, SupplyCountParams, SupplyCountResponse, SupplyFilterParams,
+        },
+        CompactItem, CompactParams, DeleteRequest, ExistsRequest, ReturnParams,
+    },
+    routes::location,
+    validations::{
+        currency::normalize_currency,
+        existence::{brand_exists, drum_exists},
+        price::normalize_price,
+        uniqueness::{
+            conflict_or_database_error, is_drum_name_available, is_drum_unique, reject_taken_name,
+        },
     },
-    validations::{existence::drum_exists, uniqueness::is_drum_unique},
 };
 use axum::{
-    extract::{Path, State},
-    http::StatusCode,
+    extract::{Query, State},
+    http::{header, StatusCode},
     response::IntoResponse,
-    Json,
 };
 use infra::database::AppState;
-use std::sync::Arc;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
 use tracing::{error, info};
 use uuid::Uuid;
 use validator::Validate;
@@ -21,37 +38,47 @@ use validator::Validate;
 /// Retrieves the total count of drums.
 ///
 /// This endpoint counts all drums stored in the database and returns the count as an integer.
+/// With `with_stock=true` the count is returned in an object along with the total units in stock.
 /// If no drums are found, 0 is returned.
 #[utoipa::path(
     get,
     path = "/api/v1/supplies/drums/count",
     tags = ["Drums"],
     summary = "Get the total count of drums.",
-    description = "This endpoint retrieves the total number of drums stored in the database.",
+    description = "This endpoint retrieves the total number of drums stored in the database. With `with_stock=true` the response is a `SupplyCount` object that also holds the sum of their stock.",
+    params(SupplyCountParams),
     responses(
-        (status = 200, description = "Drum count retrieved successfully", body = i32),
+        (status = 200, description = "Drum count retrieved successfully: the bare count, or a `SupplyCount` with `with_stock=true`", body = SupplyCountResponse),
         (status = 500, description = "An error occurred while retrieving the drum count")
     )
 )]
 pub async fn count_drums(
+    Query(params): Query<SupplyCountParams>,
     State(state): State<Arc<AppState>>,
 ) -> Result<impl IntoResponse, ApiError> {
-    let count = sqlx::query_scalar::<_, i64>(r#"SELECT COUNT(*) FROM drums;"#)
-        .fetch_one(&state.db)
-        .await
-        .map_err(|e| {
-            error!("Error retrieving drum count: {e}");
-            ApiError::DatabaseError(e)
-        })?;
+    let count = sqlx::query_as::<_, SupplyCount>(
+        r#"SELECT COUNT(*) AS count, COALESCE(SUM(stock), 0)::bigint AS total_stock FROM drums;"#,
+    )
+    .fetch_one(&state.db)
+    .await
+    .map_err(|e| {
+        error!("Error retrieving drum count: {e}");
+        ApiError::DatabaseError(e)
+    })?;
 
-    info!("Successfully retrieved drum count: {count}");
-    Ok(Json(count))
+    info!("Successfully retrieved drum count: {}", count.count);
+    let response = if params.with_stock.unwrap_or(false) {
+        SupplyCountResponse::WithStock(count)
+    } else {
+        SupplyCountResponse::Count(count.count)
+    };
+    Ok(Json(response))
 }
 
 /// Retrieves a specific drum by its ID.
 ///
 /// This endpoint searches for a drum with the specified ID.
-/// If the drum is found, it returns the drum details.
+/// If the drum is found, it returns the drum details along with their `ETag`.
 #[utoipa::path(
     get,
     path = "/api/v1/supplies/drums/{id}",
@@ -62,7 +89,8 @@ pub async fn count_drums(
         ("id", description = "The unique identifier of the drum to retrieve", example = "550e8400-e29b-41d4-a716-446655440000")
     ),
     responses(
-        (status = 200, description = "Drum retrieved successfully", body = Drum),
+        (status = 200, description = "Drum retrieved successfully", body = Drum,
+            headers(("ETag" = String, description = "Tag of the current state of the drum, for `If-Match` updates"))),
         (status = 404, description = "No drum found with the specified ID"),
         (status = 500, description = "An error occurred while retrieving the drum")
     )
@@ -70,7 +98,7 @@ pub async fn count_drums(
 pub async fn search_drum(
     Path(id): Path<Uuid>,
     State(state): State<Arc<AppState>>,
-) -> impl IntoResponse {
+) -> Result<impl IntoResponse, ApiError> {
     let drum = sqlx::query_as::<_, Drum>(r#"SELECT * FROM drums WHERE id = $1;"#)
         .bind(id)
         .fetch_optional(&state.db)
@@ -83,42 +111,157 @@ pub async fn search_drum(
     match drum {
         Some(drum) => {
             info!("Drum found: {id}");
-            Ok(Json(drum))
+            Ok(([(header::ETAG, etag(&drum))], Json(drum)))
         }
         None => {
             error!("No drum found with id: {id}");
-            Err(ApiError::IdNotFound)
+            Err(ApiError::IdNotFound { resource: "Drum" })
         }
     }
 }
 
+/// Checks whether a drum exists.
+///
+/// This endpoint answers with an empty body, using an existence query instead of fetching the drum.
+/// Useful for clients that only need to know whether the ID is valid.
+#[utoipa::path(
+    head,
+    path = "/api/v1/supplies/drums/{id}",
+    tags = ["Drums"],
+    summary = "Check whether a drum exists.",
+    description = "This endpoint checks whether a drum with the given ID exists, without returning its details.",
+    params(
+        ("id", description = "The unique identifier of the drum to check", example = "550e8400-e29b-41d4-a716-446655440000")
+    ),
+    responses(
+        (status = 200, description = "Drum exists"),
+        (status = 404, description = "No drum found with the specified ID"),
+        (status = 500, description = "An error occurred while checking the drum")
+    )
+)]
+pub async fn check_drum(
+    Path(id): Path<Uuid>,
+    State(state): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, ApiError> {
+    drum_exists(state, id).await?;
+
+    Ok(StatusCode::OK)
+}
+
+/// Checks which of several drums exist.
+///
+/// This endpoint looks up all the given IDs in a single query, so clients can validate them before bulk operations.
+/// Returns an object mapping each ID to whether a drum with that ID exists.
+#[utoipa::path(
+    post,
+    path = "/api/v1/supplies/drums/exists",
+    tags = ["Drums"],
+    summary = "Check whether several drums exist.",
+    description = "This endpoint answers with an object holding `true` or `false` for each of the given drum IDs. At most 1000 IDs can be checked at once.",
+    request_body = ExistsRequest,
+    responses(
+        (status = 200, description = "Existence checked successfully", body = HashMap<String, bool>),
+        (status = 400, description = "Too many IDs"),
+        (status = 500, description = "An error occurred while checking the drums")
+    )
+)]
+pub async fn check_drums(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<ExistsRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    // Validations
+    request.validate()?;
+
+    let existing: HashSet<Uuid> =
+        sqlx::query_scalar::<_, Uuid>(r#"SELECT id FROM drums WHERE id = ANY($1);"#)
+            .bind(&request.ids)
+            .fetch_all(&state.db)
+            .await
+            .map_err(|e| {
+                error!("Error checking drum IDs: {e}");
+                ApiError::DatabaseError(e)
+            })?
+            .into_iter()
+            .collect();
+
+    let exists: HashMap<Uuid, bool> = request
+        .ids
+        .iter()
+        .map(|id| (*id, existing.contains(id)))
+        .collect();
+
+    info!(
+        "Checked {} drum IDs, {} exist",
+        exists.len(),
+        existing.len()
+    );
+    Ok(Json(exists))
+}
+
 /// Retrieves a list of all drums.
 ///
-/// This endpoint fetches all drums stored in the database.
+/// This endpoint fetches all drums stored in the database,
+/// optionally filtered by whether their stock and price are set.
+/// With `compact=true` only their IDs and names are listed.
 /// If there are no drums, returns an empty array.
 #[utoipa::path(
     get,
     path = "/api/v1/supplies/drums",
     tags = ["Drums"],
     summary = "List all drums.",
-    description = "Fetches all drums stored in the database. If there are no drums, returns an empty array.",
+    description = "Fetches all drums stored in the database. If there are no drums, returns an empty array. `has_stock` and `has_price` keep only the drums whose stock or price is set (`true`) or missing (`false`). With `compact=true` each drum is a `CompactItem` holding only its `id` and `name`.",
+    params(SupplyFilterParams, CompactParams),
     responses(
         (status = 200, description = "Drums retrieved successfully", body = Vec<Drum>),
         (status = 404, description = "No drums found in the database"),
         (status = 500, description = "An error occurred while retrieving the drums")
     )
 )]
-pub async fn show_drums(State(state): State<Arc<AppState>>) -> Result<impl IntoResponse, ApiError> {
-    let drums = sqlx::query_as::<_, Drum>(r#"SELECT * FROM drums;"#)
+pub async fn show_drums(
+    Query(filter): Query<SupplyFilterParams>,
+    Query(compact): Query<CompactParams>,
+    State(state): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, ApiError> {
+    if compact.compact.unwrap_or(false) {
+        let drums = sqlx::query_as::<_, CompactItem>(
+            r#"
+            SELECT id, name FROM drums
+            WHERE ($1::bool IS NULL OR (stock IS NOT NULL) = $1)
+                AND ($2::bool IS NULL OR (price IS NOT NULL) = $2)
+            ORDER BY name, id;
+            "#,
+        )
+        .bind(filter.has_stock)
+        .bind(filter.has_price)
         .fetch_all(&state.db)
         .await
         .map_err(|e| {
-            error!("Error listing drums: {e}");
+            error!("Error listing compact drums: {e}");
             ApiError::DatabaseError(e)
         })?;
 
+        info!("Drums listed successfully in compact form");
+        return Ok(Json(drums).into_response());
+    }
+
+    let drums = sqlx::query_as::<_, Drum>(
+        r#"
+        SELECT * FROM drums
+        WHERE ($1::bool IS NULL OR (stock IS NOT NULL) = $1)
+            AND ($2::bool IS NULL OR (price IS NOT NULL) = $2);
+        "#,
+    )
+    .bind(filter.has_stock)
+    .bind(filter.has_price)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| {
+        error!("Error listing drums: {e}");
+        ApiError::DatabaseError(e)
+    })?;
+
     info!("Drums listed successfully");
-    Ok(Json(drums))
+    Ok(Json(drums).into_response())
 }
 
 /// Create a new drum.
@@ -133,37 +276,76 @@ pub async fn show_drums(State(state): State<Arc<AppState>>) -> Result<impl IntoR
     summary = "Create a new drum.",
     description = "This endpoint creates a new drum in the database with the provided details.",
     request_body = CreateDrumRequest,
+    params(
+        ReturnParams,
+        ("Prefer" = Option<String>, Header, description = "`return=representation` returns the created drum instead of its ID")
+    ),
     responses(
-        (status = 201, description = "Drum created successfully", body = Uuid),
-        (status = 400, description = "Invalid input, including empty name or name too short/long"),
+        (status = 201, description = "Drum created successfully. Answers with the full drum instead of its ID when `return=representation` is requested", body = Uuid),
+        (status = 400, description = "Invalid input, including empty name, name too short/long, unknown currency or a price too large once rounded"),
         (status = 409, description = "Conflict: Drum with the same name already exists"),
         (status = 500, description = "An error occurred while creating the drum")
     )
 )]
 pub async fn create_drum(
+    preference: ReturnPreference,
     State(state): State<Arc<AppState>>,
     Json(request): Json<CreateDrumRequest>,
 ) -> Result<impl IntoResponse, ApiError> {
     // Validations
     request.validate()?;
-    is_drum_unique(state.clone(), request.name.clone()).await?;
+    if let Some(brand_id) = request.brand_id {
+        brand_exists(state.clone(), brand_id).await?;
+    }
+    let existing_id = is_drum_unique(state.clone(), request.name.clone(), request.brand_id).await?;
+    reject_taken_name(request.name.clone(), existing_id)?;
 
-    let new_drum = Drum::new(&request.name, request.stock, request.price);
+    let new_drum = Drum::new(
+        &request.name,
+        request.stock,
+        request.price.map(normalize_price),
+        request.brand_id,
+        request.currency.as_deref().map(normalize_currency),
+    );
 
-    sqlx::query(r#"INSERT INTO drums (id, name, stock, price) VALUES ($1, $2, $3, $4);"#)
-        .bind(new_drum.id)
-        .bind(&new_drum.name)
-        .bind(new_drum.stock)
-        .bind(new_drum.price)
-        .execute(&state.db)
-        .await
-        .map_err(|e| {
-            error!("Error creating drum: {e}");
-            ApiError::DatabaseError(e)
-        })?;
+    let result = sqlx::query(
+        r#"INSERT INTO drums (id, name, stock, price, brand_id, currency) VALUES ($1, $2, $3, $4, $5, $6);"#,
+    )
+    .bind(new_drum.id)
+    .bind(&new_drum.name)
+    .bind(new_drum.stock)
+    .bind(new_drum.price)
+    .bind(new_drum.brand_id)
+    .bind(&new_drum.currency)
+    .execute(&state.db)
+    .await;
+
+    if let Err(e) = result {
+        error!("Error creating drum: {e}");
+        let check = is_drum_unique(state.clone(), request.name.clone(), request.brand_id);
+        return Err(conflict_or_database_error(e, request.name, check).await);
+    }
+
+    let created = match preference {
+        ReturnPreference::Representation => Some(
+            sqlx::query_as::<_, Drum>(r#"SELECT * FROM drums WHERE id = $1;"#)
+                .bind(new_drum.id)
+                .fetch_one(&state.db)
+                .await
+                .map_err(|e| {
+                    error!("Error reading back created drum: {e}");
+                    ApiError::DatabaseError(e)
+                })?,
+        ),
+        ReturnPreference::Minimal => None,
+    };
 
     info!("Drum created! ID: {}", &new_drum.id);
-    Ok((StatusCode::CREATED, Json(new_drum.id)))
+    Ok((
+        StatusCode::CREATED,
+        location(&format!("/supplies/drums/{}", new_drum.id)),
+        preference.body(new_drum.id, created),
+    ))
 }
 
 /// Updates an existing drum.
@@ -178,41 +360,93 @@ pub async fn create_drum(
     path = "/api/v1/supplies/drums",
     tags = ["Drums"],
     summary = "Update an existing drum.",
-    description = "This endpoint updates the details of an existing drum in the database.",
+    description = "This endpoint updates the details of an existing drum in the database. With an `If-Match` header holding the `ETag` of a previous GET, the update is rejected with `412` if the drum changed since.",
     request_body = UpdateDrumRequest,
+    params(
+        ("If-Match" = Option<String>, Header, description = "ETag the drum must still have for the update to apply")
+    ),
     responses(
         (status = 200, description = "Drum updated successfully", body = Uuid),
-        (status = 400, description = "Invalid input, including empty name or name too short/long"),
+        (status = 400, description = "Invalid input, including empty name, name too short/long, unknown currency or a price too large once rounded"),
         (status = 404, description = "Drum ID not found"),
         (status = 409, description = "Conflict: Drum with the same name already exists"),
+        (status = 412, description = "The drum no longer matches the `If-Match` ETag"),
         (status = 500, description = "An error occurred while updating the drum")
     )
 )]
 pub async fn update_drum(
+    if_match: IfMatch,
     State(state): State<Arc<AppState>>,
     Json(request): Json<UpdateDrumRequest>,
 ) -> Result<impl IntoResponse, ApiError> {
     // Validations
     request.validate()?;
     drum_exists(state.clone(), request.id.clone()).await?;
+    if let Some(brand_id) = request.brand_id {
+        brand_exists(state.clone(), brand_id).await?;
+    }
 
     let drum_id = request.id;
     let new_name = request.name.clone();
     let new_stock = request.stock;
-    let new_price = request.price;
+    let new_price = request.price.map(normalize_price);
+    let new_currency = request.currency.as_deref().map(normalize_currency);
 
-    // Validate and update name if provided
-    if let Some(name) = new_name {
-        // Update drum name
-        sqlx::query(r#"UPDATE drums SET name = $1 WHERE id = $2;"#)
-            .bind(&name)
+    let mut tx = state.db.begin().await.map_err(|e| {
+        error!("Error starting transaction: {e}");
+        ApiError::DatabaseError(e)
+    })?;
+
+    // Lock the drum and check that it still matches the ETag the client read
+    if if_match.is_present() {
+        let current = sqlx::query_as::<_, Drum>(r#"SELECT * FROM drums WHERE id = $1 FOR UPDATE;"#)
             .bind(drum_id)
-            .execute(&state.db)
+            .fetch_one(&mut *tx)
             .await
             .map_err(|e| {
-                error!("Error updating drum name: {e}");
+                error!("Error fetching drum by ID: {e}");
                 ApiError::DatabaseError(e)
             })?;
+        if_match.check(&etag(&current))?;
+    }
+
+    // The new name and brand must not collide with another drum, even when only one of them changes
+    let renamed = if new_name.is_some() || request.brand_id.is_some() {
+        let (current_name, current_brand_id) = sqlx::query_as::<_, (String, Option<Uuid>)>(
+            r#"SELECT name, brand_id FROM drums WHERE id = $1 FOR UPDATE;"#,
+        )
+        .bind(drum_id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| {
+            error!("Error fetching drum by ID: {e}");
+            ApiError::DatabaseError(e)
+        })?;
+
+        let name = new_name.clone().unwrap_or(current_name);
+        let brand_id = request.brand_id.or(current_brand_id);
+        let existing_id =
+            is_drum_name_available(state.clone(), name.clone(), brand_id, drum_id).await?;
+        reject_taken_name(name.clone(), existing_id)?;
+        Some((name, brand_id))
+    } else {
+        None
+    };
+
+    // Update name if provided
+    if let Some(name) = new_name {
+        let result = sqlx::query(r#"UPDATE drums SET name = $1 WHERE id = $2;"#)
+            .bind(&name)
+            .bind(drum_id)
+            .execute(&mut *tx)
+            .await;
+
+        if let Err(e) = result {
+            error!("Error updating drum name: {e}");
+            let (name, brand_id) = renamed.unwrap_or_default();
+            let check = is_drum_name_available(state.clone(), name.clone(), brand_id, drum_id);
+            return Err(conflict_or_database_error(e, name, check).await);
+        }
     }
 
     // Update stock if provided
@@ -220,7 +454,7 @@ pub async fn update_drum(
         sqlx::query(r#"UPDATE drums SET stock = $1 WHERE id = $2;"#)
             .bind(stock)
             .bind(drum_id)
-            .execute(&state.db)
+            .execute(&mut *tx)
             .await
             .map_err(|e| {
                 error!("Error updating drum stock: {e}");
@@ -233,7 +467,7 @@ pub async fn update_drum(
         sqlx::query(r#"UPDATE drums SET price = $1 WHERE id = $2;"#)
             .bind(price)
             .bind(drum_id)
-            .execute(&state.db)
+            .execute(&mut *tx)
             .await
             .map_err(|e| {
                 error!("Error updating drum price: {e}");
@@ -241,6 +475,40 @@ pub async fn update_drum(
             })?;
     }
 
+    // Update currency if provided
+    if let Some(currency) = new_currency {
+        sqlx::query(r#"UPDATE drums SET currency = $1 WHERE id = $2;"#)
+            .bind(currency)
+            .bind(drum_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| {
+                error!("Error updating drum currency: {e}");
+                ApiError::DatabaseError(e)
+            })?;
+    }
+
+    // Update brand if provided
+    if let Some(brand_id) = request.brand_id {
+        let result = sqlx::query(r#"UPDATE drums SET brand_id = $1 WHERE id = $2;"#)
+            .bind(brand_id)
+            .bind(drum_id)
+            .execute(&mut *tx)
+            .await;
+
+        if let Err(e) = result {
+            error!("Error updating drum brand: {e}");
+            let (name, brand_id) = renamed.unwrap_or_default();
+            let check = is_drum_name_available(state.clone(), name.clone(), brand_id, drum_id);
+            return Err(conflict_or_database_error(e, name, check).await);
+        }
+    }
+
+    tx.commit().await.map_err(|e| {
+        error!("Error committing drum update: {e}");
+        ApiError::DatabaseError(e)
+    })?;
+
     info!("Drum updated! ID: {}", &drum_id);
     Ok((StatusCode::OK, Json(drum_id)).into_response())
 }
@@ -283,3 +551,60 @@ pub async fn delete_drum(
     info!("Drum deleted! ID: {}", &request.id);
     Ok((StatusCode::OK, Json("Drum deleted!")).into_response())
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::{app, empty_request, json_request, send};
+    use axum::http::{HeaderValue, Method, StatusCode};
+    use serde_json::json;
+    use sqlx::PgPool;
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn the_created_drum_is_returned_only_on_request(pool: PgPool) {
+        let app = app(pool);
+
+        let (status, id) = send(
+            &app,
+            json_request(
+                Method::POST,
+                "/api/v1/supplies/drums",
+                json!({ "name": "DK-1150", "stock": 2 }),
+            ),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert!(id.is_string());
+
+        let mut request = json_request(
+            Method::POST,
+            "/api/v1/supplies/drums",
+            json!({ "name": "DR-2400", "stock": 2 }),
+        );
+        request
+            .headers_mut()
+            .insert("Prefer", HeaderValue::from_static("return=representation"));
+        let (status, drum) = send(&app, request).await;
+
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(drum["name"], "DR-2400");
+        assert_eq!(drum["stock"], 2);
+    }
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn missing_drums_are_not_found(pool: PgPool) {
+        let app = app(pool);
+
+        let (status, body) = send(
+            &app,
+            empty_request(
+                Method::GET,
+                "/api/v1/supplies/drums/550e8400-e29b-41d4-a716-446655440000",
+            ),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["code"], "ID_NOT_FOUND");
+        assert_eq!(body["message"], "Drum with the provided ID does not exist.");
+    }
+}
@@ -1,2 +1,750 @@
+use crate::{
+    errors::api_error::ApiError,
+    extractors::json::Json,
+    extractors::path::Path,
+    handlers::movement::apply_movement,
+    models::{
+        movement::{CreatedMovement, ItemType, Movement, MovementDetails, MovementView},
+        supplies::{
+            CreatedReservation, FulfilReservationRequest, ReserveRequest, SetStockRequest,
+            SupplyMovementsParams, SupplyStock,
+        },
+    },
+    routes::location,
+    validations::consumption::check_consumption_rate,
+    webhooks::{notify_stock_change, StockChangeEvent},
+};
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use chrono::{Duration, Utc};
+use infra::database::AppState;
+use sqlx::{Postgres, Transaction};
+use std::{str::FromStr, sync::Arc};
+use tracing::{error, info};
+use uuid::Uuid;
+use validator::Validate;
+
 pub mod drum;
 pub mod toner;
+
+/// Retrieves the stock of a supply, along with its reserved and available quantities.
+///
+/// This endpoint looks the ID up among toners and drums.
+/// Expired reservations are not counted, so their quantity is available again.
+#[utoipa::path(
+    get,
+    path = "/api/v1/supplies/{id}",
+    tags = ["Supplies"],
+    summary = "Get the stock of a supply.",
+    description = "This endpoint retrieves the stock of a toner or drum, the quantity held by active reservations, and the quantity still available.",
+    params(
+        ("id", description = "The unique identifier of the toner or drum", example = "550e8400-e29b-41d4-a716-446655440000")
+    ),
+    responses(
+        (status = 200, description = "Supply stock retrieved successfully", body = SupplyStock),
+        (status = 404, description = "No toner or drum found with the specified ID"),
+        (status = 500, description = "An error occurred while retrieving the supply")
+    )
+)]
+pub async fn show_supply(
+    Path(id): Path<Uuid>,
+    State(state): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, ApiError> {
+    let supply = sqlx::query_as::<_, SupplyStock>(
+        r#"
+        WITH supply AS (
+            SELECT 'toner' AS type, id, name, COALESCE(stock, 0)::bigint AS stock FROM toners WHERE id = $1
+            UNION ALL
+            SELECT 'drum', id, name, COALESCE(stock, 0)::bigint FROM drums WHERE id = $1
+        ),
+        reserved AS (
+            SELECT COALESCE(SUM(quantity), 0)::bigint AS quantity
+            FROM reservations
+            WHERE item_id = $1 AND expires_at > NOW()
+        )
+        SELECT s.type, s.id, s.name, s.stock, r.quantity AS reserved, s.stock - r.quantity AS available
+        FROM supply s, reserved r;
+        "#,
+    )
+    .bind(id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|e| {
+        error!("Error retrieving supply with id {id}: {e}");
+        ApiError::DatabaseError(e)
+    })?;
+
+    match supply {
+        Some(supply) => {
+            info!("Supply found: {id}");
+            Ok(Json(supply))
+        }
+        None => {
+            error!("No toner or drum found with id: {id}");
+            Err(ApiError::IdNotFound { resource: "Supply" })
+        }
+    }
+}
+
+/// Retrieves the movements of a supply.
+///
+/// This endpoint lists the movements of a toner or drum, newest first, along with their printers.
+/// If the supply has no movements, returns an empty array.
+#[utoipa::path(
+    get,
+    path = "/api/v1/supplies/{id}/movements",
+    tags = ["Supplies"],
+    summary = "List the movements of a supply.",
+    description = "This endpoint retrieves the movements of a toner or drum, newest first, optionally limited with `limit`. Returns an empty array if the supply has no movements.",
+    params(
+        ("id", description = "The unique identifier of the toner or drum", example = "550e8400-e29b-41d4-a716-446655440000"),
+        SupplyMovementsParams
+    ),
+    responses(
+        (status = 200, description = "Movements retrieved successfully", body = Vec<MovementDetails>),
+        (status = 400, description = "Invalid limit"),
+        (status = 404, description = "No toner or drum found with the specified ID"),
+        (status = 500, description = "An error occurred while retrieving the movements")
+    )
+)]
+pub async fn show_supply_movements(
+    Path(id): Path<Uuid>,
+    Query(params): Query<SupplyMovementsParams>,
+    State(state): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, ApiError> {
+    // Validations
+    params.validate()?;
+
+    let exists = sqlx::query_scalar::<_, bool>(
+        r#"
+        SELECT EXISTS (SELECT 1 FROM toners WHERE id = $1)
+            OR EXISTS (SELECT 1 FROM drums WHERE id = $1);
+        "#,
+    )
+    .bind(id)
+    .fetch_one(&state.db)
+    .await
+    .map_err(|e| {
+        error!("Error checking for supply with id {id}: {e}");
+        ApiError::DatabaseError(e)
+    })?;
+
+    if !exists {
+        error!("No toner or drum found with id: {id}");
+        return Err(ApiError::IdNotFound { resource: "Supply" });
+    }
+
+    let movements = sqlx::query_as::<_, MovementView>(
+        r#"
+        SELECT 
+            m.id AS movement_id,
+            p.id AS printer_id,
+            p.name AS printer_name,
+            p.model AS printer_model,
+            CASE
+                WHEN t.id IS NOT NULL THEN t.id
+                ELSE d.id
+            END AS item_id,
+            CASE
+                WHEN t.id IS NOT NULL THEN t.name
+                ELSE d.name
+            END AS item_name,
+            m.quantity AS quantity,
+            m.kind AS kind,
+            m.created_at AS created_at,
+            m.updated_at AS updated_at
+        FROM movements m
+        LEFT JOIN printers p ON m.printer_id = p.id
+        LEFT JOIN toners t ON m.item_id = t.id
+        LEFT JOIN drums d ON m.item_id = d.id
+        WHERE m.item_id = $1
+        ORDER BY m.created_at DESC, m.id
+        LIMIT $2;
+        "#,
+    )
+    .bind(id)
+    .bind(params.limit)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| {
+        error!("Error listing movements of supply {id}: {e}");
+        ApiError::DatabaseError(e)
+    })?;
+
+    let movements: Vec<MovementDetails> =
+        movements.into_iter().map(MovementDetails::from).collect();
+
+    info!("Listed {} movements of supply {id}", movements.len());
+    Ok(Json(movements))
+}
+
+/// Sets the absolute stock of a supply.
+///
+/// This endpoint sets the stock of a toner or drum to the provided count, such as after a physical recount.
+/// The difference from the previous stock is recorded as an adjustment movement in the same transaction.
+/// Nothing is recorded when the stock is already at the count, and the count can't be below the reserved quantity.
+#[utoipa::path(
+    put,
+    path = "/api/v1/supplies/{id}/stock",
+    tags = ["Supplies"],
+    summary = "Set the stock of a supply.",
+    description = "This endpoint sets the absolute stock of a toner or drum and records the difference as an adjustment movement. Returns the ID of the adjustment movement, or no content when the stock already matches.",
+    params(
+        ("id", description = "The unique identifier of the toner or drum", example = "550e8400-e29b-41d4-a716-446655440000")
+    ),
+    request_body = SetStockRequest,
+    responses(
+        (status = 200, description = "Stock set successfully", body = Uuid),
+        (status = 204, description = "The stock already matches, no adjustment was recorded"),
+        (status = 400, description = "Invalid input"),
+        (status = 404, description = "No toner or drum found with the specified ID"),
+        (status = 422, description = "The stock would be below the quantity held by active reservations"),
+        (status = 500, description = "An error occurred while setting the stock")
+    )
+)]
+pub async fn set_stock(
+    Path(id): Path<Uuid>,
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<SetStockRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    // Validations
+    request.validate()?;
+
+    let mut tx = state.db.begin().await.map_err(|e| {
+        error!("Error starting transaction: {e}");
+        ApiError::DatabaseError(e)
+    })?;
+
+    let (item_type, current_stock) = lock_supply_stock(&mut tx, id).await?;
+
+    if request.stock == current_stock {
+        info!("Stock of item {id} is already {current_stock}, no adjustment recorded");
+        return Ok(StatusCode::NO_CONTENT.into_response());
+    }
+
+    let adjustment = Movement::adjustment(id, request.stock - current_stock);
+    let new_stock = apply_movement(&mut tx, &adjustment, item_type).await?;
+
+    tx.commit().await.map_err(|e| {
+        error!("Error committing stock adjustment: {e}");
+        ApiError::DatabaseError(e)
+    })?;
+
+    notify_stock_change(StockChangeEvent {
+        item_id: id,
+        new_stock,
+        movement_id: adjustment.id,
+    });
+
+    info!("Stock of item {id} set from {current_stock} to {new_stock}");
+    Ok(Json(adjustment.id).into_response())
+}
+
+/// Reserves a quantity of a supply for a limited time.
+///
+/// The reserved quantity is no longer available until the reservation is fulfilled, cancelled or expires,
+/// while the stock itself is left unchanged. Expired reservations are released automatically.
+#[utoipa::path(
+    post,
+    path = "/api/v1/supplies/{id}/reserve",
+    tags = ["Supplies"],
+    summary = "Reserve stock of a supply.",
+    description = "This endpoint holds `quantity` units of a toner or drum for `ttl_seconds`. The reservation fails if the available stock, excluding other active reservations, is not enough.",
+    params(
+        ("id", description = "The unique identifier of the toner or drum", example = "550e8400-e29b-41d4-a716-446655440000")
+    ),
+    request_body = ReserveRequest,
+    responses(
+        (status = 201, description = "Stock reserved successfully", body = CreatedReservation),
+        (status = 400, description = "Invalid input"),
+        (status = 404, description = "No toner or drum found with the specified ID"),
+        (status = 422, description = "Insufficient available stock for the requested quantity"),
+        (status = 500, description = "An error occurred while reserving the stock")
+    )
+)]
+pub async fn reserve_supply(
+    Path(id): Path<Uuid>,
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<ReserveRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    // Validations
+    request.validate()?;
+
+    let mut tx = state.db.begin().await.map_err(|e| {
+        error!("Error starting transaction: {e}");
+        ApiError::DatabaseError(e)
+    })?;
+
+    // Locking the item serializes concurrent reservations of the same supply
+    let (_, stock) = lock_supply_stock(&mut tx, id).await?;
+
+    // Release expired reservations
+    sqlx::query(r#"DELETE FROM reservations WHERE item_id = $1 AND expires_at <= NOW();"#)
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| {
+            error!("Error releasing expired reservations: {e}");
+            ApiError::DatabaseError(e)
+        })?;
+
+    let reserved = sqlx::query_scalar::<_, i64>(
+        r#"SELECT COALESCE(SUM(quantity), 0)::bigint FROM reservations WHERE item_id = $1;"#,
+    )
+    .bind(id)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| {
+        error!("Error fetching reserved quantity: {e}");
+        ApiError::DatabaseError(e)
+    })?;
+
+    let available = stock - reserved;
+    if available < request.quantity {
+        error!(
+            "Insufficient stock to reserve item '{id}': {available} available, {} requested.",
+            request.quantity
+        );
+        return Err(ApiError::InsufficientStock);
+    }
+
+    let reservation = CreatedReservation {
+        id: Uuid::new_v4(),
+        item_id: id,
+        quantity: request.quantity,
+        expires_at: Utc::now() + Duration::seconds(request.ttl_seconds),
+        available: available - request.quantity,
+    };
+
+    sqlx::query(
+        r#"INSERT INTO reservations (id, item_id, quantity, expires_at) VALUES ($1, $2, $3, $4);"#,
+    )
+    .bind(reservation.id)
+    .bind(reservation.item_id)
+    .bind(reservation.quantity)
+    .bind(reservation.expires_at)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| {
+        error!("Error creating reservation: {e}");
+        ApiError::DatabaseError(e)
+    })?;
+
+    tx.commit().await.map_err(|e| {
+        error!("Error committing reservation: {e}");
+        ApiError::DatabaseError(e)
+    })?;
+
+    info!(
+        "Reserved {} of item {id} until {}",
+        reservation.quantity, reservation.expires_at
+    );
+    Ok((StatusCode::CREATED, Json(reservation)))
+}
+
+/// Withdraws the units held by a reservation of a supply.
+///
+/// The reservation is released and its quantity is recorded as a regular movement for the printer,
+/// in a single transaction. Only active reservations can be fulfilled.
+#[utoipa::path(
+    post,
+    path = "/api/v1/supplies/{id}/reservations/{reservation_id}/fulfil",
+    tags = ["Supplies"],
+    summary = "Fulfil a reservation of a supply.",
+    description = "This endpoint withdraws the reserved quantity of a toner or drum for the given printer and releases the reservation. Returns the created movement along with the item's resulting stock.",
+    params(
+        ("id", description = "The unique identifier of the toner or drum", example = "550e8400-e29b-41d4-a716-446655440000"),
+        ("reservation_id", description = "The unique identifier of the reservation", example = "550e8400-e29b-41d4-a716-446655440000")
+    ),
+    request_body = FulfilReservationRequest,
+    responses(
+        (status = 201, description = "Reservation fulfilled successfully", body = CreatedMovement),
+        (status = 400, description = "Invalid input"),
+        (status = 404, description = "Supply, printer or active reservation not found"),
+        (status = 429, description = "Too many consumption movements for the item in the last minute"),
+        (status = 500, description = "An error occurred while fulfilling the reservation")
+    )
+)]
+pub async fn fulfil_reservation(
+    Path((id, reservation_id)): Path<(Uuid, Uuid)>,
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<FulfilReservationRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    // Validations
+    request.validate()?;
+
+    let mut tx = state.db.begin().await.map_err(|e| {
+        error!("Error starting transaction: {e}");
+        ApiError::DatabaseError(e)
+    })?;
+
+    let (item_type, _) = lock_supply_stock(&mut tx, id).await?;
+
+    // Releasing the reservation first makes its units available to the withdrawal
+    let quantity = sqlx::query_scalar::<_, i64>(
+        r#"
+        DELETE FROM reservations
+        WHERE id = $1 AND item_id = $2 AND expires_at > NOW()
+        RETURNING quantity::bigint;
+        "#,
+    )
+    .bind(reservation_id)
+    .bind(id)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| {
+        error!("Error releasing reservation: {e}");
+        ApiError::DatabaseError(e)
+    })?
+    .ok_or_else(|| {
+        error!("No active reservation {reservation_id} found for item {id}.");
+        ApiError::IdNotFound {
+            resource: "Reservation",
+        }
+    })?;
+
+    let withdrawal = Movement::new(
+        Uuid::from_str(&request.printer_id).unwrap(),
+        id,
+        -quantity,
+        None,
+    );
+    let new_stock = apply_movement(&mut tx, &withdrawal, item_type).await?;
+    check_consumption_rate(id)?;
+
+    tx.commit().await.map_err(|e| {
+        error!("Error committing reservation fulfilment: {e}");
+        ApiError::DatabaseError(e)
+    })?;
+
+    notify_stock_change(StockChangeEvent {
+        item_id: id,
+        new_stock,
+        movement_id: withdrawal.id,
+    });
+
+    info!(
+        "Reservation {reservation_id} of item {id} fulfilled by movement {}",
+        withdrawal.id
+    );
+    Ok((
+        StatusCode::CREATED,
+        location(&format!("/movements/{}", withdrawal.id)),
+        Json(CreatedMovement {
+            id: withdrawal.id,
+            item_id: id,
+            new_stock,
+        }),
+    ))
+}
+
+/// Cancels a reservation of a supply.
+///
+/// The reserved quantity becomes available again right away, instead of when the reservation expires.
+#[utoipa::path(
+    delete,
+    path = "/api/v1/supplies/{id}/reservations/{reservation_id}",
+    tags = ["Supplies"],
+    summary = "Cancel a reservation of a supply.",
+    description = "This endpoint releases an active reservation of a toner or drum, making its quantity available again.",
+    params(
+        ("id", description = "The unique identifier of the toner or drum", example = "550e8400-e29b-41d4-a716-446655440000"),
+        ("reservation_id", description = "The unique identifier of the reservation", example = "550e8400-e29b-41d4-a716-446655440000")
+    ),
+    responses(
+        (status = 204, description = "Reservation cancelled successfully"),
+        (status = 404, description = "No active reservation found for the supply"),
+        (status = 500, description = "An error occurred while cancelling the reservation")
+    )
+)]
+pub async fn cancel_reservation(
+    Path((id, reservation_id)): Path<(Uuid, Uuid)>,
+    State(state): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, ApiError> {
+    let result = sqlx::query(
+        r#"DELETE FROM reservations WHERE id = $1 AND item_id = $2 AND expires_at > NOW();"#,
+    )
+    .bind(reservation_id)
+    .bind(id)
+    .execute(&state.db)
+    .await
+    .map_err(|e| {
+        error!("Error cancelling reservation: {e}");
+        ApiError::DatabaseError(e)
+    })?;
+
+    if result.rows_affected() == 0 {
+        error!("No active reservation {reservation_id} found for item {id}.");
+        return Err(ApiError::IdNotFound {
+            resource: "Reservation",
+        });
+    }
+
+    info!("Reservation {reservation_id} of item {id} cancelled");
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Locks the stock of a toner or drum for the rest of the transaction.
+///
+/// Returns the type of the supply and its current stock, or `IdNotFound` if the ID is neither a toner nor a drum.
+async fn lock_supply_stock(
+    tx: &mut Transaction<'_, Postgres>,
+    id: Uuid,
+) -> Result<(ItemType, i64), ApiError> {
+    let toner_stock = sqlx::query_scalar::<_, Option<i64>>(
+        r#"SELECT stock FROM toners WHERE id = $1 FOR UPDATE;"#,
+    )
+    .bind(id)
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(|e| {
+        error!("Error fetching toner stock: {e}");
+        ApiError::DatabaseError(e)
+    })?;
+
+    let (item_type, stock) = match toner_stock {
+        Some(stock) => (ItemType::Toner, Some(stock)),
+        None => {
+            let drum_stock = sqlx::query_scalar::<_, Option<i64>>(
+                r#"SELECT stock FROM drums WHERE id = $1 FOR UPDATE;"#,
+            )
+            .bind(id)
+            .fetch_optional(&mut **tx)
+            .await
+            .map_err(|e| {
+                error!("Error fetching drum stock: {e}");
+                ApiError::DatabaseError(e)
+            })?;
+
+            (ItemType::Drum, drum_stock)
+        }
+    };
+
+    let stock = stock
+        .ok_or_else(|| {
+            error!("Item with ID '{id}' not found in toners or drums.");
+            ApiError::IdNotFound { resource: "Supply" }
+        })?
+        .unwrap_or(0);
+
+    Ok((item_type, stock))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::{
+        app, create_drum, create_printer, create_toner, empty_request, json_request, send,
+    };
+    use axum::{
+        http::{Method, StatusCode},
+        Router,
+    };
+    use serde_json::{json, Value};
+    use sqlx::PgPool;
+    use uuid::Uuid;
+
+    async fn reserve(app: &Router, item_id: Uuid, quantity: i64) -> Value {
+        let (status, reservation) = send(
+            app,
+            json_request(
+                Method::POST,
+                &format!("/api/v1/supplies/{item_id}/reserve"),
+                json!({ "quantity": quantity, "ttl_seconds": 600 }),
+            ),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        reservation
+    }
+
+    async fn supply(app: &Router, item_id: Uuid) -> Value {
+        send(
+            app,
+            empty_request(Method::GET, &format!("/api/v1/supplies/{item_id}")),
+        )
+        .await
+        .1
+    }
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn fulfilling_a_reservation_withdraws_its_units(pool: PgPool) {
+        let app = app(pool);
+        let printer = create_printer(&app, "Holding Printer").await;
+        let toner = create_toner(&app, "Held Toner", 10).await;
+        let reservation = reserve(&app, toner, 8).await;
+
+        let (status, movement) = send(
+            &app,
+            json_request(
+                Method::POST,
+                &format!(
+                    "/api/v1/supplies/{toner}/reservations/{}/fulfil",
+                    reservation["id"].as_str().unwrap()
+                ),
+                json!({ "printer_id": printer }),
+            ),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(movement["new_stock"], 2);
+        let supply = supply(&app, toner).await;
+        assert_eq!(supply["reserved"], 0);
+        assert_eq!(supply["available"], 2);
+    }
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn cancelling_a_reservation_releases_its_units(pool: PgPool) {
+        let app = app(pool);
+        let toner = create_toner(&app, "Released Toner", 10).await;
+        let reservation = reserve(&app, toner, 8).await;
+        let uri = format!(
+            "/api/v1/supplies/{toner}/reservations/{}",
+            reservation["id"].as_str().unwrap()
+        );
+
+        let (status, _) = send(&app, empty_request(Method::DELETE, &uri)).await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        assert_eq!(supply(&app, toner).await["available"], 10);
+
+        let (status, _) = send(&app, empty_request(Method::DELETE, &uri)).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn available_stock_comes_back_after_expiry(pool: PgPool) {
+        let app = app(pool.clone());
+        let toner = create_toner(&app, "Expiring Toner", 10).await;
+        reserve(&app, toner, 8).await;
+        assert_eq!(supply(&app, toner).await["available"], 2);
+
+        sqlx::query("UPDATE reservations SET expires_at = NOW() - INTERVAL '1 second';")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let supply = supply(&app, toner).await;
+        assert_eq!(supply["reserved"], 0);
+        assert_eq!(supply["available"], 10);
+    }
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn setting_the_stock_records_the_difference(pool: PgPool) {
+        let app = app(pool);
+        let toner = create_toner(&app, "Recounted Toner", 10).await;
+        let set_stock = |stock: i64| {
+            json_request(
+                Method::PUT,
+                &format!("/api/v1/supplies/{toner}/stock"),
+                json!({ "stock": stock }),
+            )
+        };
+
+        let (status, _) = send(&app, set_stock(3)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(supply(&app, toner).await["stock"], 3);
+
+        let movements_uri = format!("/api/v1/supplies/{toner}/movements");
+        let (_, movements) = send(&app, empty_request(Method::GET, &movements_uri)).await;
+        assert_eq!(movements[0]["quantity"], -7);
+        assert_eq!(movements[0]["kind"], "adjustment");
+
+        let (status, _) = send(&app, set_stock(3)).await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        let (_, unchanged) = send(&app, empty_request(Method::GET, &movements_uri)).await;
+        assert_eq!(unchanged, movements);
+    }
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn the_stock_cannot_be_set_below_the_reserved_quantity(pool: PgPool) {
+        let app = app(pool);
+        let toner = create_toner(&app, "Recounted Reserved Toner", 10).await;
+        reserve(&app, toner, 5).await;
+
+        let (status, body) = send(
+            &app,
+            json_request(
+                Method::PUT,
+                &format!("/api/v1/supplies/{toner}/stock"),
+                json!({ "stock": 3 }),
+            ),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(body["code"], "INSUFFICIENT_STOCK");
+        assert_eq!(supply(&app, toner).await["stock"], 10);
+    }
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn supply_movements_are_listed_newest_first(pool: PgPool) {
+        let app = app(pool);
+        let printer = create_printer(&app, "ECOSYS M2040").await;
+        let toner = create_toner(&app, "TN-1060", 10).await;
+        for (quantity, days_ago) in [(-1, 3), (-2, 1)] {
+            let (status, _) = send(
+                &app,
+                json_request(
+                    Method::POST,
+                    "/api/v1/movements",
+                    json!({
+                        "printer_id": printer,
+                        "item_id": toner,
+                        "item_type": "toner",
+                        "quantity": quantity,
+                        "created_at": chrono::Utc::now() - chrono::Duration::days(days_ago),
+                    }),
+                ),
+            )
+            .await;
+            assert_eq!(status, StatusCode::CREATED);
+        }
+
+        let (status, movements) = send(
+            &app,
+            empty_request(Method::GET, &format!("/api/v1/supplies/{toner}/movements")),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let quantities: Vec<_> = movements
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|movement| movement["quantity"].as_i64().unwrap())
+            .collect();
+        // The opening movement of the initial stock is the newest
+        assert_eq!(quantities, [10, -2, -1]);
+        assert_eq!(movements[1]["printer"]["name"], "ECOSYS M2040");
+
+        let (_, movements) = send(
+            &app,
+            empty_request(
+                Method::GET,
+                &format!("/api/v1/supplies/{toner}/movements?limit=1"),
+            ),
+        )
+        .await;
+        assert_eq!(movements.as_array().unwrap().len(), 1);
+
+        let drum = create_drum(&app, "DK-1150", 0).await;
+        let (_, movements) = send(
+            &app,
+            empty_request(Method::GET, &format!("/api/v1/supplies/{drum}/movements")),
+        )
+        .await;
+        assert_eq!(movements, json!([]));
+
+        let (status, _) = send(
+            &app,
+            empty_request(
+                Method::GET,
+                "/api/v1/supplies/550e8400-e29b-41d4-a716-446655440000/movements",
+            ),
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+}
@@ -1,19 +1,42 @@
 use crate::{
     errors::api_error::ApiError,
+    extractors::if_match::{etag, IfMatch},
+    extractors::json::Json,
+    extractors::path::Path,
+    extractors::prefer::ReturnPreference,
+    handlers::{movement::apply_movement, search::escape_like},
     models::{
-        supplies::toner::{CreateTonerRequest, Toner, UpdateTonerRequest},
-        DeleteRequest,
+        movement::{ItemType, Movement},
+        pagination::{ListParams, Page, PageInfo},
+        search::{SearchParams, Suggestion, DEFAULT_SEARCH_LIMIT, MAX_SEARCH_LIMIT},
+        supplies::{
+            toner::{CreateTonerRequest, Toner, UpdateTonerRequest},
+            SupplyCount, SupplyCountParams, SupplyCountResponse, SupplyFilterParams,
+        },
+        CompactItem, CompactParams, DeleteRequest, ExistsRequest, ReturnParams,
+    },
+    routes::location,
+    validations::{
+        currency::normalize_currency,
+        existence::{brand_exists, toner_exists},
+        price::normalize_price,
+        uniqueness::{
+            conflict_or_database_error, is_toner_name_available, is_toner_unique, reject_taken_name,
+        },
     },
-    validations::{existence::toner_exists, uniqueness::is_toner_unique},
 };
 use axum::{
-    extract::{Path, State},
-    http::StatusCode,
-    response::IntoResponse,
-    Json,
+    extract::{Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
 };
+use config::Config;
 use infra::database::AppState;
-use std::sync::Arc;
+use serde::Serialize;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
 use tracing::{error, info};
 use uuid::Uuid;
 use validator::Validate;
@@ -21,37 +44,103 @@ use validator::Validate;
 /// Retrieves the total count of toners.
 ///
 /// This endpoint counts all toners stored in the database and returns the count as an integer.
+/// With `with_stock=true` the count is returned in an object along with the total units in stock.
 /// If no toners are found, 0 is returned.
 #[utoipa::path(
     get,
     path = "/api/v1/supplies/toners/count",
     tags = ["Toners"],
     summary = "Get the total count of toners.",
-    description = "This endpoint retrieves the total number of toners stored in the database.",
+    description = "This endpoint retrieves the total number of toners stored in the database. With `with_stock=true` the response is a `SupplyCount` object that also holds the sum of their stock.",
+    params(SupplyCountParams),
     responses(
-        (status = 200, description = "Toner count retrieved successfully", body = i32),
+        (status = 200, description = "Toner count retrieved successfully: the bare count, or a `SupplyCount` with `with_stock=true`", body = SupplyCountResponse),
         (status = 500, description = "An error occurred while retrieving the toner count")
     )
 )]
 pub async fn count_toners(
+    Query(params): Query<SupplyCountParams>,
     State(state): State<Arc<AppState>>,
 ) -> Result<impl IntoResponse, ApiError> {
-    let count = sqlx::query_scalar::<_, i64>(r#"SELECT COUNT(*) FROM toners;"#)
-        .fetch_one(&state.db)
-        .await
-        .map_err(|e| {
-            error!("Error retrieving toner count: {e}");
-            ApiError::DatabaseError(e)
-        })?;
+    let count = sqlx::query_as::<_, SupplyCount>(
+        r#"SELECT COUNT(*) AS count, COALESCE(SUM(stock), 0)::bigint AS total_stock FROM toners;"#,
+    )
+    .fetch_one(&state.db)
+    .await
+    .map_err(|e| {
+        error!("Error retrieving toner count: {e}");
+        ApiError::DatabaseError(e)
+    })?;
 
-    info!("Successfully retrieved toner count: {count}");
-    Ok(Json(count))
+    info!("Successfully retrieved toner count: {}", count.count);
+    let response = if params.with_stock.unwrap_or(false) {
+        SupplyCountResponse::WithStock(count)
+    } else {
+        SupplyCountResponse::Count(count.count)
+    };
+    Ok(Json(response))
+}
+
+/// Suggests toners whose name matches a partial name.
+///
+/// This endpoint returns only the ID and name of each toner, listing names that start with `q`
+/// before names that merely contain it. An empty query returns an empty list.
+#[utoipa::path(
+    get,
+    path = "/api/v1/supplies/toners/autocomplete",
+    tags = ["Toners"],
+    summary = "Suggest toners by partial name.",
+    description = "Returns up to `limit` toners whose name contains `q` (case-insensitive), prefix matches first. An empty `q` returns an empty array.",
+    params(SearchParams),
+    responses(
+        (status = 200, description = "Suggestions retrieved successfully", body = Vec<Suggestion>),
+        (status = 500, description = "An error occurred while retrieving the suggestions")
+    )
+)]
+pub async fn autocomplete_toners(
+    Query(params): Query<SearchParams>,
+    State(state): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, ApiError> {
+    let query = params.q.unwrap_or_default();
+    let query = query.trim();
+
+    if query.is_empty() {
+        return Ok(Json(Vec::<Suggestion>::new()));
+    }
+
+    let limit = params
+        .limit
+        .unwrap_or(DEFAULT_SEARCH_LIMIT)
+        .clamp(1, MAX_SEARCH_LIMIT);
+
+    let suggestions = sqlx::query_as::<_, Suggestion>(
+        r#"
+        SELECT id, name FROM toners
+        WHERE name ILIKE '%' || $1 || '%'
+        ORDER BY CASE WHEN name ILIKE $1 || '%' THEN 0 ELSE 1 END, name
+        LIMIT $2;
+        "#,
+    )
+    .bind(escape_like(query))
+    .bind(limit)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| {
+        error!("Error retrieving toner suggestions for '{query}': {e}");
+        ApiError::DatabaseError(e)
+    })?;
+
+    info!(
+        "Toner autocomplete for '{query}' returned {} suggestions",
+        suggestions.len()
+    );
+    Ok(Json(suggestions))
 }
 
 /// Retrieves a specific toner by its ID.
 ///
 /// This endpoint searches for a toner with the specified ID.
-/// If the toner is found, it returns the toner details.
+/// If the toner is found, it returns the toner details along with their `ETag`.
 #[utoipa::path(
     get,
     path = "/api/v1/supplies/toners/{id}",
@@ -62,7 +151,8 @@ pub async fn count_toners(
         ("id", description = "The unique identifier of the toner to retrieve", example = "550e8400-e29b-41d4-a716-446655440000")
     ),
     responses(
-        (status = 200, description = "Toner retrieved successfully", body = Toner),
+        (status = 200, description = "Toner retrieved successfully", body = Toner,
+            headers(("ETag" = String, description = "Tag of the current state of the toner, for `If-Match` updates"))),
         (status = 404, description = "No toner found with the specified ID"),
         (status = 500, description = "An error occurred while retrieving the toner")
     )
@@ -83,44 +173,221 @@ pub async fn search_toner(
     match toner {
         Some(toner) => {
             info!("Toner found: {id}");
-            Ok(Json(toner))
+            Ok(([(header::ETAG, etag(&toner))], Json(toner)))
         }
         None => {
             error!("No toner found with id: {id}");
-            Err(ApiError::IdNotFound)
+            Err(ApiError::IdNotFound { resource: "Toner" })
         }
     }
 }
 
+/// Checks whether a toner exists.
+///
+/// This endpoint answers with an empty body, using an existence query instead of fetching the toner.
+/// Useful for clients that only need to know whether the ID is valid.
+#[utoipa::path(
+    head,
+    path = "/api/v1/supplies/toners/{id}",
+    tags = ["Toners"],
+    summary = "Check whether a toner exists.",
+    description = "This endpoint checks whether a toner with the given ID exists, without returning its details.",
+    params(
+        ("id", description = "The unique identifier of the toner to check", example = "550e8400-e29b-41d4-a716-446655440000")
+    ),
+    responses(
+        (status = 200, description = "Toner exists"),
+        (status = 404, description = "No toner found with the specified ID"),
+        (status = 500, description = "An error occurred while checking the toner")
+    )
+)]
+pub async fn check_toner(
+    Path(id): Path<Uuid>,
+    State(state): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, ApiError> {
+    toner_exists(state, id).await?;
+
+    Ok(StatusCode::OK)
+}
+
+/// Checks which of several toners exist.
+///
+/// This endpoint looks up all the given IDs in a single query, so clients can validate them before bulk operations.
+/// Returns an object mapping each ID to whether a toner with that ID exists.
+#[utoipa::path(
+    post,
+    path = "/api/v1/supplies/toners/exists",
+    tags = ["Toners"],
+    summary = "Check whether several toners exist.",
+    description = "This endpoint answers with an object holding `true` or `false` for each of the given toner IDs. At most 1000 IDs can be checked at once.",
+    request_body = ExistsRequest,
+    responses(
+        (status = 200, description = "Existence checked successfully", body = HashMap<String, bool>),
+        (status = 400, description = "Too many IDs"),
+        (status = 500, description = "An error occurred while checking the toners")
+    )
+)]
+pub async fn check_toners(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<ExistsRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    // Validations
+    request.validate()?;
+
+    let existing: HashSet<Uuid> =
+        sqlx::query_scalar::<_, Uuid>(r#"SELECT id FROM toners WHERE id = ANY($1);"#)
+            .bind(&request.ids)
+            .fetch_all(&state.db)
+            .await
+            .map_err(|e| {
+                error!("Error checking toner IDs: {e}");
+                ApiError::DatabaseError(e)
+            })?
+            .into_iter()
+            .collect();
+
+    let exists: HashMap<Uuid, bool> = request
+        .ids
+        .iter()
+        .map(|id| (*id, existing.contains(id)))
+        .collect();
+
+    info!(
+        "Checked {} toner IDs, {} exist",
+        exists.len(),
+        existing.len()
+    );
+    Ok(Json(exists))
+}
+
 /// Retrieves a list of all toners.
 ///
-/// This endpoint fetches all toners stored in the database.
+/// This endpoint fetches the toners stored in the database, ordered by name,
+/// optionally limited to a page with `limit` and `offset`, and filtered by whether their stock and price are set.
+/// With `envelope=true` the toners are wrapped with the page details, and with `compact=true` only their IDs and names are listed.
 /// If there are no toners, returns an empty array.
 #[utoipa::path(
     get,
     path = "/api/v1/supplies/toners",
     tags = ["Toners"],
     summary = "List all toners.",
-    description = "Fetches all toners stored in the database. If there are no toners, returns an empty array.",
+    description = "Fetches the toners stored in the database, ordered by name. If there are no toners, returns an empty array. With `envelope=true` the response is a `Page` object holding the toners and the total count. `has_stock` and `has_price` keep only the toners whose stock or price is set (`true`) or missing (`false`). With `compact=true` each toner is a `CompactItem` holding only its `id` and `name`.",
+    params(ListParams, SupplyFilterParams, CompactParams),
     responses(
         (status = 200, description = "Toners retrieved successfully", body = Vec<Toner>),
-        (status = 404, description = "No toners found in the database"),
+        (status = 400, description = "Invalid limit or offset"),
         (status = 500, description = "An error occurred while retrieving the toners")
     )
 )]
 pub async fn show_toners(
+    Query(params): Query<ListParams>,
+    Query(filter): Query<SupplyFilterParams>,
+    Query(compact): Query<CompactParams>,
     State(state): State<Arc<AppState>>,
 ) -> Result<impl IntoResponse, ApiError> {
-    let toners = sqlx::query_as::<_, Toner>(r#"SELECT * FROM toners;"#)
+    // Validations
+    params.validate()?;
+    let offset = params.offset.unwrap_or(0);
+
+    if compact.compact.unwrap_or(false) {
+        let toners = sqlx::query_as::<_, CompactItem>(
+            r#"
+            SELECT id, name FROM toners
+            WHERE ($3::bool IS NULL OR (stock IS NOT NULL) = $3)
+                AND ($4::bool IS NULL OR (price IS NOT NULL) = $4)
+            ORDER BY name, id
+            LIMIT $1 OFFSET $2;
+            "#,
+        )
+        .bind(params.limit)
+        .bind(offset)
+        .bind(filter.has_stock)
+        .bind(filter.has_price)
         .fetch_all(&state.db)
         .await
         .map_err(|e| {
-            error!("Error listing toners: {e}");
+            error!("Error listing compact toners: {e}");
             ApiError::DatabaseError(e)
         })?;
 
-    info!("Toners listed successfully");
-    Ok(Json(toners))
+        return toner_list(toners, &params, &filter, &state).await;
+    }
+
+    let toners = sqlx::query_as::<_, Toner>(
+        r#"
+        SELECT * FROM toners
+        WHERE ($3::bool IS NULL OR (stock IS NOT NULL) = $3)
+            AND ($4::bool IS NULL OR (price IS NOT NULL) = $4)
+        ORDER BY name, id
+        LIMIT $1 OFFSET $2;
+        "#,
+    )
+    .bind(params.limit)
+    .bind(offset)
+    .bind(filter.has_stock)
+    .bind(filter.has_price)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| {
+        error!("Error listing toners: {e}");
+        ApiError::DatabaseError(e)
+    })?;
+
+    toner_list(toners, &params, &filter, &state).await
+}
+
+/// Answers with the listed toners, wrapped in a `Page` when `envelope=true` is requested.
+async fn toner_list<T: Serialize>(
+    toners: Vec<T>,
+    params: &ListParams,
+    filter: &SupplyFilterParams,
+    state: &AppState,
+) -> Result<Response, ApiError> {
+    if !params.envelope.unwrap_or(false) {
+        info!("Toners listed successfully");
+        return Ok(Json(toners).into_response());
+    }
+
+    // Only the unfiltered total is cached, as it changes just when toners are created or deleted
+    let ttl = Config::count_cache_ttl()
+        .filter(|_| filter.has_stock.is_none() && filter.has_price.is_none());
+
+    let total = match ttl.and_then(|ttl| state.toner_count.get(ttl)) {
+        Some(total) => total,
+        None => {
+            let total = sqlx::query_scalar::<_, i64>(
+                r#"
+                SELECT COUNT(*) FROM toners
+                WHERE ($1::bool IS NULL OR (stock IS NOT NULL) = $1)
+                    AND ($2::bool IS NULL OR (price IS NOT NULL) = $2);
+                "#,
+            )
+            .bind(filter.has_stock)
+            .bind(filter.has_price)
+            .fetch_one(&state.db)
+            .await
+            .map_err(|e| {
+                error!("Error counting toners: {e}");
+                ApiError::DatabaseError(e)
+            })?;
+
+            if ttl.is_some() {
+                state.toner_count.set(total);
+            }
+            total
+        }
+    };
+
+    info!("Toners listed successfully in an envelope");
+    Ok(Json(Page {
+        data: toners,
+        page: PageInfo {
+            limit: params.limit,
+            offset: params.offset.unwrap_or(0),
+            total,
+        },
+    })
+    .into_response())
 }
 
 /// Create a new toner.
@@ -128,44 +395,104 @@ pub async fn show_toners(
 /// This endpoint creates a new toner by providing its details.
 /// Validates the toner's name for length and emptiness, checks for duplicates,
 /// and inserts the new toner into the database if all validations pass.
+/// An initial stock is recorded as an opening movement in the same transaction.
 #[utoipa::path(
     post,
     path = "/api/v1/supplies/toners",
     tags = ["Toners"],
     summary = "Create a new toner.",
-    description = "This endpoint creates a new toner in the database with the provided details.",
+    description = "This endpoint creates a new toner in the database with the provided details. A positive `stock` is recorded as an `opening` movement, so the stock matches the movement history from the start.",
     request_body = CreateTonerRequest,
+    params(
+        ReturnParams,
+        ("Prefer" = Option<String>, Header, description = "`return=representation` returns the created toner instead of its ID")
+    ),
     responses(
-        (status = 201, description = "Toner created successfully", body = Uuid),
-        (status = 400, description = "Invalid input, including empty name or name too short/long"),
+        (status = 201, description = "Toner created successfully. Answers with the full toner instead of its ID when `return=representation` is requested", body = Uuid),
+        (status = 400, description = "Invalid input, including empty name, name too short/long, unknown currency or a price too large once rounded"),
         (status = 409, description = "Conflict: Toner with the same name already exists"),
         (status = 500, description = "An error occurred while creating the toner")
     )
 )]
 pub async fn create_toner(
+    preference: ReturnPreference,
     State(state): State<Arc<AppState>>,
     Json(request): Json<CreateTonerRequest>,
 ) -> Result<impl IntoResponse, ApiError> {
     // Validations
     request.validate()?;
-    is_toner_unique(state.clone(), request.name.clone()).await?;
+    if let Some(brand_id) = request.brand_id {
+        brand_exists(state.clone(), brand_id).await?;
+    }
+    let existing_id =
+        is_toner_unique(state.clone(), request.name.clone(), request.brand_id).await?;
+    reject_taken_name(request.name.clone(), existing_id)?;
 
-    let new_toner = Toner::new(&request.name, request.stock, request.price);
+    // A positive stock is added by the opening movement below
+    let opening_stock = request.stock.filter(|stock| *stock > 0);
+    let new_toner = Toner::new(
+        &request.name,
+        opening_stock.map_or(request.stock, |_| Some(0)),
+        request.price.map(normalize_price),
+        request.brand_id,
+        request.currency.as_deref().map(normalize_currency),
+    );
 
-    sqlx::query(r#"INSERT INTO toners (id, name, stock, price) VALUES ($1, $2, $3, $4);"#)
-        .bind(new_toner.id)
-        .bind(&new_toner.name)
-        .bind(new_toner.stock)
-        .bind(new_toner.price)
-        .execute(&state.db)
-        .await
-        .map_err(|e| {
-            error!("Error creating toner: {e}");
-            ApiError::DatabaseError(e)
-        })?;
+    let mut tx = state.db.begin().await.map_err(|e| {
+        error!("Error starting transaction: {e}");
+        ApiError::DatabaseError(e)
+    })?;
+
+    let result = sqlx::query(
+        r#"INSERT INTO toners (id, name, stock, price, brand_id, currency) VALUES ($1, $2, $3, $4, $5, $6);"#,
+    )
+    .bind(new_toner.id)
+    .bind(&new_toner.name)
+    .bind(new_toner.stock)
+    .bind(new_toner.price)
+    .bind(new_toner.brand_id)
+    .bind(&new_toner.currency)
+    .execute(&mut *tx)
+    .await;
+
+    if let Err(e) = result {
+        error!("Error creating toner: {e}");
+        let check = is_toner_unique(state.clone(), request.name.clone(), request.brand_id);
+        return Err(conflict_or_database_error(e, request.name, check).await);
+    }
+
+    if let Some(stock) = opening_stock {
+        let opening = Movement::opening(new_toner.id, stock);
+        apply_movement(&mut tx, &opening, ItemType::Toner).await?;
+    }
+
+    // Read back within the transaction, so the stock reflects the opening movement
+    let created = match preference {
+        ReturnPreference::Representation => Some(
+            sqlx::query_as::<_, Toner>(r#"SELECT * FROM toners WHERE id = $1;"#)
+                .bind(new_toner.id)
+                .fetch_one(&mut *tx)
+                .await
+                .map_err(|e| {
+                    error!("Error reading back created toner: {e}");
+                    ApiError::DatabaseError(e)
+                })?,
+        ),
+        ReturnPreference::Minimal => None,
+    };
+
+    tx.commit().await.map_err(|e| {
+        error!("Error committing toner creation: {e}");
+        ApiError::DatabaseError(e)
+    })?;
+    state.toner_count.invalidate();
 
     info!("Toner created! ID: {}", &new_toner.id);
-    Ok((StatusCode::CREATED, Json(new_toner.id)))
+    Ok((
+        StatusCode::CREATED,
+        location(&format!("/supplies/toners/{}", new_toner.id)),
+        preference.body(new_toner.id, created),
+    ))
 }
 
 /// Updates an existing toner.
@@ -180,41 +507,94 @@ pub async fn create_toner(
     path = "/api/v1/supplies/toners",
     tags = ["Toners"],
     summary = "Update an existing toner.",
-    description = "This endpoint updates the details of an existing toner in the database.",
+    description = "This endpoint updates the details of an existing toner in the database. With an `If-Match` header holding the `ETag` of a previous GET, the update is rejected with `412` if the toner changed since.",
     request_body = UpdateTonerRequest,
+    params(
+        ("If-Match" = Option<String>, Header, description = "ETag the toner must still have for the update to apply")
+    ),
     responses(
         (status = 200, description = "Toner updated successfully", body = Uuid),
-        (status = 400, description = "Invalid input, including empty name or name too short/long"),
+        (status = 400, description = "Invalid input, including empty name, name too short/long, unknown currency or a price too large once rounded"),
         (status = 404, description = "Toner ID not found"),
         (status = 409, description = "Conflict: Toner with the same name already exists"),
+        (status = 412, description = "The toner no longer matches the `If-Match` ETag"),
         (status = 500, description = "An error occurred while updating the toner")
     )
 )]
 pub async fn update_toner(
+    if_match: IfMatch,
     State(state): State<Arc<AppState>>,
     Json(request): Json<UpdateTonerRequest>,
 ) -> Result<impl IntoResponse, ApiError> {
     // Validations
     request.validate()?;
     toner_exists(state.clone(), request.id.clone()).await?;
+    if let Some(brand_id) = request.brand_id {
+        brand_exists(state.clone(), brand_id).await?;
+    }
 
     let toner_id = request.id;
     let new_name = request.name.clone();
     let new_stock = request.stock;
-    let new_price = request.price;
+    let new_price = request.price.map(normalize_price);
+    let new_currency = request.currency.as_deref().map(normalize_currency);
+
+    let mut tx = state.db.begin().await.map_err(|e| {
+        error!("Error starting transaction: {e}");
+        ApiError::DatabaseError(e)
+    })?;
+
+    // Lock the toner and check that it still matches the ETag the client read
+    if if_match.is_present() {
+        let current =
+            sqlx::query_as::<_, Toner>(r#"SELECT * FROM toners WHERE id = $1 FOR UPDATE;"#)
+                .bind(toner_id)
+                .fetch_one(&mut *tx)
+                .await
+                .map_err(|e| {
+                    error!("Error fetching toner by ID: {e}");
+                    ApiError::DatabaseError(e)
+                })?;
+        if_match.check(&etag(&current))?;
+    }
 
-    // Validate and update name if provided
+    // The new name and brand must not collide with another toner, even when only one of them changes
+    let renamed = if new_name.is_some() || request.brand_id.is_some() {
+        let (current_name, current_brand_id) = sqlx::query_as::<_, (String, Option<Uuid>)>(
+            r#"SELECT name, brand_id FROM toners WHERE id = $1 FOR UPDATE;"#,
+        )
+        .bind(toner_id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| {
+            error!("Error fetching toner by ID: {e}");
+            ApiError::DatabaseError(e)
+        })?;
+
+        let name = new_name.clone().unwrap_or(current_name);
+        let brand_id = request.brand_id.or(current_brand_id);
+        let existing_id =
+            is_toner_name_available(state.clone(), name.clone(), brand_id, toner_id).await?;
+        reject_taken_name(name.clone(), existing_id)?;
+        Some((name, brand_id))
+    } else {
+        None
+    };
+
+    // Update name if provided
     if let Some(name) = new_name {
-        // Update toner name
-        sqlx::query(r#"UPDATE toners SET name = $1 WHERE id = $2;"#)
+        let result = sqlx::query(r#"UPDATE toners SET name = $1 WHERE id = $2;"#)
             .bind(&name)
             .bind(toner_id)
-            .execute(&state.db)
-            .await
-            .map_err(|e| {
-                error!("Error updating toner name: {e}");
-                ApiError::DatabaseError(e)
-            })?;
+            .execute(&mut *tx)
+            .await;
+
+        if let Err(e) = result {
+            error!("Error updating toner name: {e}");
+            let (name, brand_id) = renamed.unwrap_or_default();
+            let check = is_toner_name_available(state.clone(), name.clone(), brand_id, toner_id);
+            return Err(conflict_or_database_error(e, name, check).await);
+        }
     }
 
     // Update stock if provided
@@ -222,7 +602,7 @@ pub async fn update_toner(
         sqlx::query(r#"UPDATE toners SET stock = $1 WHERE id = $2;"#)
             .bind(stock)
             .bind(toner_id)
-            .execute(&state.db)
+            .execute(&mut *tx)
             .await
             .map_err(|e| {
                 error!("Error updating toner stock: {e}");
@@ -235,7 +615,7 @@ pub async fn update_toner(
         sqlx::query(r#"UPDATE toners SET price = $1 WHERE id = $2;"#)
             .bind(price)
             .bind(toner_id)
-            .execute(&state.db)
+            .execute(&mut *tx)
             .await
             .map_err(|e| {
                 error!("Error updating toner price: {e}");
@@ -243,6 +623,40 @@ pub async fn update_toner(
             })?;
     }
 
+    // Update currency if provided
+    if let Some(currency) = new_currency {
+        sqlx::query(r#"UPDATE toners SET currency = $1 WHERE id = $2;"#)
+            .bind(currency)
+            .bind(toner_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| {
+                error!("Error updating toner currency: {e}");
+                ApiError::DatabaseError(e)
+            })?;
+    }
+
+    // Update brand if provided
+    if let Some(brand_id) = request.brand_id {
+        let result = sqlx::query(r#"UPDATE toners SET brand_id = $1 WHERE id = $2;"#)
+            .bind(brand_id)
+            .bind(toner_id)
+            .execute(&mut *tx)
+            .await;
+
+        if let Err(e) = result {
+            error!("Error updating toner brand: {e}");
+            let (name, brand_id) = renamed.unwrap_or_default();
+            let check = is_toner_name_available(state.clone(), name.clone(), brand_id, toner_id);
+            return Err(conflict_or_database_error(e, name, check).await);
+        }
+    }
+
+    tx.commit().await.map_err(|e| {
+        error!("Error committing toner update: {e}");
+        ApiError::DatabaseError(e)
+    })?;
+
     info!("Toner updated! ID: {}", &toner_id);
     Ok((StatusCode::OK, Json(toner_id)).into_response())
 }
@@ -281,7 +695,300 @@ pub async fn delete_toner(
             error!("Error deleting toner: {}", e);
             ApiError::DatabaseError(e)
         })?;
+    state.toner_count.invalidate();
 
     info!("Toner deleted! ID: {}", &request.id);
     Ok((StatusCode::OK, Json("Toner deleted!")).into_response())
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::{
+        app, create_brand, create_toner, empty_request, json_request, send, stock_of,
+    };
+    use axum::http::{Method, StatusCode};
+    use serde_json::json;
+    use sqlx::PgPool;
+    use std::env;
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn renaming_to_a_taken_name_conflicts(pool: PgPool) {
+        let app = app(pool);
+        create_toner(&app, "TN-1060", 0).await;
+        let renamed = create_toner(&app, "TN-2370", 0).await;
+
+        let (status, body) = send(
+            &app,
+            json_request(
+                Method::PUT,
+                "/api/v1/supplies/toners",
+                json!({ "id": renamed, "name": "TN-1060" }),
+            ),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(body["code"], "ALREADY_EXISTS");
+    }
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn keeping_its_own_name_is_not_a_conflict(pool: PgPool) {
+        let app = app(pool);
+        let toner = create_toner(&app, "TN-1060", 0).await;
+
+        let (status, _) = send(
+            &app,
+            json_request(
+                Method::PUT,
+                "/api/v1/supplies/toners",
+                json!({ "id": toner, "name": "TN-1060" }),
+            ),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn prices_are_stored_with_two_places(pool: PgPool) {
+        let app = app(pool);
+
+        let (status, id) = send(
+            &app,
+            json_request(
+                Method::POST,
+                "/api/v1/supplies/toners",
+                json!({ "name": "TN-1060", "price": "19.999" }),
+            ),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+
+        let (_, toner) = send(
+            &app,
+            empty_request(
+                Method::GET,
+                &format!("/api/v1/supplies/toners/{}", id.as_str().unwrap()),
+            ),
+        )
+        .await;
+        assert_eq!(toner["price"], json!(20.0));
+    }
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn prices_rounding_past_the_column_are_rejected(pool: PgPool) {
+        let app = app(pool);
+
+        let (status, body) = send(
+            &app,
+            json_request(
+                Method::POST,
+                "/api/v1/supplies/toners",
+                json!({ "name": "TN-1060", "price": "9999.999" }),
+            ),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["code"], "VALIDATION_ERROR");
+    }
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn counts_include_the_total_stock_on_request(pool: PgPool) {
+        let app = app(pool);
+        create_toner(&app, "TN-1060", 3).await;
+        create_toner(&app, "TN-2370", 5).await;
+
+        let (status, count) = send(
+            &app,
+            empty_request(Method::GET, "/api/v1/supplies/toners/count"),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(count, json!(2));
+
+        let (status, count) = send(
+            &app,
+            empty_request(Method::GET, "/api/v1/supplies/toners/count?with_stock=true"),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(count, json!({ "count": 2, "total_stock": 8 }));
+    }
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn quick_envelope_listings_count_the_toners_once(pool: PgPool) {
+        let app = app(pool.clone());
+        create_toner(&app, "TN-1060", 0).await;
+        env::set_var("COUNT_CACHE_TTL_MS", "60000");
+
+        let (_, first) = send(
+            &app,
+            empty_request(Method::GET, "/api/v1/supplies/toners?envelope=true"),
+        )
+        .await;
+        // Inserted behind the API's back, so only a new COUNT query would see it
+        sqlx::query("INSERT INTO toners (id, name) VALUES (gen_random_uuid(), 'TN-2370');")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let (_, second) = send(
+            &app,
+            empty_request(Method::GET, "/api/v1/supplies/toners?envelope=true"),
+        )
+        .await;
+        env::set_var("COUNT_CACHE_TTL_MS", "0");
+
+        assert_eq!(first["page"]["total"], 1);
+        assert_eq!(second["data"].as_array().unwrap().len(), 2);
+        assert_eq!(second["page"]["total"], 1);
+    }
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn empty_bodies_are_reported_as_such(pool: PgPool) {
+        let app = app(pool);
+        let request = axum::http::Request::builder()
+            .method(Method::POST)
+            .uri("/api/v1/supplies/toners")
+            .header(axum::http::header::CONTENT_TYPE, "application/json")
+            .header(axum::http::header::CONTENT_LENGTH, 0)
+            .body(axum::body::Body::empty())
+            .unwrap();
+
+        let (status, body) = send(&app, request).await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["code"], "EMPTY_BODY");
+    }
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn prefix_suggestions_rank_ahead_of_mid_string_ones(pool: PgPool) {
+        let app = app(pool);
+        let mid_string = create_toner(&app, "Brother TN-1060", 0).await;
+        let prefix = create_toner(&app, "TN-2370", 0).await;
+        create_toner(&app, "DK-1150", 0).await;
+
+        let (status, suggestions) = send(
+            &app,
+            empty_request(Method::GET, "/api/v1/supplies/toners/autocomplete?q=tn"),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(
+            suggestions,
+            json!([
+                { "id": prefix, "name": "TN-2370" },
+                { "id": mid_string, "name": "Brother TN-1060" },
+            ])
+        );
+
+        let (_, suggestions) = send(
+            &app,
+            empty_request(Method::GET, "/api/v1/supplies/toners/autocomplete?q="),
+        )
+        .await;
+        assert_eq!(suggestions, json!([]));
+    }
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn an_initial_stock_is_recorded_as_an_opening_movement(pool: PgPool) {
+        let app = app(pool);
+        let toner = create_toner(&app, "TN-1060", 20).await;
+
+        assert_eq!(stock_of(&app, toner).await, 20);
+        let (status, movements) = send(&app, empty_request(Method::GET, "/api/v1/movements")).await;
+        assert_eq!(status, StatusCode::OK);
+        let movements = movements.as_array().unwrap();
+        assert_eq!(movements.len(), 1);
+        assert_eq!(movements[0]["item"]["id"], json!(toner));
+        assert_eq!(movements[0]["printer"], json!(null));
+        assert_eq!(movements[0]["quantity"], 20);
+        assert_eq!(movements[0]["kind"], "opening");
+    }
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn names_are_unique_within_a_brand_when_scoped(pool: PgPool) {
+        let app = app(pool);
+        let brother = create_brand(&app, "Brother").await;
+        let kyocera = create_brand(&app, "Kyocera").await;
+
+        for collection in ["/api/v1/supplies/toners", "/api/v1/supplies/drums"] {
+            let create = |brand_id| {
+                json_request(
+                    Method::POST,
+                    collection,
+                    json!({ "name": "Black", "brand_id": brand_id }),
+                )
+            };
+
+            env::set_var("UNIQUE_NAMES_PER_BRAND", "true");
+            let (first, _) = send(&app, create(brother)).await;
+            let (other_brand, _) = send(&app, create(kyocera)).await;
+            let (same_brand, _) = send(&app, create(brother)).await;
+            env::set_var("UNIQUE_NAMES_PER_BRAND", "false");
+
+            assert_eq!(first, StatusCode::CREATED, "{collection}");
+            assert_eq!(other_brand, StatusCode::CREATED, "{collection}");
+            assert_eq!(same_brand, StatusCode::CONFLICT, "{collection}");
+        }
+    }
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn enveloped_listings_report_the_page_and_total(pool: PgPool) {
+        let app = app(pool);
+        create_toner(&app, "TN-1060", 0).await;
+        let second = create_toner(&app, "TN-2370", 0).await;
+        create_toner(&app, "TN-3480", 0).await;
+
+        let (status, page) = send(
+            &app,
+            empty_request(
+                Method::GET,
+                "/api/v1/supplies/toners?envelope=true&limit=1&offset=1",
+            ),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(page["page"], json!({ "limit": 1, "offset": 1, "total": 3 }));
+        let data = page["data"].as_array().unwrap();
+        assert_eq!(data.len(), 1);
+        assert_eq!(data[0]["id"], json!(second));
+
+        let (_, toners) = send(&app, empty_request(Method::GET, "/api/v1/supplies/toners")).await;
+        assert_eq!(toners.as_array().unwrap().len(), 3);
+    }
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn toners_are_filtered_by_the_presence_of_a_price(pool: PgPool) {
+        let app = app(pool);
+        let unpriced = create_toner(&app, "TN-1060", 0).await;
+        let (status, priced) = send(
+            &app,
+            json_request(
+                Method::POST,
+                "/api/v1/supplies/toners",
+                json!({ "name": "TN-2370", "price": 30.0 }),
+            ),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+
+        for (has_price, expected) in [("false", json!(unpriced)), ("true", priced)] {
+            let (status, toners) = send(
+                &app,
+                empty_request(
+                    Method::GET,
+                    &format!("/api/v1/supplies/toners?has_price={has_price}"),
+                ),
+            )
+            .await;
+
+            assert_eq!(status, StatusCode::OK);
+            let toners = toners.as_array().unwrap();
+            assert_eq!(toners.len(), 1, "has_price={has_price}");
+            assert_eq!(toners[0]["id"], expected, "has_price={has_price}");
+        }
+    }
+}
@@ -1,10 +1,15 @@
 mod errors;
+mod extractors;
 mod handlers;
+mod middlewares;
 mod models;
 mod openapi;
 mod routes;
 mod server;
+#[cfg(test)]
+mod test_utils;
 mod validations;
+mod webhooks;
 
 #[tokio::main]
 async fn main() {
@@ -15,7 +20,7 @@ async fn main() {
             tracing::info!("✅ Configurations loaded!");
         }
         Err(e) => {
-            tracing::error!("❌ Error loading configurations: {:?}", e);
+            tracing::error!("❌ Error loading configurations: {e}");
             std::process::exit(1);
         }
     }
@@ -0,0 +1,244 @@
+use axum::{
+    body::{to_bytes, Body, Bytes},
+    extract::{OriginalUri, Request},
+    http::{header, HeaderMap, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use config::Config;
+use serde_json::Value;
+use std::{
+    collections::HashMap,
+    hash::{DefaultHasher, Hash, Hasher},
+    sync::{Arc, Mutex, OnceLock},
+    time::Duration,
+};
+use tokio::sync::OnceCell;
+use tracing::{error, info};
+
+/// Largest request body considered for deduplication. Bigger bodies are always served.
+const MAX_DEDUP_BODY_BYTES: u64 = 64 * 1024;
+
+/// Headers that change what a request does or how it is answered, so they are part of its identity.
+const KEY_HEADERS: [header::HeaderName; 3] = [
+    header::AUTHORIZATION,
+    header::CONTENT_TYPE,
+    header::HeaderName::from_static("prefer"),
+];
+
+/// Route of a request, and hash of its body and `KEY_HEADERS`.
+type DedupKey = (String, u64);
+
+/// A response kept in memory, so every collapsed request can answer with it.
+#[derive(Clone)]
+struct BufferedResponse {
+    status: StatusCode,
+    headers: HeaderMap,
+    body: Bytes,
+}
+
+fn in_flight() -> &'static Mutex<HashMap<DedupKey, Arc<OnceCell<BufferedResponse>>>> {
+    static IN_FLIGHT: OnceLock<Mutex<HashMap<DedupKey, Arc<OnceCell<BufferedResponse>>>>> =
+        OnceLock::new();
+    IN_FLIGHT.get_or_init(Default::default)
+}
+
+/// Collapses identical `POST` requests arriving within `DEDUP_WINDOW_MS` into a single execution.
+///
+/// Requests are identical when they target the same route with the same body, ignoring
+/// whitespace around JSON strings, and the same `Authorization`, `Content-Type` and `Prefer`
+/// headers, so a double-clicked create only creates one row.
+/// Every request of the window answers with the response of the first one.
+///
+/// Only meant for the catalog create routes: repeated movements or reservations are real.
+pub async fn dedup(request: Request, next: Next) -> Response {
+    match Config::dedup_window() {
+        Some(window) => collapse(window, request, next).await,
+        None => next.run(request).await,
+    }
+}
+
+async fn collapse(window: Duration, request: Request, next: Next) -> Response {
+    let small_body = request
+        .headers()
+        .get(header::CONTENT_LENGTH)
+        .and_then(|length| length.to_str().ok())
+        .and_then(|length| length.parse::<u64>().ok())
+        .is_some_and(|length| length <= MAX_DEDUP_BODY_BYTES);
+
+    if request.method() != Method::POST || !small_body {
+        return next.run(request).await;
+    }
+
+    let (parts, body) = request.into_parts();
+
+    let bytes = match to_bytes(body, MAX_DEDUP_BODY_BYTES as usize).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            error!("Error reading request body: {e}");
+            return StatusCode::BAD_REQUEST.into_response();
+        }
+    };
+
+    // Nested routers see the path relative to their prefix, so the full one is used
+    let route = parts
+        .extensions
+        .get::<OriginalUri>()
+        .map_or_else(|| parts.uri.to_string(), |uri| uri.0.to_string());
+    let key = (route, request_hash(&parts.headers, &bytes));
+
+    let cell = {
+        let mut requests = in_flight().lock().unwrap();
+        match requests.get(&key) {
+            Some(cell) => {
+                info!("Collapsing duplicate request to {}", parts.uri);
+                cell.clone()
+            }
+            None => {
+                let cell = Arc::new(OnceCell::new());
+                requests.insert(key.clone(), cell.clone());
+
+                tokio::spawn(async move {
+                    tokio::time::sleep(window).await;
+                    in_flight().lock().unwrap().remove(&key);
+                });
+
+                cell
+            }
+        }
+    };
+
+    // Only one request runs; the others wait for its response.
+    // Should it be cancelled, the next waiting request runs instead.
+    let request = Request::from_parts(parts, Body::from(bytes));
+    let buffered = cell
+        .get_or_init(|| async move { buffer(next.run(request).await).await })
+        .await
+        .clone();
+
+    let mut response = Response::new(Body::from(buffered.body));
+    *response.status_mut() = buffered.status;
+    *response.headers_mut() = buffered.headers;
+    response
+}
+
+/// Hashes the `KEY_HEADERS` and the body of a request, with JSON strings trimmed so `"HP "` and `"HP"` match.
+fn request_hash(headers: &HeaderMap, bytes: &Bytes) -> u64 {
+    let mut hasher = DefaultHasher::new();
+
+    for name in &KEY_HEADERS {
+        headers
+            .get_all(name)
+            .iter()
+            .for_each(|value| value.as_bytes().hash(&mut hasher));
+        // Separates the headers, so a value cannot pass for the next header's
+        0xff_u8.hash(&mut hasher);
+    }
+
+    match serde_json::from_slice::<Value>(bytes) {
+        Ok(mut value) => {
+            trim_strings(&mut value);
+            value.to_string().hash(&mut hasher);
+        }
+        Err(_) => bytes.hash(&mut hasher),
+    }
+
+    hasher.finish()
+}
+
+fn trim_strings(value: &mut Value) {
+    match value {
+        Value::String(string) => *string = string.trim().to_string(),
+        Value::Array(values) => values.iter_mut().for_each(trim_strings),
+        Value::Object(fields) => fields.values_mut().for_each(trim_strings),
+        _ => {}
+    }
+}
+
+async fn buffer(response: Response) -> BufferedResponse {
+    let (parts, body) = response.into_parts();
+
+    let body = to_bytes(body, usize::MAX).await.unwrap_or_else(|e| {
+        error!("Error reading response body: {e}");
+        Bytes::new()
+    });
+
+    BufferedResponse {
+        status: parts.status,
+        headers: parts.headers,
+        body,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::collapse;
+    use crate::test_utils::{json_request, send};
+    use axum::{
+        http::{Method, StatusCode},
+        middleware,
+        routing::post,
+        Router,
+    };
+    use serde_json::json;
+    use std::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        time::Duration,
+    };
+
+    /// A route that counts its executions, with the dedup layer on a one second window.
+    fn counting_app(path: &str, executions: Arc<AtomicUsize>) -> Router {
+        Router::new()
+            .route(
+                path,
+                post(move || async move {
+                    executions.fetch_add(1, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(50)).await;
+                    StatusCode::CREATED
+                }),
+            )
+            .layer(middleware::from_fn(|request, next| {
+                collapse(Duration::from_secs(1), request, next)
+            }))
+    }
+
+    #[tokio::test]
+    async fn identical_concurrent_creates_run_once() {
+        let executions = Arc::new(AtomicUsize::new(0));
+        let app = counting_app("/dedup-identical", executions.clone());
+
+        let first = send(
+            &app,
+            json_request(Method::POST, "/dedup-identical", json!({ "name": "HP" })),
+        );
+        let second = send(
+            &app,
+            json_request(Method::POST, "/dedup-identical", json!({ "name": "HP " })),
+        );
+        let ((first_status, _), (second_status, _)) = tokio::join!(first, second);
+
+        assert_eq!(first_status, StatusCode::CREATED);
+        assert_eq!(second_status, StatusCode::CREATED);
+        assert_eq!(executions.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn requests_with_different_preferences_both_run() {
+        let executions = Arc::new(AtomicUsize::new(0));
+        let app = counting_app("/dedup-prefer", executions.clone());
+
+        let minimal = json_request(Method::POST, "/dedup-prefer", json!({ "name": "HP" }));
+        let mut representation =
+            json_request(Method::POST, "/dedup-prefer", json!({ "name": "HP" }));
+        representation
+            .headers_mut()
+            .insert("prefer", "return=representation".parse().unwrap());
+
+        tokio::join!(send(&app, minimal), send(&app, representation));
+
+        assert_eq!(executions.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,89 @@
+use axum::{
+    body::{to_bytes, Body},
+    extract::Request,
+    http::{header, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use config::Config;
+use serde_json::Value;
+use tracing::error;
+
+/// Renames the fields of JSON responses to camelCase when `JSON_CASE=camel`.
+///
+/// Request bodies are still read in snake_case.
+pub async fn json_case(request: Request, next: Next) -> Response {
+    let response = next.run(request).await;
+
+    let is_json = response.headers().get(header::CONTENT_TYPE)
+        == Some(&HeaderValue::from_static("application/json"));
+
+    if !Config::camel_case_json() || !is_json {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+
+    let bytes = match to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            error!("Error reading response body: {e}");
+            return Response::from_parts(parts, Body::empty());
+        }
+    };
+
+    let body = match serde_json::from_slice::<Value>(&bytes)
+        .and_then(|value| serde_json::to_vec(&camel_case_keys(value)))
+    {
+        Ok(camel_bytes) => Body::from(camel_bytes),
+        Err(_) => Body::from(bytes),
+    };
+
+    parts.headers.remove(header::CONTENT_LENGTH);
+    Response::from_parts(parts, body)
+}
+
+fn camel_case_keys(value: Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .map(|(key, value)| (to_camel_case(&key), camel_case_keys(value)))
+                .collect(),
+        ),
+        Value::Array(values) => Value::Array(values.into_iter().map(camel_case_keys).collect()),
+        value => value,
+    }
+}
+
+/// `printer_id` becomes `printerId`; keys without underscores, like ids used as keys, are kept.
+fn to_camel_case(key: &str) -> String {
+    let mut camel = String::with_capacity(key.len());
+    let mut upper = false;
+    for c in key.chars() {
+        if c == '_' && !camel.is_empty() {
+            upper = true;
+        } else if upper {
+            camel.extend(c.to_uppercase());
+            upper = false;
+        } else {
+            camel.push(c);
+        }
+    }
+    camel
+}
+
+#[cfg(test)]
+mod tests {
+    use super::camel_case_keys;
+    use serde_json::json;
+
+    #[test]
+    fn renamed_fields_keep_their_order() {
+        let value = json!({ "updated_at": 1, "item": { "printer_id": 2, "id": 3 } });
+
+        assert_eq!(
+            camel_case_keys(value).to_string(),
+            r#"{"updatedAt":1,"item":{"printerId":2,"id":3}}"#
+        );
+    }
+}
@@ -0,0 +1,5 @@
+pub mod dedup;
+pub mod json_case;
+pub mod preflight;
+pub mod pretty_json;
+pub mod read_only;
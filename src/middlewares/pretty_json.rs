@@ -0,0 +1,95 @@
+use axum::{
+    body::{to_bytes, Body},
+    extract::Request,
+    http::{header, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use config::Config;
+use serde_json::Value;
+use tracing::error;
+
+/// Indents JSON responses when `PRETTY_JSON` is enabled or the request has `?pretty=true`.
+///
+/// Only the formatting changes: the response is parsed and serialized again, keeping the order of its fields.
+pub async fn pretty_json(request: Request, next: Next) -> Response {
+    let pretty = Config::pretty_json()
+        || request
+            .uri()
+            .query()
+            .is_some_and(|query| query.split('&').any(|param| param == "pretty=true"));
+
+    let response = next.run(request).await;
+
+    let is_json = response.headers().get(header::CONTENT_TYPE)
+        == Some(&HeaderValue::from_static("application/json"));
+
+    if !pretty || !is_json {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+
+    let bytes = match to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            error!("Error reading response body: {e}");
+            return Response::from_parts(parts, Body::empty());
+        }
+    };
+
+    let body = match serde_json::from_slice::<Value>(&bytes)
+        .and_then(|value| serde_json::to_vec_pretty(&value))
+    {
+        Ok(pretty_bytes) => Body::from(pretty_bytes),
+        Err(_) => Body::from(bytes),
+    };
+
+    parts.headers.remove(header::CONTENT_LENGTH);
+    Response::from_parts(parts, body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::pretty_json;
+    use crate::test_utils::empty_request;
+    use axum::{
+        body::to_bytes, http::Method, middleware, response::IntoResponse, routing::get, Json,
+        Router,
+    };
+    use serde::Serialize;
+    use tower::ServiceExt;
+
+    #[derive(Serialize)]
+    struct Report {
+        zone: &'static str,
+        amount: i64,
+    }
+
+    #[tokio::test]
+    async fn pretty_responses_keep_the_field_order() {
+        let app = Router::new()
+            .route(
+                "/report",
+                get(|| async {
+                    Json(Report {
+                        zone: "north",
+                        amount: 3,
+                    })
+                    .into_response()
+                }),
+            )
+            .layer(middleware::from_fn(pretty_json));
+
+        let response = app
+            .oneshot(empty_request(Method::GET, "/report?pretty=true"))
+            .await
+            .unwrap();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+
+        assert_eq!(
+            std::str::from_utf8(&body).unwrap(),
+            "{\n  \"zone\": \"north\",\n  \"amount\": 3\n}"
+        );
+    }
+}
@@ -0,0 +1,59 @@
+use crate::errors::api_error::ApiError;
+use axum::{
+    extract::Request,
+    http::Method,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use config::Config;
+use tracing::warn;
+
+/// Rejects write requests while `READ_ONLY` is enabled.
+///
+/// `GET`, `HEAD` and `OPTIONS` requests are always served.
+pub async fn read_only(request: Request, next: Next) -> Response {
+    let is_write = !matches!(
+        *request.method(),
+        Method::GET | Method::HEAD | Method::OPTIONS
+    );
+
+    if is_write && Config::read_only() {
+        warn!(
+            "Rejected {} {} in read-only mode",
+            request.method(),
+            request.uri()
+        );
+        return ApiError::ReadOnly.into_response();
+    }
+
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::read_only;
+    use crate::test_utils::{empty_request, send};
+    use axum::{
+        http::{Method, StatusCode},
+        middleware,
+        routing::get,
+        Router,
+    };
+    use std::env;
+
+    #[tokio::test]
+    async fn only_reads_are_served_in_read_only_mode() {
+        let app = Router::new()
+            .route("/", get(|| async { "read" }).post(|| async { "written" }))
+            .layer(middleware::from_fn(read_only));
+
+        env::set_var("READ_ONLY", "true");
+        let (read_status, _) = send(&app, empty_request(Method::GET, "/")).await;
+        let (write_status, write_body) = send(&app, empty_request(Method::POST, "/")).await;
+        env::set_var("READ_ONLY", "false");
+
+        assert_eq!(read_status, StatusCode::OK);
+        assert_eq!(write_status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(write_body["code"], "READ_ONLY");
+    }
+}
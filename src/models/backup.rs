@@ -0,0 +1,57 @@
+use super::{
+    brand::Brand,
+    movement::Movement,
+    printer::Printer,
+    supplies::{drum::Drum, toner::Toner},
+};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+/// Every brand, supply, printer and movement, as exported for a backup.
+#[derive(Deserialize, Serialize, ToSchema)]
+pub struct Catalog {
+    pub brands: Vec<Brand>,
+    pub toners: Vec<Toner>,
+    pub drums: Vec<Drum>,
+    pub printers: Vec<Printer>,
+    pub movements: Vec<Movement>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct CatalogCounts {
+    pub brands: usize,
+    pub toners: usize,
+    pub drums: usize,
+    pub printers: usize,
+    pub movements: usize,
+}
+
+impl From<&Catalog> for CatalogCounts {
+    fn from(catalog: &Catalog) -> Self {
+        CatalogCounts {
+            brands: catalog.brands.len(),
+            toners: catalog.toners.len(),
+            drums: catalog.drums.len(),
+            printers: catalog.printers.len(),
+            movements: catalog.movements.len(),
+        }
+    }
+}
+
+/// How an import treats the data already stored.
+#[derive(Clone, Copy, Debug, Default, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportMode {
+    /// Deletes all existing data before importing.
+    Replace,
+    /// Inserts new rows and overwrites the rows whose ID already exists.
+    #[default]
+    Upsert,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ImportParams {
+    /// `replace` to wipe the existing data first, or `upsert` (the default) to merge into it.
+    pub mode: Option<ImportMode>,
+}
@@ -1,6 +1,8 @@
+use crate::validations::name::is_valid_brand_name;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::prelude::FromRow;
-use utoipa::ToSchema;
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 use validator::Validate;
 
@@ -8,6 +10,7 @@ use validator::Validate;
 pub struct Brand {
     pub id: Uuid,
     pub name: String,
+    pub updated_at: DateTime<Utc>,
 }
 
 impl Brand {
@@ -15,19 +18,72 @@ impl Brand {
         Brand {
             id: Uuid::new_v4(),
             name: String::from(name),
+            updated_at: Utc::now(),
         }
     }
 }
 
+#[derive(Serialize, FromRow, ToSchema)]
+pub struct BrandWithCount {
+    pub id: Uuid,
+    pub name: String,
+    pub updated_at: DateTime<Utc>,
+    pub printer_count: i64,
+}
+
+#[derive(Deserialize, Serialize, ToSchema, Validate)]
+pub struct AssignPrintersRequest {
+    /// Printers to assign to the brand.
+    #[validate(length(min = 1, message = "At least one printer must be provided"))]
+    pub printer_ids: Vec<Uuid>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct BrandImportSummary {
+    /// Brands created by the import.
+    pub created: Vec<Brand>,
+    /// Names that were skipped because the brand already exists or is repeated.
+    pub skipped: Vec<String>,
+    /// Outcome of each non-blank line of the body.
+    pub lines: Vec<BrandImportLine>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct BrandImportLine {
+    /// Line number in the body, starting at 1.
+    pub line: usize,
+    pub name: String,
+    pub outcome: BrandImportOutcome,
+}
+
+/// What the import did with a line.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BrandImportOutcome {
+    /// The brand was created.
+    Created,
+    /// The name appears on an earlier line of the same body.
+    Repeated,
+    /// A brand with this name already exists, or was created concurrently.
+    Exists,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct BrandListParams {
+    /// Includes the number of printers of each brand.
+    pub with_counts: Option<bool>,
+}
+
 #[derive(Deserialize, Serialize, ToSchema, Validate)]
 pub struct CreateBrandRequest {
-    #[validate(length(min = 3, message = "Name must be greater than 3 chars"))]
+    #[validate(custom(function = "is_valid_brand_name"))]
     pub name: String,
 }
 
 #[derive(Deserialize, Serialize, ToSchema, Validate)]
 pub struct UpdateBrandRequest {
     pub id: Uuid,
-    #[validate(length(min = 3, message = "Name must be greater than 3 chars"))]
+    #[validate(custom(function = "is_valid_brand_name"))]
     pub name: String,
 }
@@ -0,0 +1,10 @@
+use serde::Serialize;
+use utoipa::ToSchema;
+
+#[derive(Serialize, ToSchema)]
+pub struct MigrationReport {
+    /// Versions of the migrations applied by this run, in order.
+    pub applied: Vec<i64>,
+    /// Whether the database had no pending migrations.
+    pub already_up_to_date: bool,
+}
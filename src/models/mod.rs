@@ -1,14 +1,57 @@
 use serde::{Deserialize, Serialize};
-use utoipa::ToSchema;
+use sqlx::prelude::FromRow;
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
+use validator::Validate;
 
+pub mod backup;
 pub mod brand;
+pub mod migration;
 pub mod movement;
+pub mod pagination;
 pub mod printer;
+pub mod report;
+pub mod search;
 pub mod status;
 pub mod supplies;
 
+/// Minimum length of the names of toners, drums, brands and printers.
+///
+/// Two-character names such as "HP" are rejected, so every name is validated against this value.
+pub const MIN_NAME_LENGTH: u64 = 3;
+
+/// Most IDs a single existence check accepts.
+pub const MAX_EXISTS_IDS: u64 = 1000;
+
+#[derive(Deserialize, Serialize, ToSchema, Validate)]
+pub struct ExistsRequest {
+    #[validate(length(max = MAX_EXISTS_IDS, message = "At most 1000 IDs can be checked at once"))]
+    pub ids: Vec<Uuid>,
+}
+
 #[derive(Deserialize, Serialize, ToSchema)]
 pub struct DeleteRequest {
     pub id: Uuid,
 }
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ReturnParams {
+    /// `representation` returns the created resource instead of its ID, like `Prefer: return=representation`.
+    #[serde(rename = "return")]
+    pub preference: Option<String>,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct CompactParams {
+    /// Lists only the `id` and `name` of each item, as for dropdowns.
+    pub compact: Option<bool>,
+}
+
+/// An item listed with `compact=true`.
+#[derive(Serialize, FromRow, ToSchema)]
+pub struct CompactItem {
+    pub id: Uuid,
+    pub name: String,
+}
@@ -1,50 +1,156 @@
-use crate::validations::uuid::is_uuid;
-use chrono::{DateTime, Utc};
+use crate::validations::{
+    quantity::{is_movement_quantity, is_non_zero},
+    timestamp::is_not_in_future,
+    timezone::is_utc_offset,
+    uuid::is_uuid,
+};
+use chrono::{DateTime, FixedOffset, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::prelude::FromRow;
-use utoipa::ToSchema;
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 use validator::Validate;
 
-#[derive(Deserialize, Serialize, FromRow)]
+/// Whether a movement was a regular stock change made on behalf of a printer,
+/// an adjustment recorded after setting the stock to an absolute count,
+/// or the opening balance of an item created with stock.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize, sqlx::Type, ToSchema)]
+#[sqlx(type_name = "movement_kind", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum MovementKind {
+    Regular,
+    Adjustment,
+    Opening,
+}
+
+/// Kind of supply a movement refers to, which tells where its item is stored.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize, sqlx::Type, ToSchema)]
+#[sqlx(type_name = "item_type", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ItemType {
+    Toner,
+    Drum,
+}
+
+#[derive(Deserialize, Serialize, FromRow, ToSchema)]
 pub struct Movement {
     pub id: Uuid,
-    pub printer_id: Uuid,
+    pub printer_id: Option<Uuid>,
     pub item_id: Uuid,
-    pub quantity: i32,
+    pub quantity: i64,
+    pub kind: MovementKind,
     pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
 }
 
 impl Movement {
-    pub fn new(printer_id: Uuid, item_id: Uuid, quantity: i32) -> Self {
+    /// Creates a regular movement, made at `created_at` or, when `None`, now.
+    pub fn new(
+        printer_id: Uuid,
+        item_id: Uuid,
+        quantity: i64,
+        created_at: Option<DateTime<Utc>>,
+    ) -> Self {
+        let now = Utc::now();
+
+        Self {
+            id: Uuid::new_v4(),
+            printer_id: Some(printer_id),
+            item_id,
+            quantity,
+            kind: MovementKind::Regular,
+            created_at: created_at.unwrap_or(now),
+            updated_at: now,
+        }
+    }
+
+    /// Creates an adjustment movement, which is not tied to any printer.
+    pub fn adjustment(item_id: Uuid, quantity: i64) -> Self {
+        let now = Utc::now();
+
         Self {
             id: Uuid::new_v4(),
-            printer_id,
+            printer_id: None,
             item_id,
             quantity,
-            created_at: Utc::now(),
+            kind: MovementKind::Adjustment,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// Creates the opening balance movement of a new item, which is not tied to any printer.
+    pub fn opening(item_id: Uuid, quantity: i64) -> Self {
+        Self {
+            kind: MovementKind::Opening,
+            ..Self::adjustment(item_id, quantity)
         }
     }
 }
 
 pub type MovementView = (
-    Uuid,          // movement_id
-    Uuid,          // printer_id
-    String,        // printer_name
-    String,        // printer_model
-    Uuid,          // item_id
-    String,        // item_name
-    i32,           // quantity
-    DateTime<Utc>, // created_at
+    Uuid,           // movement_id
+    Option<Uuid>,   // printer_id
+    Option<String>, // printer_name
+    Option<String>, // printer_model
+    Uuid,           // item_id
+    String,         // item_name
+    i64,            // quantity
+    MovementKind,   // kind
+    DateTime<Utc>,  // created_at
+    DateTime<Utc>,  // updated_at
 );
 
 #[derive(Serialize, ToSchema)]
 pub struct MovementDetails {
     pub id: Uuid,
-    pub printer: PrinterDetails,
+    pub printer: Option<PrinterDetails>,
     pub item: ItemDetails,
-    pub quantity: i32,
-    pub created_at: DateTime<Utc>,
+    pub quantity: i64,
+    pub kind: MovementKind,
+    pub created_at: DateTime<FixedOffset>,
+    pub updated_at: DateTime<FixedOffset>,
+}
+
+impl MovementDetails {
+    /// Converts the timestamps to the given UTC offset for display.
+    pub fn with_offset(self, offset: FixedOffset) -> Self {
+        Self {
+            created_at: self.created_at.with_timezone(&offset),
+            updated_at: self.updated_at.with_timezone(&offset),
+            ..self
+        }
+    }
+}
+
+impl From<MovementView> for MovementDetails {
+    fn from(row: MovementView) -> Self {
+        let printer = match (row.1, row.2, row.3) {
+            (Some(id), Some(name), Some(model)) => Some(PrinterDetails { id, name, model }),
+            _ => None,
+        };
+
+        Self {
+            id: row.0,
+            printer,
+            item: ItemDetails {
+                id: row.4,
+                name: row.5,
+            },
+            quantity: row.6,
+            kind: row.7,
+            created_at: row.8.fixed_offset(),
+            updated_at: row.9.fixed_offset(),
+        }
+    }
+}
+
+/// Response of a created movement, with the item's resulting stock.
+#[derive(Serialize, ToSchema)]
+pub struct CreatedMovement {
+    pub id: Uuid,
+    pub item_id: Uuid,
+    pub new_stock: i64,
 }
 
 #[derive(Serialize, ToSchema)]
@@ -60,13 +166,45 @@ pub struct PrinterDetails {
     pub model: String,
 }
 
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct MovementCountParams {
+    /// Only counts movements of toners or of drums. All movements are counted when omitted.
+    pub item_type: Option<ItemType>,
+}
+
+#[derive(Deserialize, IntoParams, Validate)]
+#[into_params(parameter_in = Query)]
+pub struct MovementFilterParams {
+    /// Only includes movements created at or after this time.
+    pub from: Option<DateTime<Utc>>,
+    /// Only includes movements created at or before this time.
+    pub to: Option<DateTime<Utc>>,
+    /// Only includes movements of this printer.
+    pub printer_id: Option<Uuid>,
+    /// UTC offset the timestamps are displayed in, such as `-03:00`. Defaults to UTC.
+    #[validate(custom(function = "is_utc_offset"))]
+    pub tz: Option<String>,
+}
+
 #[derive(Deserialize, Serialize, FromRow, ToSchema, Validate)]
 pub struct CreateMovementRequest {
     #[validate(custom(function = "is_uuid"))]
     pub printer_id: String,
     #[validate(custom(function = "is_uuid"))]
     pub item_id: String,
-    pub quantity: i32,
+    /// Whether `item_id` refers to a toner or a drum.
+    pub item_type: ItemType,
+    /// Units moved: positive values add to the item's stock (restock),
+    /// negative values remove from it (consumption).
+    #[validate(
+        custom(function = "is_movement_quantity"),
+        custom(function = "is_non_zero")
+    )]
+    pub quantity: i64,
+    /// When the movement happened, for recording past movements. Defaults to now.
+    #[validate(custom(function = "is_not_in_future"))]
+    pub created_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Deserialize, Serialize, FromRow, ToSchema, Validate)]
@@ -77,5 +215,31 @@ pub struct UpdateMovementRequest {
     pub printer_id: Option<String>,
     #[validate(custom(function = "is_uuid"))]
     pub item_id: Option<String>,
-    pub quantity: Option<i32>,
+    /// Whether `item_id` refers to a toner or a drum. Defaults to the type of the movement's current item.
+    pub item_type: Option<ItemType>,
+    /// Units moved: positive values add to the item's stock (restock),
+    /// negative values remove from it (consumption).
+    #[validate(
+        custom(function = "is_movement_quantity"),
+        custom(function = "is_non_zero")
+    )]
+    pub quantity: Option<i64>,
+    /// Last known modification time of the movement. The update is rejected
+    /// if the movement has been modified since.
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PurgeParams {
+    /// Movements created before this day (UTC) are purged.
+    pub before: NaiveDate,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct PurgeResult {
+    /// Number of movements deleted.
+    pub deleted: u64,
+    /// File the deleted movements were archived to, when `ARCHIVE_DIR` is set and any was deleted.
+    pub archive: Option<String>,
 }
@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
+
+#[derive(Deserialize, IntoParams, Validate)]
+#[into_params(parameter_in = Query)]
+pub struct ListParams {
+    /// Maximum number of items to return. All items are returned when omitted.
+    #[validate(range(min = 1, message = "Limit must be greater than 0"))]
+    pub limit: Option<i64>,
+    /// Number of items to skip.
+    #[validate(range(min = 0, message = "Offset must be greater or equal than 0"))]
+    pub offset: Option<i64>,
+    /// Wraps the items in a `{ data, page }` envelope instead of returning a bare array.
+    pub envelope: Option<bool>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct PageInfo {
+    /// Limit applied to the list, or `null` when all items were returned.
+    pub limit: Option<i64>,
+    pub offset: i64,
+    /// Number of items available, regardless of the limit and offset.
+    pub total: i64,
+}
+
+/// A page of a list, returned when `envelope=true` is requested.
+#[derive(Serialize, ToSchema)]
+pub struct Page<T> {
+    pub data: Vec<T>,
+    pub page: PageInfo,
+}
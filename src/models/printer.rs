@@ -2,7 +2,8 @@ use super::{
     brand::Brand,
     supplies::{drum::Drum, toner::Toner},
 };
-use crate::validations::uuid::is_uuid;
+use crate::validations::{model::is_valid_model, name::is_valid_printer_name, uuid::is_uuid};
+use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use sqlx::prelude::FromRow;
@@ -10,7 +11,7 @@ use utoipa::ToSchema;
 use uuid::Uuid;
 use validator::Validate;
 
-#[derive(Deserialize, Serialize, FromRow)]
+#[derive(Deserialize, Serialize, FromRow, ToSchema)]
 pub struct Printer {
     pub id: Uuid,
     pub name: String,
@@ -18,6 +19,7 @@ pub struct Printer {
     pub brand: Uuid,
     pub toner: Uuid,
     pub drum: Uuid,
+    pub updated_at: DateTime<Utc>,
 }
 
 impl Printer {
@@ -29,41 +31,146 @@ impl Printer {
             brand,
             toner,
             drum,
+            updated_at: Utc::now(),
         }
     }
 }
 
-pub type PrinterView = (
-    Uuid,            // printer_id
-    String,          // printer_name
-    String,          // printer_model
-    Uuid,            // brand_id
-    String,          // brand_name
-    Uuid,            // toner_id
-    String,          // toner_name
-    Option<i32>,     // toner_stock
-    Option<Decimal>, // toner_price
-    Uuid,            // drum_id
-    String,          // drum_name
-    Option<i32>,     // drum_stock
-    Option<Decimal>, // drum_price
-);
+/// A printer joined with its brand, toner and drum, which are `NULL` when the referenced row no longer exists.
+#[derive(FromRow)]
+pub struct PrinterView {
+    pub printer_id: Uuid,
+    pub printer_name: String,
+    pub printer_model: String,
+    pub brand_id: Option<Uuid>,
+    pub brand_name: Option<String>,
+    pub toner_id: Option<Uuid>,
+    pub toner_name: Option<String>,
+    pub toner_stock: Option<i64>,
+    pub toner_price: Option<Decimal>,
+    pub toner_brand_id: Option<Uuid>,
+    pub toner_currency: Option<String>,
+    pub drum_id: Option<Uuid>,
+    pub drum_name: Option<String>,
+    pub drum_stock: Option<i64>,
+    pub drum_price: Option<Decimal>,
+    pub drum_brand_id: Option<Uuid>,
+    pub drum_currency: Option<String>,
+    pub printer_updated_at: DateTime<Utc>,
+    pub brand_updated_at: Option<DateTime<Utc>>,
+}
 
+/// A printer with its related brand, toner and drum.
+///
+/// A related object is `null` when the printer references a row that no longer exists.
 #[derive(Serialize, ToSchema)]
 pub struct PrinterDetails {
     pub id: Uuid,
     pub name: String,
     pub model: String,
-    pub brand: Brand,
-    pub toner: Toner,
-    pub drum: Drum,
+    pub brand: Option<Brand>,
+    pub toner: Option<Toner>,
+    pub drum: Option<Drum>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<PrinterView> for PrinterDetails {
+    fn from(row: PrinterView) -> Self {
+        let brand = match (row.brand_id, row.brand_name, row.brand_updated_at) {
+            (Some(id), Some(name), Some(updated_at)) => Some(Brand {
+                id,
+                name,
+                updated_at,
+            }),
+            _ => None,
+        };
+
+        let toner = match (row.toner_id, row.toner_name) {
+            (Some(id), Some(name)) => Some(Toner {
+                id,
+                name,
+                stock: row.toner_stock,
+                price: row.toner_price,
+                brand_id: row.toner_brand_id,
+                currency: row.toner_currency,
+            }),
+            _ => None,
+        };
+
+        let drum = match (row.drum_id, row.drum_name) {
+            (Some(id), Some(name)) => Some(Drum {
+                id,
+                name,
+                stock: row.drum_stock,
+                price: row.drum_price,
+                brand_id: row.drum_brand_id,
+                currency: row.drum_currency,
+            }),
+            _ => None,
+        };
+
+        Self {
+            id: row.printer_id,
+            name: row.printer_name,
+            model: row.printer_model,
+            brand,
+            toner,
+            drum,
+            updated_at: row.printer_updated_at,
+        }
+    }
+}
+
+#[derive(Serialize, FromRow, ToSchema)]
+pub struct CompatibleSupply {
+    /// Supply type: `toner` or `drum`.
+    #[serde(rename = "type")]
+    #[sqlx(rename = "type")]
+    pub kind: String,
+    pub id: Uuid,
+    pub name: String,
+}
+
+/// Net quantity of one item used by a printer, with its cost at the item's current price.
+#[derive(Serialize, FromRow, ToSchema)]
+pub struct ItemConsumption {
+    /// Type of the item: `toner` or `drum`.
+    #[serde(rename = "type")]
+    #[sqlx(rename = "type")]
+    pub kind: String,
+    pub item_id: Uuid,
+    pub name: String,
+    /// Quantity consumed, net of the quantities returned to stock.
+    pub quantity: i64,
+    /// `quantity * price`, or `null` when the item has no price.
+    #[serde(with = "rust_decimal::serde::float_option")]
+    pub cost: Option<Decimal>,
+    /// ISO 4217 code `cost` is expressed in.
+    pub currency: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct CurrencyAmount {
+    pub currency: String,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub amount: Decimal,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct PrinterConsumption {
+    pub printer_id: Uuid,
+    /// Sum of the quantities of all items.
+    pub total_quantity: i64,
+    /// Sum of the item costs, one entry per currency. Items without a price are left out.
+    pub estimated_cost: Vec<CurrencyAmount>,
+    pub items: Vec<ItemConsumption>,
 }
 
 #[derive(Deserialize, Serialize, ToSchema, Validate)]
 pub struct CreatePrinterRequest {
-    #[validate(length(min = 3, message = "Name must be greater than 3 chars"))]
+    #[validate(custom(function = "is_valid_printer_name"))]
     pub name: String,
-    #[validate(length(min = 3, message = "Model must be greater than 3 chars"))]
+    #[validate(custom(function = "is_valid_model"))]
     pub model: String,
     #[validate(custom(function = "is_uuid"))]
     pub brand: String,
@@ -76,9 +183,9 @@ pub struct CreatePrinterRequest {
 #[derive(Deserialize, Serialize, ToSchema, Validate)]
 pub struct UpdatePrinterRequest {
     pub id: Uuid,
-    #[validate(length(min = 3, message = "Name must be greater than 3 chars"))]
+    #[validate(custom(function = "is_valid_printer_name"))]
     pub name: Option<String>,
-    #[validate(length(min = 3, message = "Model must be greater than 3 chars"))]
+    #[validate(custom(function = "is_valid_model"))]
     pub model: Option<String>,
     #[validate(custom(function = "is_uuid"))]
     pub brand: Option<String>,
@@ -0,0 +1,75 @@
+use crate::validations::{date_range::is_valid_report_range, timezone::is_utc_offset};
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+use validator::Validate;
+
+/// Widest range of days a report can cover.
+pub const MAX_REPORT_DAYS: i64 = 366;
+
+#[derive(Serialize, FromRow, ToSchema)]
+pub struct MovementsByDay {
+    pub date: NaiveDate,
+    /// Sum of the quantities of the day's movements.
+    pub total_quantity: i64,
+    /// Number of movements of the day.
+    pub count: i64,
+}
+
+#[derive(Serialize, FromRow, ToSchema)]
+pub struct BrandReport {
+    pub brand_id: Uuid,
+    pub brand_name: String,
+    /// Number of printers of the brand.
+    pub printer_count: i64,
+    /// ISO 4217 code `stock_value` is expressed in.
+    pub currency: String,
+    /// Sum of `stock * price` of the toners and drums in `currency` used by the brand's printers.
+    /// Each supply is counted once, even when shared by several printers.
+    #[serde(with = "rust_decimal::serde::float")]
+    pub stock_value: Decimal,
+}
+
+#[derive(Serialize, FromRow, ToSchema)]
+pub struct BrokenReference {
+    /// Type of the row holding the reference: `printer` or `movement`.
+    #[serde(rename = "type")]
+    #[sqlx(rename = "type")]
+    pub kind: String,
+    pub id: Uuid,
+    /// Referenced entity that is missing: `brand`, `toner`, `drum`, `printer` or `item`.
+    pub reference: String,
+    pub missing_id: Uuid,
+}
+
+#[derive(Serialize, FromRow, ToSchema)]
+pub struct StockDiscrepancy {
+    /// Type of the item: `toner` or `drum`.
+    #[serde(rename = "type")]
+    #[sqlx(rename = "type")]
+    pub kind: String,
+    pub id: Uuid,
+    pub name: String,
+    /// Sum of the quantities of the item's movements.
+    pub expected: i64,
+    /// Stock currently stored for the item.
+    pub actual: i64,
+    /// `actual - expected`.
+    pub delta: i64,
+}
+
+#[derive(Deserialize, IntoParams, Validate)]
+#[into_params(parameter_in = Query)]
+#[validate(schema(function = "is_valid_report_range"))]
+pub struct ReportRangeParams {
+    /// First day of the report, inclusive.
+    pub from: NaiveDate,
+    /// Last day of the report, inclusive.
+    pub to: NaiveDate,
+    /// UTC offset whose local days the movements are grouped by, such as `-03:00`. Defaults to UTC.
+    #[validate(custom(function = "is_utc_offset"))]
+    pub tz: Option<String>,
+}
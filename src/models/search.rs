@@ -0,0 +1,35 @@
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+/// Results returned per entity type when no limit is given.
+pub const DEFAULT_SEARCH_LIMIT: i64 = 10;
+
+/// Upper bound for the per-type result limit.
+pub const MAX_SEARCH_LIMIT: i64 = 50;
+
+#[derive(Serialize, FromRow, ToSchema)]
+pub struct SearchResult {
+    /// Entity type: `brand`, `toner`, `drum` or `printer`.
+    #[serde(rename = "type")]
+    #[sqlx(rename = "type")]
+    pub kind: String,
+    pub id: Uuid,
+    pub name: String,
+}
+
+#[derive(Serialize, FromRow, ToSchema)]
+pub struct Suggestion {
+    pub id: Uuid,
+    pub name: String,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SearchParams {
+    /// Case-insensitive text matched against entity names.
+    pub q: Option<String>,
+    /// Maximum number of results per entity type.
+    pub limit: Option<i64>,
+}
@@ -13,11 +13,64 @@ pub struct Database {
 
 #[derive(Deserialize, Serialize, ToSchema)]
 pub struct Dependencies {
-    pub database: Database,
+    /// `null` when the database could not be reached.
+    pub database: Option<Database>,
+}
+
+#[derive(Deserialize, Serialize, ToSchema)]
+pub struct MigrationInfo {
+    pub up_to_date: bool,
+    /// Versions of the migrations not applied yet.
+    pub pending: Vec<i64>,
+}
+
+#[derive(Deserialize, Serialize, ToSchema)]
+pub struct DiskInfo {
+    pub log_directory: String,
+    pub available_bytes: u64,
+    pub min_free_bytes: u64,
+}
+
+/// Overall verdict of the status checks.
+///
+/// The API is `unhealthy` when the database is unreachable, and `degraded`
+/// when migrations are pending or could not be checked, or disk space is low.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthStatus {
+    Healthy,
+    Degraded,
+    Unhealthy,
+}
+
+impl HealthStatus {
+    pub fn from_checks(database_reachable: bool, migrations_applied: bool, disk_ok: bool) -> Self {
+        if !database_reachable {
+            HealthStatus::Unhealthy
+        } else if !migrations_applied || !disk_ok {
+            HealthStatus::Degraded
+        } else {
+            HealthStatus::Healthy
+        }
+    }
 }
 
 #[derive(Deserialize, Serialize, ToSchema)]
 pub struct Status {
+    pub status: HealthStatus,
     pub updated_at: DateTime<Utc>,
     pub dependencies: Dependencies,
+    /// `null` when the database is unreachable or the applied migrations could not be read.
+    pub migrations: Option<MigrationInfo>,
+    pub disk: Option<DiskInfo>,
+}
+
+#[derive(Deserialize, Serialize, ToSchema)]
+pub struct PoolStatus {
+    /// Connections currently open by the pool, idle or in use.
+    pub size: u32,
+    /// Open connections not in use.
+    pub idle: usize,
+    pub min_connections: u32,
+    pub max_connections: u32,
 }
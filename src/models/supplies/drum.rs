@@ -1,3 +1,4 @@
+use crate::validations::{currency::is_currency, name::is_valid_name, price::is_price};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use sqlx::prelude::FromRow;
@@ -9,9 +10,15 @@ use validator::Validate;
 pub struct Drum {
     pub id: Uuid,
     pub name: String,
-    pub stock: Option<i32>,
+    pub stock: Option<i64>,
     #[serde(with = "rust_decimal::serde::float_option")]
     pub price: Option<Decimal>,
+    /// Brand the drum belongs to, omitted when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub brand_id: Option<Uuid>,
+    /// ISO 4217 code of the price, omitted when the `DEFAULT_CURRENCY` applies.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub currency: Option<String>,
 }
 
 impl Default for Drum {
@@ -21,36 +28,56 @@ impl Default for Drum {
             name: String::from("Unknown"),
             stock: None,
             price: None,
+            brand_id: None,
+            currency: None,
         }
     }
 }
 
 impl Drum {
-    pub fn new(name: &str, stock: Option<i32>, price: Option<Decimal>) -> Self {
+    pub fn new(
+        name: &str,
+        stock: Option<i64>,
+        price: Option<Decimal>,
+        brand_id: Option<Uuid>,
+        currency: Option<String>,
+    ) -> Self {
         Drum {
             id: Uuid::new_v4(),
             name: String::from(name),
             stock,
             price,
+            brand_id,
+            currency,
         }
     }
 }
 
 #[derive(Deserialize, Serialize, ToSchema, Validate)]
 pub struct CreateDrumRequest {
-    #[validate(length(min = 3, message = "Name must be greater than 3 chars"))]
+    #[validate(custom(function = "is_valid_name"))]
     pub name: String,
     #[validate(range(min = 0, message = "Stock must be greater or equal than 0"))]
-    pub stock: Option<i32>,
+    pub stock: Option<i64>,
+    #[validate(custom(function = "is_price"))]
     pub price: Option<Decimal>,
+    pub brand_id: Option<Uuid>,
+    /// ISO 4217 code of the price, such as `EUR`. Defaults to the `DEFAULT_CURRENCY`.
+    #[validate(custom(function = "is_currency"))]
+    pub currency: Option<String>,
 }
 
 #[derive(Deserialize, Serialize, ToSchema, Validate)]
 pub struct UpdateDrumRequest {
     pub id: Uuid,
-    #[validate(length(min = 3, message = "Name must be greater than 3 chars"))]
+    #[validate(custom(function = "is_valid_name"))]
     pub name: Option<String>,
     #[validate(range(min = 0, message = "Stock must be greater or equal than 0"))]
-    pub stock: Option<i32>,
+    pub stock: Option<i64>,
+    #[validate(custom(function = "is_price"))]
     pub price: Option<Decimal>,
+    pub brand_id: Option<Uuid>,
+    /// ISO 4217 code of the price, such as `EUR`. Defaults to the `DEFAULT_CURRENCY`.
+    #[validate(custom(function = "is_currency"))]
+    pub currency: Option<String>,
 }
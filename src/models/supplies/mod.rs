@@ -1,2 +1,107 @@
+use crate::validations::uuid::is_uuid;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+use validator::Validate;
+
+/// Longest time a reservation can be held for, in seconds.
+pub const MAX_RESERVATION_TTL_SECONDS: i64 = 7 * 24 * 60 * 60;
+
 pub mod drum;
 pub mod toner;
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SupplyCountParams {
+    /// Answers with a `{ count, total_stock }` object instead of the bare count.
+    pub with_stock: Option<bool>,
+}
+
+#[derive(Serialize, FromRow, ToSchema)]
+pub struct SupplyCount {
+    pub count: i64,
+    /// Sum of the stock of the supplies, counting a missing stock as 0.
+    pub total_stock: i64,
+}
+
+/// Answer of the supply count endpoints, whose shape depends on `with_stock`.
+#[derive(Serialize, ToSchema)]
+#[serde(untagged)]
+pub enum SupplyCountResponse {
+    /// The bare number of supplies, returned unless `with_stock=true`.
+    Count(i64),
+    /// The number of supplies along with their total stock, returned with `with_stock=true`.
+    WithStock(SupplyCount),
+}
+
+#[derive(Deserialize, IntoParams, Validate)]
+#[into_params(parameter_in = Query)]
+pub struct SupplyMovementsParams {
+    /// Maximum number of movements to return, newest first. All movements are returned when omitted.
+    #[validate(range(min = 1, message = "Limit must be greater than 0"))]
+    pub limit: Option<i64>,
+}
+
+/// Filters for finding incompletely entered toners and drums.
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SupplyFilterParams {
+    /// Only includes supplies whose stock is set (`true`) or missing (`false`).
+    pub has_stock: Option<bool>,
+    /// Only includes supplies whose price is set (`true`) or missing (`false`).
+    pub has_price: Option<bool>,
+}
+
+#[derive(Deserialize, Serialize, ToSchema, Validate)]
+pub struct SetStockRequest {
+    /// Absolute stock of the item, as counted.
+    #[validate(range(min = 0, message = "Stock must be greater or equal than 0"))]
+    pub stock: i64,
+}
+
+#[derive(Deserialize, Serialize, ToSchema, Validate)]
+pub struct ReserveRequest {
+    #[validate(range(min = 1, message = "Quantity must be greater than 0"))]
+    pub quantity: i64,
+    /// Seconds the reservation is held before its quantity becomes available again.
+    #[validate(range(
+        min = 1,
+        max = MAX_RESERVATION_TTL_SECONDS,
+        message = "TTL must be between 1 second and 7 days"
+    ))]
+    pub ttl_seconds: i64,
+}
+
+#[derive(Deserialize, Serialize, ToSchema, Validate)]
+pub struct FulfilReservationRequest {
+    /// Printer the reserved units are withdrawn for.
+    #[validate(custom(function = "is_uuid"))]
+    pub printer_id: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct CreatedReservation {
+    pub id: Uuid,
+    pub item_id: Uuid,
+    pub quantity: i64,
+    pub expires_at: DateTime<Utc>,
+    /// Stock of the item still available after the reservation.
+    pub available: i64,
+}
+
+#[derive(Serialize, FromRow, ToSchema)]
+pub struct SupplyStock {
+    /// Type of the supply: `toner` or `drum`.
+    #[serde(rename = "type")]
+    #[sqlx(rename = "type")]
+    pub kind: String,
+    pub id: Uuid,
+    pub name: String,
+    pub stock: i64,
+    /// Quantity held by reservations that have not expired.
+    pub reserved: i64,
+    /// `stock - reserved`.
+    pub available: i64,
+}
@@ -1,12 +1,26 @@
-use crate::models::brand::Brand;
-use crate::models::movement::MovementDetails;
-use crate::models::printer::PrinterDetails;
-use crate::models::status::Status;
+use crate::errors::api_error::ErrorCode;
+use crate::models::backup::{Catalog, CatalogCounts, ImportMode};
+use crate::models::brand::{
+    Brand, BrandImportLine, BrandImportOutcome, BrandImportSummary, BrandWithCount,
+};
+use crate::models::migration::MigrationReport;
+use crate::models::movement::{
+    CreatedMovement, ItemType, MovementDetails, MovementKind, PurgeResult,
+};
+use crate::models::pagination::Page;
+use crate::models::printer::{
+    CompatibleSupply, CurrencyAmount, ItemConsumption, PrinterConsumption, PrinterDetails,
+};
+use crate::models::report::{BrandReport, BrokenReference, MovementsByDay, StockDiscrepancy};
+use crate::models::search::{SearchResult, Suggestion};
+use crate::models::status::{PoolStatus, Status};
 use crate::models::supplies::drum::Drum;
+use crate::models::supplies::{CreatedReservation, SupplyCount, SupplyCountResponse, SupplyStock};
+use crate::models::CompactItem;
 use crate::{
     handlers::{
-        brand, migrations, movement, printer, status,
-        supplies::{drum, toner},
+        backup, brand, migrations, movement, printer, reports, search, status,
+        supplies::{self, drum, toner},
     },
     models::supplies::toner::Toner,
 };
@@ -22,13 +36,17 @@ use crate::{
     paths(
         // Status
         status::show_status,
+        status::show_pool_status,
 
         // Migrations
         migrations::live_run,
 
         // Toner
         toner::count_toners,
+        toner::autocomplete_toners,
         toner::search_toner,
+        toner::check_toner,
+        toner::check_toners,
         toner::show_toners,
         toner::create_toner,
         toner::update_toner,
@@ -37,47 +55,88 @@ use crate::{
         // Drum
         drum::count_drums,
         drum::search_drum,
+        drum::check_drum,
+        drum::check_drums,
         drum::show_drums,
         drum::create_drum,
         drum::update_drum,
         drum::delete_drum,
 
+        // Supplies
+        supplies::show_supply,
+        supplies::show_supply_movements,
+        supplies::set_stock,
+        supplies::reserve_supply,
+        supplies::fulfil_reservation,
+        supplies::cancel_reservation,
+
         // Brands
         brand::count_brands,
         brand::search_brand,
+        brand::check_brand,
+        brand::check_brands,
         brand::show_brands,
         brand::create_brand,
+        brand::import_brands,
+        brand::assign_printers,
         brand::update_brand,
+        brand::upsert_brand,
         brand::delete_brand,
 
         // Printers
         printer::count_printers,
         printer::search_printer,
+        printer::check_printer,
+        printer::check_printers,
         printer::show_printers,
         printer::create_printer,
         printer::update_printer,
         printer::delete_printer,
+        printer::show_compatible_supplies,
+        printer::show_consumption,
+        printer::add_compatible_supply,
+        printer::remove_compatible_supply,
 
         // Movements
         movement::count_movements,
         movement::search_movement,
         movement::show_movements,
+        movement::export_movements,
         movement::create_movement,
+        movement::create_movement_batch,
         movement::update_movement,
         movement::delete_movement,
+        movement::purge_movements,
+
+        // Search
+        search::search,
+
+        // Reports
+        reports::movements_by_day,
+        reports::by_brand,
+        reports::integrity,
+        reports::balance,
+
+        // Backup
+        backup::export_catalog,
+        backup::import_catalog,
 
     ),
     components(
-        schemas(Status, Drum, Toner, Brand, PrinterDetails, MovementDetails)
+        schemas(CompactItem, Status, PoolStatus, ErrorCode, Catalog, CatalogCounts, ImportMode, MigrationReport, SupplyStock, SupplyCount, SupplyCountResponse, CreatedReservation, Drum, Toner, Page<Toner>, Brand, BrandWithCount, BrandImportSummary, BrandImportLine, BrandImportOutcome, PrinterDetails, CompatibleSupply, PrinterConsumption, ItemConsumption, CurrencyAmount, MovementDetails, MovementKind, ItemType, CreatedMovement, PurgeResult, SearchResult, Suggestion, MovementsByDay, BrandReport, BrokenReference, StockDiscrepancy)
     ),
     tags(
         (name = "Status", description = "Status endpoints"),
         (name = "Migrations", description = "Migrations endpoints"),
         (name = "Toners", description = "Toners endpoints"),
         (name = "Drums", description = "Drums endpoints"),
+        (name = "Supplies", description = "Supplies endpoints"),
         (name = "Brands", description = "Brands endpoints"),
         (name = "Printers", description = "Printers endpoints"),
         (name = "Movements", description = "Movements endpoints"),
+        (name = "Search", description = "Search endpoints"),
+        (name = "Reports", description = "Reports endpoints"),
+        (name = "Backup", description = "Backup endpoints"),
     )
 )]
 pub struct ApiDoc;
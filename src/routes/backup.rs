@@ -0,0 +1,21 @@
+use crate::handlers::backup;
+use axum::{
+    extract::DefaultBodyLimit,
+    routing::{get, post},
+    Router,
+};
+use infra::database::AppState;
+use std::sync::Arc;
+
+/// Largest catalog accepted by the import, in bytes.
+const IMPORT_BODY_LIMIT: usize = 64 * 1024 * 1024;
+
+pub fn create_routes(state: Arc<AppState>) -> Router {
+    Router::new()
+        .route("/export", get(backup::export_catalog))
+        .route(
+            "/import",
+            post(backup::import_catalog).layer(DefaultBodyLimit::max(IMPORT_BODY_LIMIT)),
+        )
+        .with_state(state)
+}
@@ -1,18 +1,33 @@
-use crate::handlers::brand;
-use axum::{routing::get, Router};
+use crate::{handlers::brand, middlewares::dedup::dedup, routes::allow};
+use axum::{
+    handler::Handler,
+    middleware,
+    routing::{get, post},
+    Router,
+};
 use infra::database::AppState;
 use std::sync::Arc;
 
 pub fn create_routes(state: Arc<AppState>) -> Router {
     Router::new()
         .route("/count", get(brand::count_brands))
-        .route("/:id", get(brand::search_brand))
+        .route("/exists", post(brand::check_brands))
+        .route("/import", post(brand::import_brands))
+        .route("/:id/assign-printers", post(brand::assign_printers))
+        .route(
+            "/:id",
+            get(brand::search_brand)
+                .head(brand::check_brand)
+                .put(brand::upsert_brand)
+                .options(|| allow("GET, HEAD, PUT, OPTIONS")),
+        )
         .route(
             "/",
             get(brand::show_brands)
-                .post(brand::create_brand)
+                .post(brand::create_brand.layer(middleware::from_fn(dedup)))
                 .put(brand::update_brand)
-                .delete(brand::delete_brand),
+                .delete(brand::delete_brand)
+                .options(|| allow("GET, HEAD, POST, PUT, DELETE, OPTIONS")),
         )
         .with_state(state)
 }
@@ -1,28 +1,241 @@
+pub mod backup;
 pub mod brands;
 pub mod migrations;
 pub mod movements;
 pub mod printers;
+pub mod reports;
+pub mod search;
 pub mod status;
 pub mod supplies;
 pub mod swagger;
 
-use axum::Router;
+use crate::middlewares::{
+    json_case::json_case, preflight::skip_cors_for_options, pretty_json::pretty_json,
+    read_only::read_only,
+};
+use axum::{
+    http::{header, HeaderName, StatusCode},
+    middleware,
+    response::IntoResponse,
+    Router,
+};
 use config::Config;
 use infra::database::AppState;
 use std::sync::Arc;
 
+/// Prefix the paths of the OpenAPI document are written with.
+///
+/// The routes themselves are served under [`Config::api_prefix`].
+pub const DOCUMENTED_API_PREFIX: &str = "/api/v1";
+
 pub fn create_routes(state: Arc<AppState>) -> Router {
-    Router::new()
-        .nest(
-            "/api/v1",
-            Router::new()
-                .nest("/status", status::create_routes(state.clone()))
-                .nest("/migrations", migrations::create_routes(state.clone()))
-                .nest("/printers", printers::create_routes(state.clone()))
-                .nest("/supplies", supplies::create_routes(state.clone()))
-                .nest("/movements", movements::create_routes(state.clone()))
-                .nest("/brands", brands::create_routes(state)),
-        )
-        .merge(swagger::swagger_routes())
+    let api = Router::new()
+        .merge(backup::create_routes(state.clone()))
+        .nest("/status", status::create_routes(state.clone()))
+        .nest("/migrations", migrations::create_routes(state.clone()))
+        .nest("/printers", printers::create_routes(state.clone()))
+        .nest("/supplies", supplies::create_routes(state.clone()))
+        .nest("/movements", movements::create_routes(state.clone()))
+        .nest("/search", search::create_routes(state.clone()))
+        .nest("/reports", reports::create_routes(state.clone()))
+        .nest("/brands", brands::create_routes(state));
+
+    // Axum cannot nest at the root, so an empty prefix merges the routes instead
+    let prefix = Config::api_prefix();
+    let router = if prefix.is_empty() {
+        Router::new().merge(api)
+    } else {
+        Router::new().nest(&prefix, api)
+    };
+
+    let router = router
+        .layer(middleware::from_fn(read_only))
+        .layer(middleware::from_fn(json_case))
+        .layer(middleware::from_fn(pretty_json))
+        .merge(swagger::swagger_routes());
+
+    router
+        .clone()
         .layer(Config::cors())
+        .layer(middleware::from_fn_with_state(
+            router,
+            skip_cors_for_options,
+        ))
+}
+
+/// Answers an `OPTIONS` request with the methods allowed on the route.
+pub async fn allow(methods: &'static str) -> impl IntoResponse {
+    (StatusCode::NO_CONTENT, [(header::ALLOW, methods)])
+}
+
+/// Builds a `Location` header pointing at the resource at `path` under the API prefix.
+pub fn location(path: &str) -> [(HeaderName, String); 1] {
+    [(header::LOCATION, format!("{}{path}", Config::api_prefix()))]
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::{
+        app, create_brand, create_drum, create_printer, create_toner, empty_request, json_request,
+        send,
+    };
+    use axum::{
+        body::to_bytes,
+        http::{header, Method, StatusCode},
+    };
+    use serde_json::{json, Value};
+    use sqlx::PgPool;
+    use std::env;
+    use tower::ServiceExt;
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn head_reports_existence_without_a_body(pool: PgPool) {
+        let app = app(pool);
+        let missing = "550e8400-e29b-41d4-a716-446655440000";
+        let resources = [
+            ("toners", create_toner(&app, "TN-1060", 0).await),
+            ("drums", create_drum(&app, "DK-1150", 0).await),
+            ("brands", create_brand(&app, "Brother").await),
+            ("printers", create_printer(&app, "ECOSYS M2040").await),
+        ];
+
+        for (collection, id) in resources {
+            let prefix = match collection {
+                "toners" | "drums" => "/api/v1/supplies",
+                _ => "/api/v1",
+            };
+
+            for (id, expected) in [
+                (id.to_string(), StatusCode::OK),
+                (String::from(missing), StatusCode::NOT_FOUND),
+            ] {
+                let uri = format!("{prefix}/{collection}/{id}");
+                let response = app
+                    .clone()
+                    .oneshot(empty_request(Method::HEAD, &uri))
+                    .await
+                    .unwrap();
+
+                assert_eq!(response.status(), expected, "{uri}");
+                let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+                assert!(body.is_empty(), "{uri}");
+            }
+        }
+    }
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn created_resources_are_located_by_their_id(pool: PgPool) {
+        let app = app(pool);
+        let brand = create_brand(&app, "Kyocera").await;
+        let toner = create_toner(&app, "TK-1170", 0).await;
+        let drum = create_drum(&app, "DK-1150", 0).await;
+
+        for (collection, body) in [
+            ("/api/v1/supplies/toners", json!({ "name": "TK-3160" })),
+            (
+                "/api/v1/printers",
+                json!({ "name": "ECOSYS M2040", "model": "M2040dn", "brand": brand, "toner": toner, "drum": drum }),
+            ),
+        ] {
+            let response = app
+                .clone()
+                .oneshot(json_request(Method::POST, collection, body))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::CREATED);
+            let location = response.headers()[header::LOCATION]
+                .to_str()
+                .unwrap()
+                .to_owned();
+            let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            let id: Value = serde_json::from_slice(&bytes).unwrap();
+
+            assert_eq!(location, format!("{collection}/{}", id.as_str().unwrap()));
+            let (status, _) = send(&app, empty_request(Method::GET, &location)).await;
+            assert_eq!(status, StatusCode::OK, "{location}");
+        }
+    }
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn every_endpoint_applies_the_same_minimum_name_length(pool: PgPool) {
+        let app = app(pool);
+
+        for collection in [
+            "/api/v1/supplies/toners",
+            "/api/v1/supplies/drums",
+            "/api/v1/brands",
+        ] {
+            let (status, _) = send(
+                &app,
+                json_request(Method::POST, collection, json!({ "name": "HP" })),
+            )
+            .await;
+            assert_eq!(status, StatusCode::BAD_REQUEST, "{collection}");
+
+            let (status, _) = send(
+                &app,
+                json_request(Method::POST, collection, json!({ "name": "HPE" })),
+            )
+            .await;
+            assert_eq!(status, StatusCode::CREATED, "{collection}");
+        }
+    }
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn routes_are_served_under_the_configured_prefix(pool: PgPool) {
+        // The prefix is read when the routes are built, so the variable is only needed until then
+        env::set_var("API_PREFIX", "inventory/v2/");
+        let app = app(pool);
+        env::set_var("API_PREFIX", "/api/v1");
+
+        let (status, toners) = send(
+            &app,
+            empty_request(Method::GET, "/inventory/v2/supplies/toners"),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(toners, json!([]));
+
+        let (status, _) = send(&app, empty_request(Method::GET, "/api/v1/supplies/toners")).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn existence_is_checked_for_each_id(pool: PgPool) {
+        let app = app(pool);
+        let missing = "550e8400-e29b-41d4-a716-446655440000";
+        let resources = [
+            (
+                "/api/v1/supplies/toners",
+                create_toner(&app, "TN-1060", 0).await,
+            ),
+            (
+                "/api/v1/supplies/drums",
+                create_drum(&app, "DK-1150", 0).await,
+            ),
+            ("/api/v1/brands", create_brand(&app, "Brother").await),
+            (
+                "/api/v1/printers",
+                create_printer(&app, "ECOSYS M2040").await,
+            ),
+        ];
+
+        for (collection, id) in resources {
+            let (status, exists) = send(
+                &app,
+                json_request(
+                    Method::POST,
+                    &format!("{collection}/exists"),
+                    json!({ "ids": [id, missing] }),
+                ),
+            )
+            .await;
+
+            assert_eq!(status, StatusCode::OK, "{collection}");
+            let mut expected = serde_json::Map::new();
+            expected.insert(id.to_string(), json!(true));
+            expected.insert(String::from(missing), json!(false));
+            assert_eq!(exists, Value::Object(expected), "{collection}");
+        }
+    }
 }
@@ -1,18 +1,28 @@
-use crate::handlers::movement;
-use axum::{routing::get, Router};
+use crate::{handlers::movement, routes::allow};
+use axum::{
+    routing::{get, post},
+    Router,
+};
 use infra::database::AppState;
 use std::sync::Arc;
 
 pub fn create_routes(state: Arc<AppState>) -> Router {
     Router::new()
         .route("/count", get(movement::count_movements))
-        .route("/:id", get(movement::search_movement))
+        .route("/batch", post(movement::create_movement_batch))
+        .route("/purge", post(movement::purge_movements))
+        .route("/export.jsonl", get(movement::export_movements))
+        .route(
+            "/:id",
+            get(movement::search_movement).options(|| allow("GET, HEAD, OPTIONS")),
+        )
         .route(
             "/",
             get(movement::show_movements)
                 .post(movement::create_movement)
                 .put(movement::update_movement)
-                .delete(movement::delete_movement),
+                .delete(movement::delete_movement)
+                .options(|| allow("GET, HEAD, POST, PUT, DELETE, OPTIONS")),
         )
         .with_state(state)
 }
@@ -1,18 +1,44 @@
-use crate::handlers::printer;
-use axum::{routing::get, Router};
+use crate::{handlers::printer, middlewares::dedup::dedup, routes::allow};
+use axum::{
+    handler::Handler,
+    middleware,
+    routing::{get, post},
+    Router,
+};
 use infra::database::AppState;
 use std::sync::Arc;
 
 pub fn create_routes(state: Arc<AppState>) -> Router {
     Router::new()
         .route("/count", get(printer::count_printers))
-        .route("/:id", get(printer::search_printer))
+        .route("/exists", post(printer::check_printers))
+        .route(
+            "/:id",
+            get(printer::search_printer)
+                .head(printer::check_printer)
+                .options(|| allow("GET, HEAD, OPTIONS")),
+        )
+        .route(
+            "/:id/compatible",
+            get(printer::show_compatible_supplies).options(|| allow("GET, HEAD, OPTIONS")),
+        )
+        .route(
+            "/:id/consumption",
+            get(printer::show_consumption).options(|| allow("GET, HEAD, OPTIONS")),
+        )
+        .route(
+            "/:id/compatible/:supply_id",
+            post(printer::add_compatible_supply)
+                .delete(printer::remove_compatible_supply)
+                .options(|| allow("POST, DELETE, OPTIONS")),
+        )
         .route(
             "/",
             get(printer::show_printers)
-                .post(printer::create_printer)
+                .post(printer::create_printer.layer(middleware::from_fn(dedup)))
                 .put(printer::update_printer)
-                .delete(printer::delete_printer),
+                .delete(printer::delete_printer)
+                .options(|| allow("GET, HEAD, POST, PUT, DELETE, OPTIONS")),
         )
         .with_state(state)
 }
@@ -0,0 +1,13 @@
+use crate::handlers::reports;
+use axum::{routing::get, Router};
+use infra::database::AppState;
+use std::sync::Arc;
+
+pub fn create_routes(state: Arc<AppState>) -> Router {
+    Router::new()
+        .route("/movements-by-day", get(reports::movements_by_day))
+        .route("/by-brand", get(reports::by_brand))
+        .route("/integrity", get(reports::integrity))
+        .route("/balance", get(reports::balance))
+        .with_state(state)
+}
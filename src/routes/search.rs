@@ -0,0 +1,10 @@
+use crate::handlers::search;
+use axum::{routing::get, Router};
+use infra::database::AppState;
+use std::sync::Arc;
+
+pub fn create_routes(state: Arc<AppState>) -> Router {
+    Router::new()
+        .route("/", get(search::search))
+        .with_state(state)
+}
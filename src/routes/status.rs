@@ -4,5 +4,8 @@ use infra::database::AppState;
 use std::sync::Arc;
 
 pub fn create_routes(state: Arc<AppState>) -> Router {
-    Router::new().route("/", get(status::show_status).with_state(state))
+    Router::new()
+        .route("/", get(status::show_status))
+        .route("/pool", get(status::show_pool_status))
+        .with_state(state)
 }
@@ -1,18 +1,30 @@
-use crate::handlers::supplies::drum;
-use axum::{routing::get, Router};
+use crate::{handlers::supplies::drum, middlewares::dedup::dedup, routes::allow};
+use axum::{
+    handler::Handler,
+    middleware,
+    routing::{get, post},
+    Router,
+};
 use infra::database::AppState;
 use std::sync::Arc;
 
 pub fn create_routes(state: Arc<AppState>) -> Router {
     Router::new()
         .route("/count", get(drum::count_drums))
-        .route("/:id", get(drum::search_drum))
+        .route("/exists", post(drum::check_drums))
+        .route(
+            "/:id",
+            get(drum::search_drum)
+                .head(drum::check_drum)
+                .options(|| allow("GET, HEAD, OPTIONS")),
+        )
         .route(
             "/",
             get(drum::show_drums)
-                .post(drum::create_drum)
+                .post(drum::create_drum.layer(middleware::from_fn(dedup)))
                 .put(drum::update_drum)
-                .delete(drum::delete_drum),
+                .delete(drum::delete_drum)
+                .options(|| allow("GET, HEAD, POST, PUT, DELETE, OPTIONS")),
         )
         .with_state(state)
 }
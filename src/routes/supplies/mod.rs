@@ -1,4 +1,8 @@
-use axum::Router;
+use crate::{handlers::supplies, routes::allow};
+use axum::{
+    routing::{delete, get, post, put},
+    Router,
+};
 use infra::database::AppState;
 use std::sync::Arc;
 
@@ -9,6 +13,31 @@ pub fn create_routes(state: Arc<AppState>) -> Router {
     Router::new().nest(
         "/",
         Router::new()
+            .route(
+                "/:id",
+                get(supplies::show_supply).options(|| allow("GET, HEAD, OPTIONS")),
+            )
+            .route(
+                "/:id/movements",
+                get(supplies::show_supply_movements).options(|| allow("GET, HEAD, OPTIONS")),
+            )
+            .route(
+                "/:id/reserve",
+                post(supplies::reserve_supply).options(|| allow("POST, OPTIONS")),
+            )
+            .route(
+                "/:id/reservations/:reservation_id",
+                delete(supplies::cancel_reservation).options(|| allow("DELETE, OPTIONS")),
+            )
+            .route(
+                "/:id/reservations/:reservation_id/fulfil",
+                post(supplies::fulfil_reservation).options(|| allow("POST, OPTIONS")),
+            )
+            .route(
+                "/:id/stock",
+                put(supplies::set_stock).options(|| allow("PUT, OPTIONS")),
+            )
+            .with_state(state.clone())
             .nest("/toners", toners::create_routes(state.clone()))
             .nest("/drums", drums::create_routes(state)),
     )
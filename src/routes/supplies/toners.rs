@@ -1,18 +1,85 @@
-use crate::handlers::supplies::toner;
-use axum::{routing::get, Router};
+use crate::{handlers::supplies::toner, middlewares::dedup::dedup, routes::allow};
+use axum::{
+    handler::Handler,
+    middleware,
+    routing::{get, post},
+    Router,
+};
 use infra::database::AppState;
 use std::sync::Arc;
 
 pub fn create_routes(state: Arc<AppState>) -> Router {
     Router::new()
         .route("/count", get(toner::count_toners))
-        .route("/:id", get(toner::search_toner))
+        .route("/exists", post(toner::check_toners))
+        .route("/autocomplete", get(toner::autocomplete_toners))
+        .route(
+            "/:id",
+            get(toner::search_toner)
+                .head(toner::check_toner)
+                .options(|| allow("GET, HEAD, OPTIONS")),
+        )
         .route(
             "/",
             get(toner::show_toners)
-                .post(toner::create_toner)
+                .post(toner::create_toner.layer(middleware::from_fn(dedup)))
                 .put(toner::update_toner)
-                .delete(toner::delete_toner),
+                .delete(toner::delete_toner)
+                .options(|| allow("GET, HEAD, POST, PUT, DELETE, OPTIONS")),
         )
         .with_state(state)
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::{app, empty_request};
+    use axum::http::{header, Method, StatusCode};
+    use sqlx::PgPool;
+    use tower::ServiceExt;
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn options_lists_the_allowed_methods(pool: PgPool) {
+        let app = app(pool);
+
+        for (uri, allowed) in [
+            (
+                "/api/v1/supplies/toners",
+                "GET, HEAD, POST, PUT, DELETE, OPTIONS",
+            ),
+            (
+                "/api/v1/supplies/toners/550e8400-e29b-41d4-a716-446655440000",
+                "GET, HEAD, OPTIONS",
+            ),
+        ] {
+            let response = app
+                .clone()
+                .oneshot(empty_request(Method::OPTIONS, uri))
+                .await
+                .unwrap();
+
+            assert_eq!(response.status(), StatusCode::NO_CONTENT, "{uri}");
+            assert_eq!(response.headers()[header::ALLOW], allowed, "{uri}");
+        }
+    }
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn cors_preflights_are_still_answered_by_the_cors_layer(pool: PgPool) {
+        let app = app(pool);
+        let mut request = empty_request(Method::OPTIONS, "/api/v1/supplies/toners");
+        request
+            .headers_mut()
+            .insert(header::ORIGIN, "http://localhost:3000".parse().unwrap());
+        request.headers_mut().insert(
+            header::ACCESS_CONTROL_REQUEST_METHOD,
+            "POST".parse().unwrap(),
+        );
+
+        let response = app.oneshot(request).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()[header::ACCESS_CONTROL_ALLOW_ORIGIN],
+            "http://localhost:3000"
+        );
+    }
+}
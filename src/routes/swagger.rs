@@ -1,8 +1,66 @@
+use config::Config;
+use utoipa::openapi::{server::Server, OpenApi as OpenApiDoc};
 use utoipa::OpenApi;
 use utoipa_swagger_ui::SwaggerUi;
 
 use crate::openapi::api_doc::ApiDoc;
+use crate::routes::DOCUMENTED_API_PREFIX;
 
 pub fn swagger_routes() -> SwaggerUi {
-    SwaggerUi::new("/swagger-ui").url("/api-docs/openapi.json", ApiDoc::openapi())
+    SwaggerUi::new("/swagger-ui").url("/api-docs/openapi.json", api_doc())
+}
+
+/// Builds the OpenAPI document with the configured API prefix as its server,
+/// so the paths are listed relative to it.
+///
+/// The server is prefixed with `PUBLIC_BASE_URL` when set. Otherwise it is relative,
+/// so "Try it out" targets the origin the document was served from.
+fn api_doc() -> OpenApiDoc {
+    let mut doc = ApiDoc::openapi();
+    let prefix = Config::api_prefix();
+
+    doc.paths.paths = std::mem::take(&mut doc.paths.paths)
+        .into_iter()
+        .map(|(path, item)| {
+            let path = path
+                .strip_prefix(DOCUMENTED_API_PREFIX)
+                .map(String::from)
+                .unwrap_or(path);
+            (path, item)
+        })
+        .collect();
+
+    let url = match (Config::public_base_url(), prefix.is_empty()) {
+        (Some(base_url), _) => format!("{base_url}{prefix}"),
+        (None, true) => String::from("/"),
+        (None, false) => prefix,
+    };
+    doc.servers = Some(vec![Server::new(url)]);
+
+    doc
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::{app, empty_request, send};
+    use axum::http::{Method, StatusCode};
+    use config::Config;
+    use sqlx::PgPool;
+    use std::env;
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn the_served_spec_targets_the_public_base_url(pool: PgPool) {
+        // The spec is built along with the routes, so the variable is only needed until then
+        env::set_var("PUBLIC_BASE_URL", "https://supplies.example.com/");
+        let app = app(pool);
+        env::set_var("PUBLIC_BASE_URL", "");
+
+        let (status, spec) = send(&app, empty_request(Method::GET, "/api-docs/openapi.json")).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(
+            spec["servers"][0]["url"],
+            format!("https://supplies.example.com{}", Config::api_prefix())
+        );
+    }
 }
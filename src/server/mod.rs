@@ -1,10 +1,34 @@
 use crate::routes;
-use infra::database::{connection::create_pool, AppState};
+use axum::{extract::Request, Router, ServiceExt};
+use config::Config;
+use infra::database::{
+    connection::{create_pool, PoolSettings},
+    count_cache::CountCache,
+    indexes::missing_indexes,
+    AppState,
+};
+use sqlx::PgPool;
 use std::sync::Arc;
-use tracing::{error, info};
+use tower_http::normalize_path::NormalizePath;
+use tracing::{error, info, warn};
 
 pub async fn run() -> Result<(), axum::Error> {
-    let pool = match create_pool().await {
+    let (min_connections, max_connections) = match Config::database_connections() {
+        Ok(connections) => connections,
+        Err(e) => {
+            error!("❌ {e}");
+            std::process::exit(1);
+        }
+    };
+
+    let pool_settings = PoolSettings {
+        min_connections,
+        max_connections,
+        statement_timeout_ms: Config::database_statement_timeout_ms(),
+        slow_query_ms: Config::slow_query_ms(),
+    };
+
+    let pool = match create_pool(pool_settings).await {
         Ok(pool) => {
             info!("✅ Connected to the database");
             pool
@@ -15,7 +39,14 @@ pub async fn run() -> Result<(), axum::Error> {
         }
     };
 
-    let app = routes::create_routes(Arc::new(AppState { db: pool.clone() }));
+    if Config::check_indexes() {
+        check_indexes(&pool).await;
+    }
+
+    let app = routes::create_routes(Arc::new(AppState {
+        db: pool.clone(),
+        toner_count: CountCache::default(),
+    }));
 
     let addr = std::env::var("HOST").expect("Failed to load HOST");
     let listener = match tokio::net::TcpListener::bind(&addr).await {
@@ -29,8 +60,66 @@ pub async fn run() -> Result<(), axum::Error> {
         }
     };
 
-    axum::serve(listener, app)
-        .await
-        .expect("Error starting the server");
+    if Config::normalize_trailing_slash() {
+        let app = trim_trailing_slash(app);
+        axum::serve(listener, ServiceExt::<Request>::into_make_service(app))
+            .await
+            .expect("Error starting the server");
+    } else {
+        axum::serve(listener, app)
+            .await
+            .expect("Error starting the server");
+    }
     Ok(())
 }
+
+/// Trims trailing slashes, which must happen before the request reaches the router.
+fn trim_trailing_slash(app: Router) -> NormalizePath<Router> {
+    NormalizePath::trim_trailing_slash(app)
+}
+
+/// Warns about the columns the queries filter by that have no index.
+async fn check_indexes(pool: &PgPool) {
+    match missing_indexes(pool).await {
+        Ok(missing) if missing.is_empty() => info!("✅ All expected indexes are present"),
+        Ok(missing) => {
+            for (table, column) in missing {
+                warn!("⚠️ Missing index on {table}.{column}: queries filtering by it will scan the whole table");
+            }
+        }
+        Err(e) => error!("❌ Error checking the database indexes: {e}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::trim_trailing_slash;
+    use crate::test_utils::{app, create_toner, empty_request};
+    use axum::{
+        body::to_bytes,
+        http::{Method, StatusCode},
+    };
+    use serde_json::{json, Value};
+    use sqlx::PgPool;
+    use tower::ServiceExt;
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn trailing_slashes_reach_the_same_handler(pool: PgPool) {
+        let router = app(pool);
+        let toner = create_toner(&router, "TN-1060", 0).await;
+        let app = trim_trailing_slash(router);
+
+        for uri in ["/api/v1/supplies/toners", "/api/v1/supplies/toners/"] {
+            let response = app
+                .clone()
+                .oneshot(empty_request(Method::GET, uri))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK, "{uri}");
+
+            let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            let toners: Value = serde_json::from_slice(&bytes).unwrap();
+            assert_eq!(toners[0]["id"], json!(toner), "{uri}");
+        }
+    }
+}
@@ -0,0 +1,143 @@
+//! Helpers for the handler tests.
+//!
+//! Each `#[sqlx::test]` gets a fresh database, created on the server given by `DATABASE_URL`,
+//! with the migrations applied.
+
+use crate::routes;
+use axum::{
+    body::{to_bytes, Body},
+    http::{header, Method, Request, StatusCode},
+    Router,
+};
+use infra::database::{count_cache::CountCache, AppState};
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use std::sync::{Arc, Once};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+/// Token the admin endpoints accept in tests, as set in `.env.test`.
+pub const ADMIN_TOKEN: &str = "test-admin-token";
+
+static ENVIRONMENT: Once = Once::new();
+
+/// Builds the API router on the test database, with the test environment loaded.
+pub fn app(pool: PgPool) -> Router {
+    ENVIRONMENT.call_once(|| {
+        dotenvy::from_path("environments/.env.test").expect("Error loading .env.test");
+    });
+
+    routes::create_routes(Arc::new(AppState {
+        db: pool,
+        toner_count: CountCache::default(),
+    }))
+}
+
+/// A request with a JSON body.
+pub fn json_request(method: Method, uri: &str, body: Value) -> Request<Body> {
+    let body = body.to_string();
+
+    Request::builder()
+        .method(method)
+        .uri(uri)
+        .header(header::CONTENT_TYPE, "application/json")
+        .header(header::CONTENT_LENGTH, body.len())
+        .body(Body::from(body))
+        .unwrap()
+}
+
+/// A request without a body.
+pub fn empty_request(method: Method, uri: &str) -> Request<Body> {
+    Request::builder()
+        .method(method)
+        .uri(uri)
+        .body(Body::empty())
+        .unwrap()
+}
+
+/// Adds the admin token to the request.
+pub fn as_admin(mut request: Request<Body>) -> Request<Body> {
+    request.headers_mut().insert(
+        header::AUTHORIZATION,
+        format!("Bearer {ADMIN_TOKEN}").parse().unwrap(),
+    );
+    request
+}
+
+/// Sends the request, returning the status and the JSON body, or `Null` when the body is not JSON.
+pub async fn send(app: &Router, request: Request<Body>) -> (StatusCode, Value) {
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+
+    (
+        status,
+        serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+    )
+}
+
+/// Creates a resource through the API and returns its ID.
+async fn create(app: &Router, uri: &str, body: Value) -> Uuid {
+    let (status, body) = send(app, json_request(Method::POST, uri, body)).await;
+    assert_eq!(status, StatusCode::CREATED, "creating {uri} failed: {body}");
+
+    serde_json::from_value(body).unwrap()
+}
+
+pub async fn create_brand(app: &Router, name: &str) -> Uuid {
+    create(app, "/api/v1/brands", json!({ "name": name })).await
+}
+
+pub async fn create_toner(app: &Router, name: &str, stock: i64) -> Uuid {
+    create(
+        app,
+        "/api/v1/supplies/toners",
+        json!({ "name": name, "stock": stock }),
+    )
+    .await
+}
+
+pub async fn create_drum(app: &Router, name: &str, stock: i64) -> Uuid {
+    create(
+        app,
+        "/api/v1/supplies/drums",
+        json!({ "name": name, "stock": stock }),
+    )
+    .await
+}
+
+/// Creates a printer along with a brand, a toner and a drum of its own.
+pub async fn create_printer(app: &Router, name: &str) -> Uuid {
+    let brand = create_brand(app, &format!("{name} Brand")).await;
+    let toner = create_toner(app, &format!("{name} Toner"), 0).await;
+    let drum = create_drum(app, &format!("{name} Drum"), 0).await;
+
+    create(
+        app,
+        "/api/v1/printers",
+        json!({
+            "name": name,
+            "model": "Model 100",
+            "brand": brand,
+            "toner": toner,
+            "drum": drum,
+        }),
+    )
+    .await
+}
+
+/// Current stock of a toner or drum.
+pub async fn stock_of(app: &Router, item_id: Uuid) -> i64 {
+    let (status, body) = send(
+        app,
+        empty_request(Method::GET, &format!("/api/v1/supplies/{item_id}")),
+    )
+    .await;
+    assert_eq!(
+        status,
+        StatusCode::OK,
+        "reading supply {item_id} failed: {body}"
+    );
+
+    body["stock"].as_i64().unwrap()
+}
@@ -0,0 +1,89 @@
+use crate::errors::api_error::ApiError;
+use config::Config;
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Mutex, OnceLock},
+    time::{Duration, Instant},
+};
+use tracing::warn;
+use uuid::Uuid;
+
+/// Span of the sliding window consumption movements are counted in.
+const CONSUMPTION_WINDOW: Duration = Duration::from_secs(60);
+
+type ConsumptionTimes = HashMap<Uuid, VecDeque<Instant>>;
+
+fn recent_consumptions() -> &'static Mutex<ConsumptionTimes> {
+    static RECENT: OnceLock<Mutex<ConsumptionTimes>> = OnceLock::new();
+    RECENT.get_or_init(Default::default)
+}
+
+/// Counts a consumption of `item_id`, rejecting it when the item already had
+/// `MAX_CONSUMPTION_PER_MINUTE` consumptions within the last minute.
+///
+/// Accepted consumptions count even if the movement fails to commit afterwards.
+/// The window is kept in memory, per instance.
+pub fn check_consumption_rate(item_id: Uuid) -> Result<(), ApiError> {
+    let Some(limit) = Config::max_consumption_per_minute() else {
+        return Ok(());
+    };
+
+    let mut recent = recent_consumptions().lock().unwrap();
+    record_consumption(&mut recent, item_id, limit, Instant::now())
+}
+
+fn record_consumption(
+    recent: &mut ConsumptionTimes,
+    item_id: Uuid,
+    limit: usize,
+    now: Instant,
+) -> Result<(), ApiError> {
+    // Items without consumptions in the window are forgotten, so the map doesn't keep growing
+    recent.retain(|_, times| {
+        while times
+            .front()
+            .is_some_and(|time| now.duration_since(*time) >= CONSUMPTION_WINDOW)
+        {
+            times.pop_front();
+        }
+        !times.is_empty()
+    });
+
+    let times = recent.entry(item_id).or_default();
+
+    if let Some(oldest) = times.front().filter(|_| times.len() >= limit) {
+        let wait = CONSUMPTION_WINDOW.saturating_sub(now.duration_since(*oldest));
+        let retry_after = wait.as_secs() + u64::from(wait.subsec_nanos() > 0);
+
+        warn!("Throttled consumption of item {item_id}: {limit} per minute reached");
+        return Err(ApiError::ConsumptionRateLimited { retry_after });
+    }
+
+    times.push_back(now);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{record_consumption, ConsumptionTimes, CONSUMPTION_WINDOW};
+    use crate::errors::api_error::ApiError;
+    use std::time::Instant;
+    use uuid::Uuid;
+
+    #[test]
+    fn items_are_forgotten_once_their_window_is_empty() {
+        let mut recent = ConsumptionTimes::new();
+        let (first, second) = (Uuid::new_v4(), Uuid::new_v4());
+        let start = Instant::now();
+
+        record_consumption(&mut recent, first, 1, start).unwrap();
+        assert!(matches!(
+            record_consumption(&mut recent, first, 1, start),
+            Err(ApiError::ConsumptionRateLimited { retry_after: 60 })
+        ));
+
+        record_consumption(&mut recent, second, 1, start + CONSUMPTION_WINDOW).unwrap();
+        assert!(!recent.contains_key(&first));
+        assert_eq!(recent.len(), 1);
+    }
+}
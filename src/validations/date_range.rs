@@ -0,0 +1,16 @@
+use crate::models::report::{ReportRangeParams, MAX_REPORT_DAYS};
+use std::borrow::Cow;
+use validator::ValidationError;
+
+pub fn is_valid_report_range(params: &ReportRangeParams) -> Result<(), ValidationError> {
+    if params.from > params.to {
+        return Err(ValidationError::new("INVALID_RANGE")
+            .with_message(Cow::Borrowed("`from` must not be after `to`")));
+    }
+
+    if (params.to - params.from).num_days() >= MAX_REPORT_DAYS {
+        return Err(ValidationError::new("RANGE_TOO_WIDE")
+            .with_message(Cow::Borrowed("The range must not cover more than 366 days")));
+    }
+    Ok(())
+}
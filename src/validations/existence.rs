@@ -17,7 +17,7 @@ pub async fn toner_exists(state: Arc<AppState>, toner_id: Uuid) -> Result<(), Ap
 
     if !exists {
         error!("Toner ID not found.");
-        Err(ApiError::IdNotFound)
+        Err(ApiError::IdNotFound { resource: "Toner" })
     } else {
         Ok(())
     }
@@ -36,7 +36,7 @@ pub async fn drum_exists(state: Arc<AppState>, drum_id: Uuid) -> Result<(), ApiE
 
     if !exists {
         error!("Drum ID not found.");
-        Err(ApiError::IdNotFound)
+        Err(ApiError::IdNotFound { resource: "Drum" })
     } else {
         Ok(())
     }
@@ -55,7 +55,7 @@ pub async fn brand_exists(state: Arc<AppState>, brand_id: Uuid) -> Result<(), Ap
 
     if !exists {
         error!("Brand ID not found.");
-        Err(ApiError::IdNotFound)
+        Err(ApiError::IdNotFound { resource: "Brand" })
     } else {
         Ok(())
     }
@@ -74,7 +74,9 @@ pub async fn printer_exists(state: Arc<AppState>, printer_id: Uuid) -> Result<()
 
     if !exists {
         error!("Printer ID not found.");
-        Err(ApiError::IdNotFound)
+        Err(ApiError::IdNotFound {
+            resource: "Printer",
+        })
     } else {
         Ok(())
     }
@@ -93,7 +95,9 @@ pub async fn movement_exists(state: Arc<AppState>, movement_id: Uuid) -> Result<
 
     if !exists {
         error!("Movement ID not found.");
-        Err(ApiError::IdNotFound)
+        Err(ApiError::IdNotFound {
+            resource: "Movement",
+        })
     } else {
         Ok(())
     }
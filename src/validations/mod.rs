@@ -1,3 +1,12 @@
+pub mod consumption;
+pub mod currency;
+pub mod date_range;
 pub mod existence;
+pub mod model;
+pub mod name;
+pub mod price;
+pub mod quantity;
+pub mod timestamp;
+pub mod timezone;
 pub mod uniqueness;
 pub mod uuid;
@@ -0,0 +1,45 @@
+use config::Config;
+use std::borrow::Cow;
+use validator::ValidationError;
+
+/// Shortest printer model accepted, after whitespace is collapsed.
+pub const MIN_MODEL_LENGTH: usize = 3;
+
+/// Longest printer model accepted, as stored in the database.
+pub const MAX_MODEL_LENGTH: usize = 50;
+
+/// Trims a printer model and collapses inner whitespace into single spaces.
+///
+/// The model is also uppercased when `UPPERCASE_PRINTER_MODELS` is enabled.
+pub fn normalize_model(model: &str) -> String {
+    let model = model.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    if Config::uppercase_printer_models() {
+        model.to_uppercase()
+    } else {
+        model
+    }
+}
+
+/// Checks the length a printer model will have once normalized.
+pub fn is_valid_model(model: &str) -> Result<(), ValidationError> {
+    let length = normalize_model(model).chars().count();
+
+    if length < MIN_MODEL_LENGTH {
+        return Err(
+            ValidationError::new("MODEL_TOO_SHORT").with_message(Cow::Owned(format!(
+                "Model must have at least {MIN_MODEL_LENGTH} characters"
+            ))),
+        );
+    }
+
+    if length > MAX_MODEL_LENGTH {
+        return Err(
+            ValidationError::new("MODEL_TOO_LONG").with_message(Cow::Owned(format!(
+                "Model must have at most {MAX_MODEL_LENGTH} characters"
+            ))),
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,160 @@
+use crate::models::MIN_NAME_LENGTH;
+use config::Config;
+use regex::Regex;
+use std::{borrow::Cow, sync::OnceLock};
+use tracing::error;
+use validator::ValidationError;
+
+/// Longest name accepted for toners, drums and brands, as stored in the database.
+pub const MAX_NAME_LENGTH: u64 = 50;
+
+/// Longest name accepted for printers, as stored in the database.
+pub const MAX_PRINTER_NAME_LENGTH: u64 = 100;
+
+/// Characters allowed in brand names unless `BRAND_NAME_PATTERN` is set:
+/// letters, digits, spaces and common punctuation.
+const DEFAULT_BRAND_NAME_PATTERN: &str = r"^[\p{L}\p{N} .,&'()/+\-]+$";
+
+/// The brand name pattern, compiled once.
+fn brand_name_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| compile_brand_name_pattern(Config::brand_name_pattern()))
+}
+
+/// Compiles a brand name pattern so it must match the whole name.
+///
+/// An invalid pattern is logged and falls back to the default character set.
+fn compile_brand_name_pattern(pattern: Option<String>) -> Regex {
+    pattern
+        .and_then(|pattern| match Regex::new(&format!("^(?:{pattern})$")) {
+            Ok(regex) => Some(regex),
+            Err(e) => {
+                error!("Invalid BRAND_NAME_PATTERN '{pattern}', using the default: {e}");
+                None
+            }
+        })
+        .unwrap_or_else(|| Regex::new(DEFAULT_BRAND_NAME_PATTERN).unwrap())
+}
+
+/// Checks a name, with a distinct code for empty, too short and too long names.
+///
+/// Surrounding whitespace doesn't count towards the minimum length, but does towards the maximum,
+/// as the name is stored as sent.
+fn check_name(name: &str, max_length: u64) -> Result<(), ValidationError> {
+    let length = name.trim().chars().count() as u64;
+
+    if length == 0 {
+        return Err(ValidationError::new("NAME_EMPTY")
+            .with_message(Cow::Borrowed("Name must not be empty")));
+    }
+
+    if length < MIN_NAME_LENGTH {
+        return Err(
+            ValidationError::new("NAME_TOO_SHORT").with_message(Cow::Owned(format!(
+                "Name must have at least {MIN_NAME_LENGTH} characters"
+            ))),
+        );
+    }
+
+    if name.chars().count() as u64 > max_length {
+        return Err(
+            ValidationError::new("NAME_TOO_LONG").with_message(Cow::Owned(format!(
+                "Name must have at most {max_length} characters"
+            ))),
+        );
+    }
+
+    Ok(())
+}
+
+pub fn is_valid_name(name: &str) -> Result<(), ValidationError> {
+    check_name(name, MAX_NAME_LENGTH)
+}
+
+pub fn is_valid_printer_name(name: &str) -> Result<(), ValidationError> {
+    check_name(name, MAX_PRINTER_NAME_LENGTH)
+}
+
+/// Checks a brand name, which must also be free of control characters and match the brand name pattern.
+pub fn is_valid_brand_name(name: &str) -> Result<(), ValidationError> {
+    check_name(name, MAX_NAME_LENGTH)?;
+
+    if name.chars().any(char::is_control) {
+        return Err(ValidationError::new("NAME_INVALID_CHARACTERS")
+            .with_message(Cow::Borrowed("Name must not contain control characters")));
+    }
+
+    if !brand_name_regex().is_match(name) {
+        return Err(
+            ValidationError::new("NAME_INVALID_CHARACTERS").with_message(Cow::Borrowed(
+                "Name contains characters that are not allowed in brand names",
+            )),
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        compile_brand_name_pattern, is_valid_brand_name, is_valid_name, is_valid_printer_name,
+        MAX_NAME_LENGTH,
+    };
+
+    fn code(name: &str) -> Option<String> {
+        is_valid_name(name).err().map(|e| e.code.into_owned())
+    }
+
+    #[test]
+    fn names_get_a_code_per_kind_of_problem() {
+        assert_eq!(code("   ").as_deref(), Some("NAME_EMPTY"));
+        assert_eq!(code(" ab ").as_deref(), Some("NAME_TOO_SHORT"));
+        assert_eq!(code(&"a".repeat(51)).as_deref(), Some("NAME_TOO_LONG"));
+    }
+
+    #[test]
+    fn every_kind_of_name_shares_the_minimum_length() {
+        for validate in [is_valid_name, is_valid_printer_name, is_valid_brand_name] {
+            assert_eq!(
+                validate("HP").unwrap_err().code.into_owned(),
+                "NAME_TOO_SHORT"
+            );
+            assert!(validate("HPE").is_ok());
+        }
+    }
+
+    #[test]
+    fn the_maximum_length_includes_surrounding_whitespace() {
+        let longest = "a".repeat(MAX_NAME_LENGTH as usize);
+
+        assert_eq!(code(&longest), None);
+        assert_eq!(
+            code(&format!("{longest} ")).as_deref(),
+            Some("NAME_TOO_LONG")
+        );
+    }
+
+    #[test]
+    fn brand_names_with_control_characters_are_rejected() {
+        assert!(is_valid_brand_name("Brother Industries").is_ok());
+        assert!(is_valid_brand_name("Brother\u{7}").is_err());
+    }
+
+    #[test]
+    fn configured_brand_name_patterns_must_match_the_whole_name() {
+        let regex = compile_brand_name_pattern(Some(String::from("[A-Z]+")));
+
+        assert!(regex.is_match("HP"));
+        assert!(!regex.is_match("HP!"));
+        assert!(!regex.is_match("hp"));
+    }
+
+    #[test]
+    fn invalid_brand_name_patterns_fall_back_to_the_default() {
+        let regex = compile_brand_name_pattern(Some(String::from("[A-Z")));
+
+        assert!(regex.is_match("Canon (Japan)"));
+        assert!(!regex.is_match("Canon 🖨"));
+    }
+}
@@ -0,0 +1,49 @@
+use config::Config;
+use rust_decimal::{Decimal, RoundingStrategy};
+use std::borrow::Cow;
+use validator::ValidationError;
+
+/// Smallest price the `DECIMAL(6, 2)` price columns cannot hold.
+const PRICE_LIMIT: Decimal = Decimal::from_parts(10000, 0, 0, false, 0);
+
+/// Rounds a price to the configured `PRICE_SCALE`, with halves rounded away from zero.
+pub fn normalize_price(price: Decimal) -> Decimal {
+    price.round_dp_with_strategy(
+        Config::price_scale(),
+        RoundingStrategy::MidpointAwayFromZero,
+    )
+}
+
+/// Checks that a price still fits the price columns once rounded, so `9999.999` is
+/// rejected instead of failing the insert as `10000.00`.
+pub fn is_price(price: &Decimal) -> Result<(), ValidationError> {
+    if normalize_price(*price).abs() < PRICE_LIMIT {
+        return Ok(());
+    }
+
+    Err(
+        ValidationError::new("PRICE_OUT_OF_RANGE").with_message(Cow::Owned(format!(
+            "Price must be less than {PRICE_LIMIT} once rounded, got {price}"
+        ))),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn prices_are_rounded_to_two_places() {
+        let price = Decimal::from_str("19.999").unwrap();
+
+        assert_eq!(normalize_price(price), Decimal::from_str("20.00").unwrap());
+    }
+
+    #[test]
+    fn prices_that_round_past_the_column_are_rejected() {
+        assert!(is_price(&Decimal::from_str("9999.994").unwrap()).is_ok());
+        assert!(is_price(&Decimal::from_str("9999.995").unwrap()).is_err());
+        assert!(is_price(&Decimal::from_str("-10000").unwrap()).is_err());
+    }
+}
@@ -0,0 +1,28 @@
+use config::Config;
+use std::borrow::Cow;
+use validator::ValidationError;
+
+pub fn is_non_zero(quantity: i64) -> Result<(), ValidationError> {
+    if quantity == 0 {
+        return Err(ValidationError::new("ZERO_QUANTITY")
+            .with_message(Cow::Borrowed("Quantity must not be zero")));
+    }
+    Ok(())
+}
+
+/// Checks that a movement quantity is within `MIN_MOVEMENT_QUANTITY` and `MAX_MOVEMENT_QUANTITY`.
+pub fn is_movement_quantity(quantity: i64) -> Result<(), ValidationError> {
+    let (min, max) = (
+        Config::min_movement_quantity(),
+        Config::max_movement_quantity(),
+    );
+
+    if quantity < min || quantity > max {
+        return Err(
+            ValidationError::new("QUANTITY_OUT_OF_RANGE").with_message(Cow::Owned(format!(
+                "Quantity must be between {min} and {max}"
+            ))),
+        );
+    }
+    Ok(())
+}
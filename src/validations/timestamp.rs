@@ -0,0 +1,11 @@
+use chrono::{DateTime, Utc};
+use std::borrow::Cow;
+use validator::ValidationError;
+
+pub fn is_not_in_future(timestamp: &DateTime<Utc>) -> Result<(), ValidationError> {
+    if *timestamp > Utc::now() {
+        return Err(ValidationError::new("FUTURE_TIMESTAMP")
+            .with_message(Cow::Borrowed("Timestamp must not be in the future")));
+    }
+    Ok(())
+}
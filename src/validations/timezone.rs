@@ -0,0 +1,20 @@
+use chrono::FixedOffset;
+use std::borrow::Cow;
+use validator::ValidationError;
+
+/// Parses a UTC offset such as `-03:00`, `+05:30` or `Z`.
+pub fn parse_utc_offset(tz: &str) -> Option<FixedOffset> {
+    match tz {
+        "Z" | "UTC" => FixedOffset::east_opt(0),
+        _ => tz.parse().ok(),
+    }
+}
+
+pub fn is_utc_offset(tz: &str) -> Result<(), ValidationError> {
+    parse_utc_offset(tz).ok_or_else(|| {
+        ValidationError::new("INVALID_TIMEZONE").with_message(Cow::Borrowed(
+            "Timezone must be a UTC offset such as -03:00 or +05:30",
+        ))
+    })?;
+    Ok(())
+}
@@ -1,80 +1,228 @@
 use crate::errors::api_error::ApiError;
+use config::Config;
 use infra::database::AppState;
-use std::sync::Arc;
+use std::{future::Future, sync::Arc};
 use tracing::error;
+use uuid::Uuid;
 
-pub async fn is_toner_unique(state: Arc<AppState>, toner_name: String) -> Result<(), ApiError> {
-    let exists = sqlx::query(r#"SELECT id FROM toners WHERE name = $1;"#)
-        .bind(&toner_name)
-        .fetch_optional(&state.db)
-        .await
-        .map_err(|e| {
-            error!("Error checking for existing toner: {e}");
-            ApiError::DatabaseError(e)
-        })?
-        .is_some();
-
-    if exists {
-        error!("Toner '{}' already exists.", &toner_name);
-        Err(ApiError::AlreadyExists)
-    } else {
-        Ok(())
-    }
+/// Checks that no toner is named `toner_name`, returning the ID of the toner that is.
+///
+/// With `UNIQUE_NAMES_PER_BRAND` enabled, only toners of the same brand are considered.
+pub async fn is_toner_unique(
+    state: Arc<AppState>,
+    toner_name: String,
+    brand_id: Option<Uuid>,
+) -> Result<Option<Uuid>, ApiError> {
+    let existing_id = sqlx::query_scalar::<_, Uuid>(
+        r#"
+        SELECT id FROM toners
+        WHERE name = $1 AND (NOT $2 OR brand_id IS NOT DISTINCT FROM $3);
+        "#,
+    )
+    .bind(&toner_name)
+    .bind(Config::unique_names_per_brand())
+    .bind(brand_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|e| {
+        error!("Error checking for existing toner: {e}");
+        ApiError::DatabaseError(e)
+    })?;
+
+    Ok(existing_id)
 }
 
-pub async fn is_drum_unique(state: Arc<AppState>, drum_name: String) -> Result<(), ApiError> {
-    let exists = sqlx::query(r#"SELECT id FROM drums WHERE name = $1;"#)
-        .bind(&drum_name)
-        .fetch_optional(&state.db)
-        .await
-        .map_err(|e| {
-            error!("Error checking for existing drum: {e}");
-            ApiError::DatabaseError(e)
-        })?
-        .is_some();
-
-    if exists {
-        error!("Drum '{}' already exists.", &drum_name);
-        Err(ApiError::AlreadyExists)
-    } else {
-        Ok(())
-    }
+/// Checks that no toner other than `toner_id` is named `toner_name`, returning the ID of the one that is.
+///
+/// With `UNIQUE_NAMES_PER_BRAND` enabled, only toners of the same brand are considered.
+pub async fn is_toner_name_available(
+    state: Arc<AppState>,
+    toner_name: String,
+    brand_id: Option<Uuid>,
+    toner_id: Uuid,
+) -> Result<Option<Uuid>, ApiError> {
+    let existing_id = sqlx::query_scalar::<_, Uuid>(
+        r#"
+        SELECT id FROM toners
+        WHERE name = $1 AND id <> $4 AND (NOT $2 OR brand_id IS NOT DISTINCT FROM $3);
+        "#,
+    )
+    .bind(&toner_name)
+    .bind(Config::unique_names_per_brand())
+    .bind(brand_id)
+    .bind(toner_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|e| {
+        error!("Error checking for existing toner: {e}");
+        ApiError::DatabaseError(e)
+    })?;
+
+    Ok(existing_id)
+}
+
+/// Checks that no drum is named `drum_name`, returning the ID of the drum that is.
+///
+/// With `UNIQUE_NAMES_PER_BRAND` enabled, only drums of the same brand are considered.
+pub async fn is_drum_unique(
+    state: Arc<AppState>,
+    drum_name: String,
+    brand_id: Option<Uuid>,
+) -> Result<Option<Uuid>, ApiError> {
+    let existing_id = sqlx::query_scalar::<_, Uuid>(
+        r#"
+        SELECT id FROM drums
+        WHERE name = $1 AND (NOT $2 OR brand_id IS NOT DISTINCT FROM $3);
+        "#,
+    )
+    .bind(&drum_name)
+    .bind(Config::unique_names_per_brand())
+    .bind(brand_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|e| {
+        error!("Error checking for existing drum: {e}");
+        ApiError::DatabaseError(e)
+    })?;
+
+    Ok(existing_id)
+}
+
+/// Checks that no drum other than `drum_id` is named `drum_name`, returning the ID of the one that is.
+///
+/// With `UNIQUE_NAMES_PER_BRAND` enabled, only drums of the same brand are considered.
+pub async fn is_drum_name_available(
+    state: Arc<AppState>,
+    drum_name: String,
+    brand_id: Option<Uuid>,
+    drum_id: Uuid,
+) -> Result<Option<Uuid>, ApiError> {
+    let existing_id = sqlx::query_scalar::<_, Uuid>(
+        r#"
+        SELECT id FROM drums
+        WHERE name = $1 AND id <> $4 AND (NOT $2 OR brand_id IS NOT DISTINCT FROM $3);
+        "#,
+    )
+    .bind(&drum_name)
+    .bind(Config::unique_names_per_brand())
+    .bind(brand_id)
+    .bind(drum_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|e| {
+        error!("Error checking for existing drum: {e}");
+        ApiError::DatabaseError(e)
+    })?;
+
+    Ok(existing_id)
 }
 
-pub async fn is_brand_unique(state: Arc<AppState>, brand_name: String) -> Result<(), ApiError> {
-    let exists = sqlx::query(r#"SELECT id FROM brands WHERE name = $1;"#)
+/// Checks that no brand is named `brand_name`, returning the ID of the brand that is.
+pub async fn is_brand_unique(
+    state: Arc<AppState>,
+    brand_name: String,
+) -> Result<Option<Uuid>, ApiError> {
+    let existing_id = sqlx::query_scalar::<_, Uuid>(r#"SELECT id FROM brands WHERE name = $1;"#)
         .bind(&brand_name)
         .fetch_optional(&state.db)
         .await
         .map_err(|e| {
             error!("Error checking for existing brand: {e}");
             ApiError::DatabaseError(e)
-        })?
-        .is_some();
-
-    if exists {
-        error!("Brand '{}' already exists.", &brand_name);
-        Err(ApiError::AlreadyExists)
-    } else {
-        Ok(())
-    }
+        })?;
+
+    Ok(existing_id)
 }
 
-pub async fn is_printer_unique(state: Arc<AppState>, printer_name: String) -> Result<(), ApiError> {
-    let exists = sqlx::query(r#"SELECT id FROM printers WHERE name = $1;"#)
+/// Checks that no brand other than `brand_id` is named `brand_name`, returning the ID of the one that is.
+pub async fn is_brand_name_available(
+    state: Arc<AppState>,
+    brand_name: String,
+    brand_id: Uuid,
+) -> Result<Option<Uuid>, ApiError> {
+    let existing_id =
+        sqlx::query_scalar::<_, Uuid>(r#"SELECT id FROM brands WHERE name = $1 AND id <> $2;"#)
+            .bind(&brand_name)
+            .bind(brand_id)
+            .fetch_optional(&state.db)
+            .await
+            .map_err(|e| {
+                error!("Error checking for existing brand: {e}");
+                ApiError::DatabaseError(e)
+            })?;
+
+    Ok(existing_id)
+}
+
+/// Checks that no printer is named `printer_name`, returning the ID of the printer that is.
+pub async fn is_printer_unique(
+    state: Arc<AppState>,
+    printer_name: String,
+) -> Result<Option<Uuid>, ApiError> {
+    let existing_id = sqlx::query_scalar::<_, Uuid>(r#"SELECT id FROM printers WHERE name = $1;"#)
         .bind(&printer_name)
         .fetch_optional(&state.db)
         .await
         .map_err(|e| {
             error!("Error checking for existing printer: {e}");
             ApiError::DatabaseError(e)
-        })?
-        .is_some();
-
-    if exists {
-        error!("Printer '{}' already exists.", &printer_name);
-        Err(ApiError::AlreadyExists)
-    } else {
-        Ok(())
+        })?;
+
+    Ok(existing_id)
+}
+
+/// Checks that no printer other than `printer_id` is named `printer_name`, returning the ID of the one that is.
+pub async fn is_printer_name_available(
+    state: Arc<AppState>,
+    printer_name: String,
+    printer_id: Uuid,
+) -> Result<Option<Uuid>, ApiError> {
+    let existing_id =
+        sqlx::query_scalar::<_, Uuid>(r#"SELECT id FROM printers WHERE name = $1 AND id <> $2;"#)
+            .bind(&printer_name)
+            .bind(printer_id)
+            .fetch_optional(&state.db)
+            .await
+            .map_err(|e| {
+                error!("Error checking for existing printer: {e}");
+                ApiError::DatabaseError(e)
+            })?;
+
+    Ok(existing_id)
+}
+
+/// Fails with `AlreadyExists` when another resource, found by one of the checks above, is named `name`.
+pub fn reject_taken_name(name: String, existing_id: Option<Uuid>) -> Result<(), ApiError> {
+    match existing_id {
+        Some(id) => {
+            error!("'{name}' is already taken by the resource with ID {id}.");
+            Err(ApiError::AlreadyExists { name, id })
+        }
+        None => Ok(()),
+    }
+}
+
+/// Maps a failed insert or update to `AlreadyExists` when it violated a unique constraint.
+///
+/// Two concurrent requests can both pass the uniqueness check before writing the same name,
+/// so the loser runs `check` again to report the row that won. Other errors are returned as `DatabaseError`.
+pub async fn conflict_or_database_error(
+    e: sqlx::Error,
+    name: String,
+    check: impl Future<Output = Result<Option<Uuid>, ApiError>>,
+) -> ApiError {
+    let is_unique_violation = e
+        .as_database_error()
+        .is_some_and(|db_error| db_error.is_unique_violation());
+
+    if is_unique_violation {
+        if let Err(conflict) = check
+            .await
+            .and_then(|existing_id| reject_taken_name(name, existing_id))
+        {
+            return conflict;
+        }
     }
+
+    ApiError::DatabaseError(e)
 }
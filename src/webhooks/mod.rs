@@ -0,0 +1,131 @@
+use config::Config;
+use serde::Serialize;
+use std::{sync::OnceLock, time::Duration};
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(5);
+const WEBHOOK_ATTEMPTS: u32 = 2;
+
+#[derive(Serialize)]
+pub struct StockChangeEvent {
+    pub item_id: Uuid,
+    pub new_stock: i64,
+    pub movement_id: Uuid,
+}
+
+fn client() -> &'static reqwest::Client {
+    static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+
+    CLIENT.get_or_init(|| {
+        reqwest::Client::builder()
+            .timeout(WEBHOOK_TIMEOUT)
+            .build()
+            .expect("Failed to build webhook client")
+    })
+}
+
+/// Notifies the configured `WEBHOOK_URL` of a stock change.
+///
+/// The request runs in a background task, so it never delays or fails the caller.
+/// A failed delivery is retried once and then only logged.
+pub fn notify_stock_change(event: StockChangeEvent) {
+    let Some(url) = Config::webhook_url() else {
+        return;
+    };
+
+    tokio::spawn(async move {
+        for attempt in 1..=WEBHOOK_ATTEMPTS {
+            let result = client()
+                .post(&url)
+                .json(&event)
+                .send()
+                .await
+                .and_then(|response| response.error_for_status());
+
+            match result {
+                Ok(_) => {
+                    info!(
+                        "Stock change webhook delivered for movement {}",
+                        event.movement_id
+                    );
+                    return;
+                }
+                Err(e) if attempt < WEBHOOK_ATTEMPTS => {
+                    warn!("Stock change webhook failed (attempt {attempt}), retrying: {e}");
+                }
+                Err(e) => {
+                    error!(
+                        "Stock change webhook failed for movement {}: {e}",
+                        event.movement_id
+                    );
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::{app, create_printer, create_toner, json_request, send};
+    use axum::{
+        extract::State,
+        http::{Method, StatusCode},
+        routing::post,
+        Json, Router,
+    };
+    use serde_json::{json, Value};
+    use sqlx::PgPool;
+    use std::{env, time::Duration};
+    use tokio::{net::TcpListener, sync::mpsc};
+
+    #[sqlx::test(migrations = "libs/infra/src/database/migrations")]
+    async fn movements_are_posted_to_the_webhook(pool: PgPool) {
+        let app = app(pool);
+        let (sender, mut received) = mpsc::unbounded_channel::<Value>();
+        let receiver = Router::new()
+            .route(
+                "/",
+                post(
+                    |State(sender): State<mpsc::UnboundedSender<Value>>,
+                     Json(event): Json<Value>| async move {
+                        let _ = sender.send(event);
+                    },
+                ),
+            )
+            .with_state(sender);
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, receiver).await });
+        env::set_var("WEBHOOK_URL", format!("http://{address}/"));
+
+        let printer = create_printer(&app, "ECOSYS M2040").await;
+        let toner = create_toner(&app, "TN-1060", 10).await;
+        let (status, movement) = send(
+            &app,
+            json_request(
+                Method::POST,
+                "/api/v1/movements",
+                json!({ "printer_id": printer, "item_id": toner, "item_type": "toner", "quantity": -2 }),
+            ),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+
+        // Other tests may notify the webhook too, so wait for this movement's event
+        let event = tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                let event = received.recv().await.unwrap();
+                if event["movement_id"] == movement["id"] {
+                    return event;
+                }
+            }
+        })
+        .await;
+        env::set_var("WEBHOOK_URL", "");
+
+        let event = event.expect("The webhook was not called");
+        assert_eq!(event["item_id"], json!(toner));
+        assert_eq!(event["new_stock"], 8);
+    }
+}